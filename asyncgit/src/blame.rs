@@ -0,0 +1,108 @@
+use crate::{
+    error::Result,
+    sync::{self, CommitId, FileBlame},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+/// file (and revision, `None` for the workdir) to blame
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlameParams {
+    ///
+    pub path: String,
+    ///
+    pub commit_id: Option<CommitId>,
+}
+
+/// the blame or why it failed
+type ResultType = std::result::Result<FileBlame, String>;
+struct Request<R, A>(R, A);
+
+///
+pub struct AsyncBlame {
+    current: Arc<Mutex<Option<Request<BlameParams, ResultType>>>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl AsyncBlame {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            current: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    ///
+    pub fn current(
+        &self,
+    ) -> Result<Option<(BlameParams, ResultType)>> {
+        let c = self.current.lock()?;
+
+        if let Some(c) = c.as_ref() {
+            Ok(Some((c.0.clone(), c.1.clone())))
+        } else {
+            Ok(None)
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) > 0
+    }
+
+    /// blames again even if `params` were the last ones (the
+    /// workdir may have changed)
+    pub fn request(&mut self, params: BlameParams) -> Result<()> {
+        if self.is_pending() {
+            return Ok(());
+        }
+
+        log::trace!("request: {:?}", params);
+
+        *self.current.lock()? = None;
+
+        let arc_current = Arc::clone(&self.current);
+        let sender = self.sender.clone();
+        let arc_pending = Arc::clone(&self.pending);
+
+        arc_pending.fetch_add(1, Ordering::Relaxed);
+
+        rayon_core::spawn(move || {
+            Self::blame_helper(params, arc_current)
+                .expect("failed to blame");
+
+            arc_pending.fetch_sub(1, Ordering::Relaxed);
+
+            sender
+                .send(AsyncNotification::Blame)
+                .expect("error sending");
+        });
+
+        Ok(())
+    }
+
+    fn blame_helper(
+        params: BlameParams,
+        arc_current: Arc<
+            Mutex<Option<Request<BlameParams, ResultType>>>,
+        >,
+    ) -> Result<()> {
+        let res =
+            sync::blame_file(CWD, &params.path, params.commit_id)
+                .map_err(|e| e.to_string());
+
+        {
+            let mut last = arc_current.lock()?;
+            *last = Some(Request(params, res));
+        }
+
+        Ok(())
+    }
+}
@@ -6,14 +6,15 @@ use std::sync::{
     atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
-use sync::CommitId;
+use sync::{CommitId, DiffOptions};
 
 type ResultType = Vec<StatusItem>;
 struct Request<R, A>(R, A);
+type Params = (CommitId, DiffOptions);
 
 ///
 pub struct AsyncCommitFiles {
-    current: Arc<Mutex<Option<Request<CommitId, ResultType>>>>,
+    current: Arc<Mutex<Option<Request<Params, ResultType>>>>,
     sender: Sender<AsyncNotification>,
     pending: Arc<AtomicUsize>,
 }
@@ -28,10 +29,10 @@ impl AsyncCommitFiles {
         }
     }
 
-    ///
+    /// last fetched files with the commit and options they are of
     pub fn current(
         &mut self,
-    ) -> Result<Option<(CommitId, ResultType)>> {
+    ) -> Result<Option<(Params, ResultType)>> {
         let c = self.current.lock()?;
 
         if let Some(c) = c.as_ref() {
@@ -47,7 +48,11 @@ impl AsyncCommitFiles {
     }
 
     ///
-    pub fn fetch(&mut self, id: CommitId) -> Result<()> {
+    pub fn fetch(
+        &mut self,
+        id: CommitId,
+        options: DiffOptions,
+    ) -> Result<()> {
         if self.is_pending() {
             return Ok(());
         }
@@ -57,7 +62,7 @@ impl AsyncCommitFiles {
         {
             let current = self.current.lock()?;
             if let Some(ref c) = *current {
-                if c.0 == id {
+                if c.0 == (id, options) {
                     return Ok(());
                 }
             }
@@ -70,7 +75,7 @@ impl AsyncCommitFiles {
         rayon_core::spawn(move || {
             arc_pending.fetch_add(1, Ordering::Relaxed);
 
-            Self::fetch_helper(id, arc_current)
+            Self::fetch_helper((id, options), arc_current)
                 .expect("failed to fetch");
 
             arc_pending.fetch_sub(1, Ordering::Relaxed);
@@ -84,16 +89,15 @@ impl AsyncCommitFiles {
     }
 
     fn fetch_helper(
-        id: CommitId,
-        arc_current: Arc<
-            Mutex<Option<Request<CommitId, ResultType>>>,
-        >,
+        params: Params,
+        arc_current: Arc<Mutex<Option<Request<Params, ResultType>>>>,
     ) -> Result<()> {
-        let res = sync::get_commit_files(CWD, id)?;
+        let res =
+            sync::get_commit_files(CWD, params.0, Some(params.1))?;
 
         {
             let mut last = arc_current.lock()?;
-            *last = Some(Request(id, res));
+            *last = Some(Request(params, res));
         }
 
         Ok(())
@@ -0,0 +1,107 @@
+use crate::{
+    error::Result, sync, AsyncNotification, StatusItem, CWD,
+};
+use crossbeam_channel::Sender;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+use sync::{CommitId, DiffOptions};
+
+type ResultType = Vec<StatusItem>;
+struct Request<R, A>(R, A);
+/// compared commits, `to` being the workdir if `None`
+type Params = (CommitId, Option<CommitId>, DiffOptions);
+
+///
+pub struct AsyncCompareFiles {
+    current: Arc<Mutex<Option<Request<Params, ResultType>>>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl AsyncCompareFiles {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            current: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// last fetched files with the commits and options they are of
+    pub fn current(
+        &mut self,
+    ) -> Result<Option<(Params, ResultType)>> {
+        let c = self.current.lock()?;
+
+        if let Some(c) = c.as_ref() {
+            Ok(Some((c.0, c.1.clone())))
+        } else {
+            Ok(None)
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) > 0
+    }
+
+    /// fetches the files changed between the commits in `params`,
+    /// files already fetched are only fetched again for the workdir
+    pub fn fetch(&mut self, params: Params) -> Result<()> {
+        if self.is_pending() {
+            return Ok(());
+        }
+
+        log::trace!("request: {}", params.0.to_string());
+
+        {
+            let current = self.current.lock()?;
+            if let Some(ref c) = *current {
+                if c.0 == params && params.1.is_some() {
+                    return Ok(());
+                }
+            }
+        }
+
+        let arc_current = Arc::clone(&self.current);
+        let sender = self.sender.clone();
+        let arc_pending = Arc::clone(&self.pending);
+
+        rayon_core::spawn(move || {
+            arc_pending.fetch_add(1, Ordering::Relaxed);
+
+            Self::fetch_helper(params, arc_current)
+                .expect("failed to fetch");
+
+            arc_pending.fetch_sub(1, Ordering::Relaxed);
+
+            sender
+                .send(AsyncNotification::CompareFiles)
+                .expect("error sending");
+        });
+
+        Ok(())
+    }
+
+    fn fetch_helper(
+        params: Params,
+        arc_current: Arc<Mutex<Option<Request<Params, ResultType>>>>,
+    ) -> Result<()> {
+        let res = sync::get_compare_files(
+            CWD,
+            params.0,
+            params.1,
+            Some(params.2),
+        )?;
+
+        {
+            let mut last = arc_current.lock()?;
+            *last = Some(Request(params, res));
+        }
+
+        Ok(())
+    }
+}
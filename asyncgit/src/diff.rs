@@ -9,13 +9,17 @@ use std::{
         Arc, Mutex,
     },
 };
-use sync::CommitId;
+use sync::{CommitId, DiffOptions};
 
 ///
 #[derive(Hash, Clone, PartialEq)]
 pub enum DiffType {
     /// diff in a given commit
     Commit(CommitId),
+    /// diff between two commits
+    CommitToCommit(CommitId, CommitId),
+    /// diff between a commit and the workdir
+    CommitToWorkdir(CommitId),
     /// diff against staged file
     Stage,
     /// diff against file in workdir
@@ -29,6 +33,8 @@ pub struct DiffParams {
     pub path: String,
     /// what kind of diff
     pub diff_type: DiffType,
+    /// how the diff is generated
+    pub options: DiffOptions,
 }
 
 struct Request<R, A>(R, Option<A>);
@@ -139,17 +145,42 @@ impl AsyncDiff {
         hash: u64,
     ) -> Result<bool> {
         let res = match params.diff_type {
-            DiffType::Stage => {
-                sync::diff::get_diff(CWD, params.path.clone(), true)?
-            }
-            DiffType::WorkDir => {
-                sync::diff::get_diff(CWD, params.path.clone(), false)?
-            }
+            DiffType::Stage => sync::diff::get_diff(
+                CWD,
+                params.path.clone(),
+                true,
+                Some(params.options),
+            )?,
+            DiffType::WorkDir => sync::diff::get_diff(
+                CWD,
+                params.path.clone(),
+                false,
+                Some(params.options),
+            )?,
             DiffType::Commit(id) => sync::diff::get_diff_commit(
                 CWD,
                 id,
                 params.path.clone(),
+                Some(params.options),
             )?,
+            DiffType::CommitToCommit(from, to) => {
+                sync::diff::get_diff_commits(
+                    CWD,
+                    from,
+                    Some(to),
+                    params.path.clone(),
+                    Some(params.options),
+                )?
+            }
+            DiffType::CommitToWorkdir(from) => {
+                sync::diff::get_diff_commits(
+                    CWD,
+                    from,
+                    None,
+                    params.path.clone(),
+                    Some(params.options),
+                )?
+            }
         };
 
         let mut notify = false;
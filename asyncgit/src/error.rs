@@ -8,6 +8,15 @@ pub enum Error {
     #[error("git: no head found")]
     NoHead,
 
+    #[error("rejected by remote:\n{}", .0.join("\n"))]
+    PushRejected(Vec<String>),
+
+    #[error("stale info: `{0}` changed on the remote since the last fetch")]
+    StaleLease(String),
+
+    #[error("authentication failed: {0}")]
+    Auth(String),
+
     #[error("io error:{0}")]
     Io(#[from] std::io::Error),
 
@@ -0,0 +1,168 @@
+use crate::{
+    error::{Error, Result},
+    sync::{self, BasicAuthCredential, FetchProgress},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::sync::{Arc, Mutex};
+
+///
+#[derive(Default, Clone, Debug)]
+pub struct FetchRequest {
+    /// name of the remote to fetch
+    pub remote: String,
+    /// tried after ssh-agent, key files and credential helper
+    pub basic_credential: Option<BasicAuthCredential>,
+}
+
+#[derive(Default, Clone, Debug)]
+struct FetchState {
+    progress: Option<FetchProgress>,
+}
+
+///
+pub struct AsyncFetch {
+    state: Arc<Mutex<Option<FetchState>>>,
+    last_result: Arc<Mutex<Option<(String, bool)>>>,
+    sender: Sender<AsyncNotification>,
+}
+
+impl AsyncFetch {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(None)),
+            last_result: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> Result<bool> {
+        let state = self.state.lock()?;
+        Ok(state.is_some())
+    }
+
+    /// error message of the last fetch, `None` if it succeeded
+    pub fn last_result(&self) -> Result<Option<String>> {
+        let res = self.last_result.lock()?;
+        Ok(res.as_ref().map(|(msg, _)| msg.clone()))
+    }
+
+    /// `true` if the last fetch failed because no credentials worked
+    pub fn last_auth_failed(&self) -> Result<bool> {
+        let res = self.last_result.lock()?;
+        Ok(res.as_ref().map_or(false, |(_, auth)| *auth))
+    }
+
+    /// progress of the running fetch
+    pub fn progress(&self) -> Result<Option<FetchProgress>> {
+        let state = self.state.lock()?;
+        Ok(state.as_ref().and_then(|s| s.progress))
+    }
+
+    ///
+    pub fn request(&mut self, params: FetchRequest) -> Result<()> {
+        log::trace!("request");
+
+        self.set_request()?;
+
+        let arc_state = Arc::clone(&self.state);
+        let arc_res = Arc::clone(&self.last_result);
+        let sender = self.sender.clone();
+
+        rayon_core::spawn(move || {
+            let res = sync::fetch_remote(
+                CWD,
+                params.remote.as_str(),
+                params.basic_credential,
+                |p| {
+                    Self::set_progress(&arc_state, &sender, p)
+                        .expect("set progress failed");
+                },
+            );
+
+            Self::set_result(&arc_res, res.map(|_| ()))
+                .expect("result error");
+
+            Self::clear_request(&arc_state).expect("clear error");
+
+            sender
+                .send(AsyncNotification::Fetch)
+                .expect("error sending fetch");
+        });
+
+        Ok(())
+    }
+
+    fn set_request(&self) -> Result<()> {
+        let mut state = self.state.lock()?;
+
+        if state.is_some() {
+            return Err(Error::Generic("pending request".into()));
+        }
+
+        *state = Some(FetchState::default());
+
+        Ok(())
+    }
+
+    fn clear_request(
+        arc_state: &Arc<Mutex<Option<FetchState>>>,
+    ) -> Result<()> {
+        let mut state = arc_state.lock()?;
+
+        *state = None;
+
+        Ok(())
+    }
+
+    /// only notifies when the visible percentage changed
+    /// to not flood the channel
+    fn set_progress(
+        arc_state: &Arc<Mutex<Option<FetchState>>>,
+        sender: &Sender<AsyncNotification>,
+        progress: FetchProgress,
+    ) -> Result<()> {
+        let changed = {
+            let mut state = arc_state.lock()?;
+
+            if let Some(state) = state.as_mut() {
+                let changed = state.progress.map_or(true, |p| {
+                    p.progress_percent()
+                        != progress.progress_percent()
+                });
+                state.progress = Some(progress);
+                changed
+            } else {
+                false
+            }
+        };
+
+        if changed {
+            sender
+                .send(AsyncNotification::Fetch)
+                .expect("error sending fetch");
+        }
+
+        Ok(())
+    }
+
+    fn set_result(
+        arc_result: &Arc<Mutex<Option<(String, bool)>>>,
+        res: Result<()>,
+    ) -> Result<()> {
+        let mut last_res = arc_result.lock()?;
+
+        *last_res = match res {
+            Ok(_) => None,
+            Err(e) => {
+                log::error!("fetch error: {}", e);
+                let auth_failed = matches!(e, Error::Auth(_));
+                Some((e.to_string(), auth_failed))
+            }
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    error::Result,
+    sync::{self, FileHistoryEntry},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+type ResultType = Vec<FileHistoryEntry>;
+struct Request<R, A>(R, A);
+
+///
+pub struct AsyncFileHistory {
+    current: Arc<Mutex<Option<Request<String, ResultType>>>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl AsyncFileHistory {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            current: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    ///
+    pub fn current(&self) -> Result<Option<(String, ResultType)>> {
+        let c = self.current.lock()?;
+
+        if let Some(c) = c.as_ref() {
+            Ok(Some((c.0.clone(), c.1.clone())))
+        } else {
+            Ok(None)
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) > 0
+    }
+
+    /// walks the history of `path`, `force` fetches it again even if
+    /// it was the last one.
+    /// ignored while a walk is pending, request again once it is done
+    pub fn fetch(&mut self, path: &str, force: bool) -> Result<()> {
+        if self.is_pending() {
+            return Ok(());
+        }
+
+        log::trace!("request: {}", path);
+
+        {
+            let mut current = self.current.lock()?;
+            if let Some(ref c) = *current {
+                if c.0 == path && !force {
+                    return Ok(());
+                }
+            }
+            *current = None;
+        }
+
+        let path = String::from(path);
+        let arc_current = Arc::clone(&self.current);
+        let sender = self.sender.clone();
+        let arc_pending = Arc::clone(&self.pending);
+
+        arc_pending.fetch_add(1, Ordering::Relaxed);
+
+        rayon_core::spawn(move || {
+            Self::fetch_helper(path, arc_current)
+                .expect("failed to fetch");
+
+            arc_pending.fetch_sub(1, Ordering::Relaxed);
+
+            sender
+                .send(AsyncNotification::FileHistory)
+                .expect("error sending");
+        });
+
+        Ok(())
+    }
+
+    fn fetch_helper(
+        path: String,
+        arc_current: Arc<Mutex<Option<Request<String, ResultType>>>>,
+    ) -> Result<()> {
+        // e.g. no commit yet
+        let res =
+            sync::get_file_history(CWD, &path).unwrap_or_else(|e| {
+                log::error!("file history error: {}", e);
+                Vec::new()
+            });
+
+        {
+            let mut last = arc_current.lock()?;
+            *last = Some(Request(path, res));
+        }
+
+        Ok(())
+    }
+}
@@ -6,20 +6,32 @@
 #![deny(clippy::result_unwrap_used)]
 #![deny(clippy::panic)]
 
+mod blame;
 mod commit_files;
+mod compare_files;
 mod diff;
 mod error;
+mod fetch;
+mod file_history;
+mod log_filter;
+mod push;
 mod revlog;
 mod status;
 pub mod sync;
 
 pub use crate::{
+    blame::{AsyncBlame, BlameParams},
     commit_files::AsyncCommitFiles,
+    compare_files::AsyncCompareFiles,
     diff::{AsyncDiff, DiffParams, DiffType},
+    fetch::{AsyncFetch, FetchRequest},
+    file_history::AsyncFileHistory,
+    log_filter::AsyncLogFilter,
+    push::{AsyncPush, PushRequest},
     revlog::{AsyncLog, FetchStatus},
     status::{AsyncStatus, StatusParams},
     sync::{
-        diff::{DiffLine, DiffLineType, FileDiff},
+        diff::{DiffLine, DiffLinePosition, DiffLineType, FileDiff},
         status::{StatusItem, StatusItemType},
     },
 };
@@ -39,6 +51,18 @@ pub enum AsyncNotification {
     Log,
     ///
     CommitFiles,
+    ///
+    CompareFiles,
+    ///
+    Fetch,
+    ///
+    Push,
+    ///
+    LogFilter,
+    ///
+    FileHistory,
+    ///
+    Blame,
 }
 
 /// current working director `./`
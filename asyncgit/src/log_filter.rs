@@ -0,0 +1,163 @@
+use crate::{
+    error::Result,
+    sync::{utils::repo, LogFilter},
+    AsyncLog, AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use git2::Oid;
+use scopetime::scope_time;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+static BATCH_SIZE: usize = 1000;
+static SLEEP_WAITING: Duration = Duration::from_millis(10);
+
+/// searches the commits an `AsyncLog` collects (while it is still
+/// walking) in the background
+pub struct AsyncLogFilter {
+    matches: Arc<Mutex<Vec<Oid>>>,
+    pending: Arc<AtomicBool>,
+    abort: Arc<AtomicBool>,
+    sender: Sender<AsyncNotification>,
+}
+
+impl AsyncLogFilter {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            matches: Arc::new(Mutex::new(Vec::new())),
+            pending: Arc::new(AtomicBool::new(false)),
+            abort: Arc::new(AtomicBool::new(false)),
+            sender: sender.clone(),
+        }
+    }
+
+    /// number of matches found so far
+    pub fn count(&self) -> Result<usize> {
+        Ok(self.matches.lock()?.len())
+    }
+
+    ///
+    pub fn get_slice(
+        &self,
+        start_index: usize,
+        amount: usize,
+    ) -> Result<Vec<Oid>> {
+        let list = self.matches.lock()?;
+        let list_len = list.len();
+        let min = start_index.min(list_len);
+        let max = min + amount;
+        let max = max.min(list_len);
+        Ok(list[min..max].to_vec())
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// aborts a running search and starts searching `log` for `filter`
+    pub fn start(&mut self, log: &AsyncLog, filter: LogFilter) {
+        self.stop();
+
+        // fresh state so an aborted job can not mix in old results
+        self.matches = Arc::new(Mutex::new(Vec::new()));
+        self.pending = Arc::new(AtomicBool::new(true));
+        self.abort = Arc::new(AtomicBool::new(false));
+
+        let (log_current, log_pending) = log.shared();
+        let arc_matches = Arc::clone(&self.matches);
+        let arc_pending = Arc::clone(&self.pending);
+        let arc_abort = Arc::clone(&self.abort);
+        let sender = self.sender.clone();
+
+        rayon_core::spawn(move || {
+            scope_time!("async::log_filter");
+
+            if let Err(e) = Self::filter_helper(
+                CWD,
+                &filter,
+                &log_current,
+                &log_pending,
+                &arc_matches,
+                &arc_abort,
+                &sender,
+            ) {
+                log::error!("log filter error: {}", e);
+            }
+
+            arc_pending.store(false, Ordering::Relaxed);
+            Self::notify(&sender);
+        });
+    }
+
+    /// aborts a running search and drops the results
+    pub fn stop(&mut self) {
+        self.abort.store(true, Ordering::Relaxed);
+        self.pending.store(false, Ordering::Relaxed);
+        if let Ok(mut matches) = self.matches.lock() {
+            matches.clear();
+        }
+    }
+
+    fn filter_helper(
+        repo_path: &str,
+        filter: &LogFilter,
+        log_current: &Arc<Mutex<Vec<Oid>>>,
+        log_pending: &Arc<AtomicBool>,
+        arc_matches: &Arc<Mutex<Vec<Oid>>>,
+        arc_abort: &Arc<AtomicBool>,
+        sender: &Sender<AsyncNotification>,
+    ) -> Result<()> {
+        let r = repo(repo_path)?;
+        let mut idx = 0;
+
+        while !arc_abort.load(Ordering::Relaxed) {
+            let batch = {
+                let list = log_current.lock()?;
+                let min = idx.min(list.len());
+                let max = (min + BATCH_SIZE).min(list.len());
+                list[min..max].to_vec()
+            };
+
+            if batch.is_empty() {
+                if log_pending.load(Ordering::Relaxed) {
+                    thread::sleep(SLEEP_WAITING);
+                    continue;
+                }
+                break;
+            }
+
+            idx += batch.len();
+
+            let found = batch
+                .into_iter()
+                .filter(|id| {
+                    r.find_commit(*id)
+                        .map(|c| filter.is_match(&c))
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<_>>();
+
+            if !found.is_empty() && !arc_abort.load(Ordering::Relaxed)
+            {
+                arc_matches.lock()?.extend(found);
+                Self::notify(sender);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn notify(sender: &Sender<AsyncNotification>) {
+        sender
+            .send(AsyncNotification::LogFilter)
+            .expect("error sending");
+    }
+}
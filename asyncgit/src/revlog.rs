@@ -1,6 +1,9 @@
 use crate::{
     error::Result,
-    sync::{utils::repo, LogWalker},
+    sync::{
+        resolve_walk_spec, utils::repo, CommitGraph, GraphRow,
+        LogWalkSpec, LogWalker,
+    },
     AsyncNotification, CWD,
 };
 use crossbeam_channel::Sender;
@@ -30,11 +33,20 @@ pub enum FetchStatus {
 ///
 pub struct AsyncLog {
     current: Arc<Mutex<Vec<Oid>>>,
+    graph: Arc<Mutex<Vec<GraphRow>>>,
     sender: Sender<AsyncNotification>,
     pending: Arc<AtomicBool>,
     background: Arc<AtomicBool>,
+    last_walk: Option<Walk>,
+    /// `HEAD` when `last_walk` was resolved
+    last_head: Option<Oid>,
+    /// refs may have moved since `last_walk` was resolved
+    refs_changed: bool,
 }
 
+/// spec and the commits it resolved to (pushed, hidden)
+type Walk = (LogWalkSpec, (Vec<Oid>, Vec<Oid>));
+
 static LIMIT_COUNT: usize = 3000;
 static SLEEP_FOREGROUND: Duration = Duration::from_millis(2);
 static SLEEP_BACKGROUND: Duration = Duration::from_millis(1000);
@@ -44,9 +56,13 @@ impl AsyncLog {
     pub fn new(sender: &Sender<AsyncNotification>) -> Self {
         Self {
             current: Arc::new(Mutex::new(Vec::new())),
+            graph: Arc::new(Mutex::new(Vec::new())),
             sender: sender.clone(),
             pending: Arc::new(AtomicBool::new(false)),
             background: Arc::new(AtomicBool::new(false)),
+            last_walk: None,
+            last_head: None,
+            refs_changed: false,
         }
     }
 
@@ -69,56 +85,95 @@ impl AsyncLog {
         Ok(Vec::from_iter(list[min..max].iter().cloned()))
     }
 
+    /// graph rows of the commits `get_slice` returns for this range
+    pub fn get_graph_slice(
+        &self,
+        start_index: usize,
+        amount: usize,
+    ) -> Result<Vec<GraphRow>> {
+        let list = self.graph.lock()?;
+        let list_len = list.len();
+        let min = start_index.min(list_len);
+        let max = min + amount;
+        let max = max.min(list_len);
+        Ok(list[min..max].to_vec())
+    }
+
     ///
     pub fn is_pending(&self) -> bool {
         self.pending.load(Ordering::Relaxed)
     }
 
-    ///
-    pub fn set_background(&mut self) {
-        self.background.store(true, Ordering::Relaxed)
+    /// commits found so far and whether the walk is still running
+    pub(crate) fn shared(
+        &self,
+    ) -> (Arc<Mutex<Vec<Oid>>>, Arc<AtomicBool>) {
+        (Arc::clone(&self.current), Arc::clone(&self.pending))
     }
 
     ///
-    fn current_head(&self) -> Result<Oid> {
-        Ok(self.current.lock()?.first().map_or(Oid::zero(), |f| *f))
+    pub fn set_background(&mut self) {
+        self.background.store(true, Ordering::Relaxed)
     }
 
-    ///
-    fn head_changed(&self) -> Result<bool> {
-        if let Ok(head) = repo(CWD)?.head() {
-            if let Some(head) = head.target() {
-                return Ok(head != self.current_head()?);
-            }
-        }
-        Ok(false)
+    /// resolve the spec again on the next fetch, branches or tags
+    /// it refers to may have moved
+    pub fn invalidate_refs(&mut self) {
+        self.refs_changed = true;
     }
 
-    ///
-    pub fn fetch(&mut self) -> Result<FetchStatus> {
+    /// (re)starts the walk if `spec` or the commits it resolves to
+    /// changed since the last fetch.
+    /// the spec is only resolved again if it, `HEAD` or the refs
+    /// (see `invalidate_refs`) changed
+    pub fn fetch(
+        &mut self,
+        spec: &LogWalkSpec,
+    ) -> Result<FetchStatus> {
         self.background.store(false, Ordering::Relaxed);
 
         if self.is_pending() {
             return Ok(FetchStatus::Pending);
         }
 
-        if !self.head_changed()? {
+        let repo = repo(CWD)?;
+        let head = repo.head().ok().and_then(|h| h.target());
+
+        let same_spec = matches!(&self.last_walk, Some((last, _)) if last == spec);
+        if same_spec && !self.refs_changed && self.last_head == head {
             return Ok(FetchStatus::NoChange);
         }
 
+        let walk = (spec.clone(), resolve_walk_spec(&repo, spec)?);
+        self.last_head = head;
+        self.refs_changed = false;
+
+        if self.last_walk.as_ref() == Some(&walk) {
+            return Ok(FetchStatus::NoChange);
+        }
+
+        self.last_walk = Some(walk);
         self.clear()?;
 
+        let spec = spec.clone();
+
         let arc_current = Arc::clone(&self.current);
+        let arc_graph = Arc::clone(&self.graph);
         let sender = self.sender.clone();
         let arc_pending = Arc::clone(&self.pending);
         let arc_background = Arc::clone(&self.background);
 
+        // set before spawning so a search started right after this
+        // waits for the walk
+        self.pending.store(true, Ordering::Relaxed);
+
         rayon_core::spawn(move || {
             scope_time!("async::revlog");
 
-            arc_pending.store(true, Ordering::Relaxed);
             AsyncLog::fetch_helper(
+                spec,
                 arc_current,
+                arc_graph,
                 arc_background,
                 &sender,
             )
@@ -131,21 +186,35 @@ impl AsyncLog {
     }
 
     fn fetch_helper(
+        spec: LogWalkSpec,
         arc_current: Arc<Mutex<Vec<Oid>>>,
+        arc_graph: Arc<Mutex<Vec<GraphRow>>>,
         arc_background: Arc<AtomicBool>,
         sender: &Sender<AsyncNotification>,
     ) -> Result<()> {
         let mut entries = Vec::with_capacity(LIMIT_COUNT);
+        let mut rows = Vec::with_capacity(LIMIT_COUNT);
         let r = repo(CWD)?;
-        let mut walker = LogWalker::new(&r);
+        let mut walker = LogWalker::with_spec(&r, spec);
+        let mut graph = CommitGraph::new();
         loop {
             entries.clear();
             let res_is_err =
                 walker.read(&mut entries, LIMIT_COUNT).is_err();
 
             if !res_is_err {
+                for id in &entries {
+                    let parents = r
+                        .find_commit(*id)
+                        .map(|c| c.parent_ids().collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    rows.push(graph.add(*id, &parents));
+                }
+
                 let mut current = arc_current.lock()?;
+                let mut graph_rows = arc_graph.lock()?;
                 current.extend(entries.iter());
+                graph_rows.extend(rows.drain(..));
             }
 
             if res_is_err || entries.len() <= 1 {
@@ -168,6 +237,7 @@ impl AsyncLog {
 
     fn clear(&mut self) -> Result<()> {
         self.current.lock()?.clear();
+        self.graph.lock()?.clear();
         Ok(())
     }
 
@@ -0,0 +1,229 @@
+//! blame of a file in a commit or the workdir
+
+use super::{
+    utils::{repo, work_dir},
+    CommitId,
+};
+use crate::error::{Error, Result};
+use git2::{BlameOptions, DiffOptions, Oid, Patch, Repository};
+use scopetime::scope_time;
+use std::{fs, path::Path};
+
+/// the commit that last changed a range of lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlameHunk {
+    ///
+    pub commit_id: CommitId,
+    ///
+    pub author: String,
+    /// commit time (seconds since epoch)
+    pub time: i64,
+}
+
+/// every line of a file with the hunk that last changed it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileBlame {
+    ///
+    pub path: String,
+    /// blamed revision, `None` for the workdir
+    pub commit_id: Option<CommitId>,
+    ///
+    pub hunks: Vec<BlameHunk>,
+    /// content of each line and the index of its hunk (`None` if the
+    /// line is not committed yet)
+    pub lines: Vec<(Option<usize>, String)>,
+}
+
+/// blames `path` as of `commit_id` or, if `None`, as in the workdir
+/// (lines changed since `HEAD` have no hunk)
+pub fn blame_file(
+    repo_path: &str,
+    path: &str,
+    commit_id: Option<CommitId>,
+) -> Result<FileBlame> {
+    scope_time!("blame_file");
+
+    let repo = repo(repo_path)?;
+
+    let mut opts = BlameOptions::new();
+    let committed = match commit_id {
+        Some(id) => {
+            opts.newest_commit(id.into());
+            Some(blob_content(&repo, id.into(), path)?)
+        }
+        // not committed yet if missing in `HEAD`
+        None => repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .ok()
+            .and_then(|head| {
+                blob_content(&repo, head.id(), path).ok()
+            }),
+    };
+    let content = match (commit_id, &committed) {
+        (Some(_), Some(committed)) => committed.clone(),
+        _ => fs::read(work_dir(&repo).join(path))?,
+    };
+
+    let blame = match committed {
+        Some(_) => {
+            Some(repo.blame_file(Path::new(path), Some(&mut opts))?)
+        }
+        None => None,
+    };
+
+    let mut hunks: Vec<BlameHunk> = Vec::new();
+    let mut hunk_of_line = |line: usize| -> Option<usize> {
+        // git2 line numbers are 1-based
+        let hunk = blame.as_ref()?.get_line(line + 1)?;
+        let id = CommitId::new(hunk.final_commit_id());
+
+        if let Some(idx) =
+            hunks.iter().position(|h| h.commit_id == id)
+        {
+            return Some(idx);
+        }
+
+        let sig = hunk.final_signature();
+        hunks.push(BlameHunk {
+            commit_id: id,
+            author: String::from_utf8_lossy(sig.name_bytes())
+                .to_string(),
+            time: sig.when().seconds(),
+        });
+        Some(hunks.len() - 1)
+    };
+
+    let lines = String::from_utf8_lossy(&content)
+        .lines()
+        .map(String::from)
+        .zip(map_lines(committed.as_deref(), &content)?)
+        .map(|(line, committed_line)| {
+            (committed_line.and_then(&mut hunk_of_line), line)
+        })
+        .collect();
+
+    Ok(FileBlame {
+        path: String::from(path),
+        commit_id,
+        hunks,
+        lines,
+    })
+}
+
+fn blob_content(
+    repo: &Repository,
+    commit: Oid,
+    path: &str,
+) -> Result<Vec<u8>> {
+    let tree = repo.find_commit(commit)?.tree()?;
+    let entry = tree.get_path(Path::new(path))?;
+    let blob = repo.find_blob(entry.id()).map_err(|_| {
+        Error::Generic(format!("'{}' is not a file", path))
+    })?;
+
+    Ok(blob.content().to_vec())
+}
+
+/// line (0-based) in `old` each line of `new` comes from, if any
+fn map_lines(
+    old: Option<&[u8]>,
+    new: &[u8],
+) -> Result<Vec<Option<usize>>> {
+    let line_count = String::from_utf8_lossy(new).lines().count();
+
+    let old = match old {
+        Some(old) => old,
+        None => return Ok(vec![None; line_count]),
+    };
+
+    if old == new {
+        return Ok((0..line_count).map(Some).collect());
+    }
+
+    // all lines as context, so every line of `new` shows up
+    let mut opts = DiffOptions::new();
+    opts.context_lines(u32::MAX);
+
+    let patch =
+        Patch::from_buffers(old, None, new, None, Some(&mut opts))?;
+
+    let mut res = vec![None; line_count];
+    for hunk in 0..patch.num_hunks() {
+        for line in 0..patch.num_lines_in_hunk(hunk)? {
+            let line = patch.line_in_hunk(hunk, line)?;
+            if let (Some(old), Some(new)) =
+                (line.old_lineno(), line.new_lineno())
+            {
+                if let Some(entry) = res.get_mut(new as usize - 1) {
+                    *entry = Some(old as usize - 1);
+                }
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{commit, stage_add_file, tests::repo_init};
+    use std::{fs::File, io::Write};
+
+    fn write(root: &Path, content: &str) {
+        File::create(&root.join("foo"))
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_blame() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        write(root, "a\nb\n");
+        stage_add_file(repo_path, Path::new("foo")).unwrap();
+        let c1 = CommitId::new(commit(repo_path, "c1").unwrap());
+
+        write(root, "a\nB\nc\n");
+        stage_add_file(repo_path, Path::new("foo")).unwrap();
+        let c2 = CommitId::new(commit(repo_path, "c2").unwrap());
+
+        let hunk_ids = |blame: &FileBlame| {
+            blame
+                .lines
+                .iter()
+                .map(|(h, _)| h.map(|h| blame.hunks[h].commit_id))
+                .collect::<Vec<_>>()
+        };
+
+        let blame = blame_file(repo_path, "foo", Some(c1)).unwrap();
+        assert_eq!(hunk_ids(&blame), vec![Some(c1), Some(c1)]);
+
+        let blame = blame_file(repo_path, "foo", Some(c2)).unwrap();
+        assert_eq!(
+            hunk_ids(&blame),
+            vec![Some(c1), Some(c2), Some(c2)]
+        );
+        assert_eq!(blame.hunks[0].author, "name");
+
+        // uncommitted lines have no hunk
+        write(root, "new\na\nB\nc\n");
+        let blame = blame_file(repo_path, "foo", None).unwrap();
+        assert_eq!(
+            hunk_ids(&blame),
+            vec![None, Some(c1), Some(c2), Some(c2)]
+        );
+        assert_eq!(blame.lines[0].1, "new");
+
+        File::create(&root.join("bar"))
+            .unwrap()
+            .write_all(b"x\n")
+            .unwrap();
+        let blame = blame_file(repo_path, "bar", None).unwrap();
+        assert_eq!(blame.lines, vec![(None, String::from("x"))]);
+    }
+}
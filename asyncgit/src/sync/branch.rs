@@ -1,8 +1,10 @@
 //!
 
-use crate::{
-    error::{Error, Result},
-    sync::utils,
+use super::{commits_info::get_message, utils, CommitId};
+use crate::error::{Error, Result};
+use git2::{
+    build::CheckoutBuilder, BranchType, ErrorCode, ObjectType,
+    Repository,
 };
 use scopetime::scope_time;
 
@@ -26,10 +28,248 @@ pub fn get_branch_name(repo_path: &str) -> Result<String> {
     Err(Error::NoHead)
 }
 
+/// returns the full reference name of the branch HEAD is pointing to
+/// (e.g. `refs/heads/master`)
+pub fn get_head_ref(repo_path: &str) -> Result<String> {
+    scope_time!("get_head_ref");
+
+    let repo = utils::repo(repo_path)?;
+
+    let head = match repo.head() {
+        Ok(head) if head.is_branch() => head,
+        _ => return Err(Error::NoHead),
+    };
+
+    Ok(bytes2string(head.name_bytes()))
+}
+
+/// information about a local branch
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchInfo {
+    /// short name (e.g. `master`)
+    pub name: String,
+    /// full reference name (e.g. `refs/heads/master`)
+    pub reference: String,
+    /// first line of the message of the branch tip
+    pub top_commit_message: String,
+    /// commit the branch points to
+    pub top_commit: CommitId,
+    /// is this the branch HEAD is pointing to
+    pub is_head: bool,
+    /// short name of the upstream branch if one is configured
+    pub upstream: Option<String>,
+}
+
+/// returns all local branches sorted by name
+pub fn get_branches_info(repo_path: &str) -> Result<Vec<BranchInfo>> {
+    scope_time!("get_branches_info");
+
+    let repo = utils::repo(repo_path)?;
+
+    let mut res = Vec::new();
+
+    for b in repo.branches(Some(BranchType::Local))? {
+        let branch = b?.0;
+
+        let top_commit = branch.get().peel_to_commit()?;
+
+        let name = bytes2string(branch.name_bytes()?);
+        let reference = bytes2string(branch.get().name_bytes());
+
+        let upstream = branch
+            .upstream()
+            .ok()
+            .and_then(|u| u.name_bytes().ok().map(bytes2string));
+
+        res.push(BranchInfo {
+            name,
+            reference,
+            top_commit_message: get_message(&top_commit, Some(80)),
+            top_commit: CommitId::new(top_commit.id()),
+            is_head: branch.is_head(),
+            upstream,
+        });
+    }
+
+    res.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(res)
+}
+
+/// creates a new local branch called `name` pointing to `target`
+/// (or to HEAD if `None`), does not check it out
+pub fn create_branch(
+    repo_path: &str,
+    name: &str,
+    target: Option<CommitId>,
+) -> Result<String> {
+    scope_time!("create_branch");
+
+    let repo = utils::repo(repo_path)?;
+
+    let target = match target {
+        Some(id) => id,
+        None => utils::get_head_repo(&repo)?,
+    };
+
+    let commit = repo.find_commit(target.into())?;
+
+    let branch = repo.branch(name, &commit, false)?;
+
+    Ok(bytes2string(branch.get().name_bytes()))
+}
+
+/// checks out the branch with the full reference name `branch_ref`.
+/// this uses a safe checkout: local changes that would be overwritten
+/// by the switch make the checkout fail and leave everything untouched
+pub fn checkout_branch(
+    repo_path: &str,
+    branch_ref: &str,
+) -> Result<()> {
+    scope_time!("checkout_branch");
+
+    let repo = utils::repo(repo_path)?;
+
+    let target =
+        repo.find_reference(branch_ref)?.peel(ObjectType::Commit)?;
+
+    let mut opts = CheckoutBuilder::new();
+    opts.safe();
+
+    repo.checkout_tree(&target, Some(&mut opts))?;
+    repo.set_head(branch_ref)?;
+
+    Ok(())
+}
+
+/// renames the local branch `branch_ref` to `new_name`
+pub fn rename_branch(
+    repo_path: &str,
+    branch_ref: &str,
+    new_name: &str,
+) -> Result<()> {
+    scope_time!("rename_branch");
+
+    let repo = utils::repo(repo_path)?;
+
+    let mut branch =
+        git2::Branch::wrap(repo.find_reference(branch_ref)?);
+
+    branch.rename(new_name, false)?;
+
+    Ok(())
+}
+
+/// deletes the local branch `branch_ref`, fails for the branch HEAD is on
+pub fn delete_branch(
+    repo_path: &str,
+    branch_ref: &str,
+) -> Result<()> {
+    scope_time!("delete_branch");
+
+    let repo = utils::repo(repo_path)?;
+
+    let mut branch =
+        git2::Branch::wrap(repo.find_reference(branch_ref)?);
+
+    if branch.is_head() {
+        return Err(Error::Generic(
+            "cannot delete the checked out branch".to_string(),
+        ));
+    }
+
+    branch.delete()?;
+
+    Ok(())
+}
+
+/// upstream of a branch and how far the branch diverged from it
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpstreamInfo {
+    /// short name of the upstream (e.g. `origin/master`)
+    pub name: String,
+    /// commits on the branch that are not on the upstream
+    pub ahead: usize,
+    /// commits on the upstream that are not on the branch
+    pub behind: usize,
+}
+
+/// returns `UpstreamInfo` of the branch HEAD is pointing to,
+/// `None` if HEAD is detached or no upstream is configured
+pub fn get_head_upstream(
+    repo_path: &str,
+) -> Result<Option<UpstreamInfo>> {
+    scope_time!("get_head_upstream");
+
+    let repo = utils::repo(repo_path)?;
+
+    let head = match repo.head() {
+        Ok(head) if head.is_branch() => head,
+        _ => return Ok(None),
+    };
+
+    let branch_ref = bytes2string(head.name_bytes());
+
+    get_upstream_info_repo(&repo, branch_ref.as_str())
+}
+
+/// returns `UpstreamInfo` of the local branch `branch_ref`,
+/// `None` if no upstream is configured (`branch.<name>.remote/merge`)
+pub fn get_branch_upstream(
+    repo_path: &str,
+    branch_ref: &str,
+) -> Result<Option<UpstreamInfo>> {
+    scope_time!("get_branch_upstream");
+
+    let repo = utils::repo(repo_path)?;
+
+    get_upstream_info_repo(&repo, branch_ref)
+}
+
+fn get_upstream_info_repo(
+    repo: &Repository,
+    branch_ref: &str,
+) -> Result<Option<UpstreamInfo>> {
+    let upstream_ref = match repo.branch_upstream_name(branch_ref) {
+        Ok(name) => bytes2string(&name),
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let upstream = match repo.find_reference(upstream_ref.as_str()) {
+        Ok(upstream) => upstream,
+        // configured but never fetched
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let local = repo.find_reference(branch_ref)?.peel_to_commit()?;
+    let upstream_commit = upstream.peel_to_commit()?;
+
+    let (ahead, behind) =
+        repo.graph_ahead_behind(local.id(), upstream_commit.id())?;
+
+    Ok(Some(UpstreamInfo {
+        name: bytes2string(upstream.shorthand_bytes()),
+        ahead,
+        behind,
+    }))
+}
+
+fn bytes2string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sync::tests::{repo_init, repo_init_empty};
+    use crate::sync::{
+        commit, stage_add_file,
+        tests::{repo_init, repo_init_bare, repo_init_empty},
+        utils::get_head,
+    };
+    use git2::ResetType;
+    use std::{fs::File, io::Write, path::Path};
 
     #[test]
     fn test_smoke() {
@@ -43,6 +283,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_head_ref() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert_eq!(
+            get_head_ref(repo_path).unwrap(),
+            "refs/heads/master"
+        );
+
+        let head = repo.head().unwrap().target().unwrap();
+        repo.set_head_detached(head).unwrap();
+
+        assert_eq!(get_head_ref(repo_path).is_err(), true);
+    }
+
     #[test]
     fn test_empty_repo() {
         let (_td, repo) = repo_init_empty().unwrap();
@@ -54,4 +311,186 @@ mod tests {
             Err(Error::NoHead)
         ));
     }
+
+    #[test]
+    fn test_branches_info() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        create_branch(repo_path, "foo", None).unwrap();
+
+        let branches = get_branches_info(repo_path).unwrap();
+
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches[0].name, "foo");
+        assert_eq!(branches[0].reference, "refs/heads/foo");
+        assert_eq!(branches[0].is_head, false);
+        assert_eq!(branches[0].top_commit_message, "initial");
+        assert_eq!(branches[0].upstream, None);
+        assert_eq!(branches[1].name, "master");
+        assert_eq!(branches[1].is_head, true);
+        assert_eq!(branches[0].top_commit, branches[1].top_commit);
+    }
+
+    #[test]
+    fn test_create_from_commit() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let first = get_head(repo_path).unwrap();
+
+        File::create(&root.join("foo.txt"))
+            .unwrap()
+            .write_all(b"foo")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+        commit(repo_path, "second").unwrap();
+
+        create_branch(repo_path, "old", Some(first)).unwrap();
+
+        let branches = get_branches_info(repo_path).unwrap();
+        let old = branches.iter().find(|b| b.name == "old").unwrap();
+
+        assert_eq!(old.top_commit, first);
+    }
+
+    #[test]
+    fn test_checkout() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let branch_ref =
+            create_branch(repo_path, "foo", None).unwrap();
+
+        checkout_branch(repo_path, branch_ref.as_str()).unwrap();
+
+        assert_eq!(get_branch_name(repo_path).unwrap(), "foo");
+    }
+
+    #[test]
+    fn test_checkout_refuses_conflicting_changes() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let file = Path::new("foo.txt");
+
+        File::create(&root.join(file))
+            .unwrap()
+            .write_all(b"a")
+            .unwrap();
+        stage_add_file(repo_path, file).unwrap();
+        commit(repo_path, "a").unwrap();
+
+        create_branch(repo_path, "other", None).unwrap();
+
+        File::create(&root.join(file))
+            .unwrap()
+            .write_all(b"b")
+            .unwrap();
+        stage_add_file(repo_path, file).unwrap();
+        commit(repo_path, "b").unwrap();
+
+        // local change to a file that differs between the branches
+        File::create(&root.join(file))
+            .unwrap()
+            .write_all(b"c")
+            .unwrap();
+
+        assert!(
+            checkout_branch(repo_path, "refs/heads/other").is_err()
+        );
+        assert_eq!(get_branch_name(repo_path).unwrap(), "master");
+    }
+
+    #[test]
+    fn test_rename_and_delete() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let branch_ref =
+            create_branch(repo_path, "foo", None).unwrap();
+
+        rename_branch(repo_path, branch_ref.as_str(), "bar").unwrap();
+
+        let branches = get_branches_info(repo_path).unwrap();
+        assert_eq!(branches[0].name, "bar");
+
+        assert!(
+            delete_branch(repo_path, "refs/heads/master").is_err()
+        );
+
+        delete_branch(repo_path, "refs/heads/bar").unwrap();
+
+        assert_eq!(get_branches_info(repo_path).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_upstream_ahead_behind() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let (r_td, _remote) = repo_init_bare().unwrap();
+        let remote_path = r_td.path().to_str().unwrap();
+
+        assert_eq!(get_head_upstream(repo_path).unwrap(), None);
+
+        let mut remote = repo.remote("origin", remote_path).unwrap();
+        remote
+            .push(&["refs/heads/master:refs/heads/master"], None)
+            .unwrap();
+        remote
+            .fetch(
+                &["refs/heads/*:refs/remotes/origin/*"],
+                None,
+                None,
+            )
+            .unwrap();
+
+        repo.find_branch("master", BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("origin/master"))
+            .unwrap();
+
+        let info = get_head_upstream(repo_path).unwrap().unwrap();
+        assert_eq!(info.name, "origin/master");
+        assert_eq!((info.ahead, info.behind), (0, 0));
+
+        File::create(&root.join("foo.txt"))
+            .unwrap()
+            .write_all(b"foo")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+        commit(repo_path, "ahead").unwrap();
+
+        let info = get_head_upstream(repo_path).unwrap().unwrap();
+        assert_eq!((info.ahead, info.behind), (1, 0));
+
+        // publish the commit and move the local branch back
+        remote
+            .push(&["refs/heads/master:refs/heads/master"], None)
+            .unwrap();
+        remote
+            .fetch(
+                &["refs/heads/*:refs/remotes/origin/*"],
+                None,
+                None,
+            )
+            .unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let parent = head.parent(0).unwrap();
+        repo.reset(parent.as_object(), ResetType::Hard, None)
+            .unwrap();
+
+        let info =
+            get_branch_upstream(repo_path, "refs/heads/master")
+                .unwrap()
+                .unwrap();
+        assert_eq!((info.ahead, info.behind), (0, 1));
+    }
 }
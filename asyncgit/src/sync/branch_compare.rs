@@ -0,0 +1,122 @@
+//! what the current branch would bring into another one
+
+use super::{
+    logwalker::{LogWalkSpec, LogWalker},
+    utils::repo,
+    CommitId,
+};
+use crate::error::Result;
+use scopetime::scope_time;
+
+/// commits read from the walk at once
+const READ_CHUNK: usize = 1000;
+
+/// `HEAD` compared against a target branch
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchCompare {
+    /// commits on `HEAD` not on the target, newest first
+    pub commits: Vec<CommitId>,
+    /// common ancestor of `HEAD` and the target the changes are
+    /// diffed against
+    pub merge_base: CommitId,
+    /// commit `HEAD` points to
+    pub head: CommitId,
+}
+
+/// commits on `HEAD` that are not on `target` (a revision like
+/// `refs/heads/master`) and the merge base to diff `HEAD` against
+pub fn compare_branch(
+    repo_path: &str,
+    target: &str,
+) -> Result<BranchCompare> {
+    scope_time!("compare_branch");
+
+    let repo = repo(repo_path)?;
+
+    let head = repo.head()?.peel_to_commit()?.id();
+    let target_id =
+        repo.revparse_single(target)?.peel_to_commit()?.id();
+    let merge_base = repo.merge_base(head, target_id)?;
+
+    let mut walker = LogWalker::with_spec(
+        &repo,
+        LogWalkSpec::Range {
+            hide: target.to_string(),
+            push: String::from("HEAD"),
+        },
+    );
+
+    let mut ids = Vec::new();
+    while walker.read(&mut ids, READ_CHUNK)? > 0 {}
+
+    Ok(BranchCompare {
+        commits: ids.into_iter().map(CommitId::new).collect(),
+        merge_base: CommitId::new(merge_base),
+        head: CommitId::new(head),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compare_branch;
+    use crate::{
+        error::Result,
+        sync::{
+            checkout_branch, commit, create_branch,
+            get_compare_files, stage_add_file,
+            tests::repo_init_empty, CommitId,
+        },
+    };
+    use std::{fs::File, io::Write, path::Path};
+
+    #[test]
+    fn test_compare_branch() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let write = |name: &str, content: &[u8]| -> Result<()> {
+            File::create(&root.join(name))?.write_all(content)?;
+            stage_add_file(repo_path, Path::new(name))?;
+            Ok(())
+        };
+
+        write("foo", b"a")?;
+        let base = CommitId::new(commit(repo_path, "base")?);
+
+        create_branch(repo_path, "feature", None)?;
+
+        write("bar", b"master only")?;
+        commit(repo_path, "on master")?;
+
+        checkout_branch(repo_path, "refs/heads/feature")?;
+        write("foo", b"b")?;
+        let c1 = CommitId::new(commit(repo_path, "feature 1")?);
+        write("baz", b"c")?;
+        let c2 = CommitId::new(commit(repo_path, "feature 2")?);
+
+        let res = compare_branch(repo_path, "refs/heads/master")?;
+
+        assert_eq!(res.commits, vec![c2, c1]);
+        assert_eq!(res.merge_base, base);
+        assert_eq!(res.head, c2);
+
+        // the change on master is not part of it
+        let files = get_compare_files(
+            repo_path,
+            res.merge_base,
+            Some(res.head),
+            None,
+        )?;
+        let mut paths: Vec<_> =
+            files.iter().map(|f| f.path.as_str()).collect();
+        paths.sort_unstable();
+        assert_eq!(paths, vec!["baz", "foo"]);
+
+        let res = compare_branch(repo_path, "refs/heads/feature")?;
+        assert!(res.commits.is_empty());
+        assert_eq!(res.merge_base, c2);
+
+        Ok(())
+    }
+}
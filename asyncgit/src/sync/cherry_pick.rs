@@ -0,0 +1,301 @@
+//! applying or undoing single commits on `HEAD`
+
+use super::{
+    utils::{has_tracked_changes, repo},
+    CommitId,
+};
+use crate::error::{Error, Result};
+use git2::{Commit, Oid, Repository, RepositoryState};
+use scopetime::scope_time;
+use std::fs;
+
+/// what happened when cherry-picking or reverting a commit
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PickOutcome {
+    /// the changes were committed
+    Committed(CommitId),
+    /// the changes are staged, `MERGE_MSG` holds the prepared message
+    Staged,
+    /// applying stopped on conflicts, index and workdir contain the
+    /// conflict markers
+    Conflicts,
+}
+
+/// applies the changes of `id` onto `HEAD`,
+/// committing them (as the original author) if `commit` is set
+pub fn cherry_pick(
+    repo_path: &str,
+    id: CommitId,
+    commit: bool,
+) -> Result<PickOutcome> {
+    scope_time!("cherry_pick");
+
+    let repo = repo(repo_path)?;
+    check_clean(&repo)?;
+
+    let picked = repo.find_commit(id.into())?;
+    if picked.parent_count() > 1 {
+        return Err(Error::Generic(String::from(
+            "cannot cherry-pick merge commits",
+        )));
+    }
+
+    repo.cherrypick(&picked, None)?;
+
+    finish(&repo, &picked, commit)
+}
+
+/// applies the inverse of the changes of `id` onto `HEAD`,
+/// committing them if `commit` is set
+pub fn revert_commit(
+    repo_path: &str,
+    id: CommitId,
+    commit: bool,
+) -> Result<PickOutcome> {
+    scope_time!("revert_commit");
+
+    let repo = repo(repo_path)?;
+    check_clean(&repo)?;
+
+    let reverted = repo.find_commit(id.into())?;
+    if reverted.parent_count() != 1 {
+        return Err(Error::Generic(String::from(
+            "can only revert commits with a single parent",
+        )));
+    }
+
+    let msg = format!(
+        "Revert \"{}\"\n\nThis reverts commit {}.\n",
+        reverted.summary().unwrap_or_default(),
+        reverted.id()
+    );
+
+    // cherry-picking a commit going from `reverted` back to its
+    // parent undoes it
+    let signature = repo.signature()?;
+    let inverse = repo.commit(
+        None,
+        &signature,
+        &signature,
+        &msg,
+        &reverted.parent(0)?.tree()?,
+        &[&reverted],
+    )?;
+    let inverse = repo.find_commit(inverse)?;
+
+    repo.cherrypick(&inverse, None)?;
+
+    fs::remove_file(repo.path().join("CHERRY_PICK_HEAD"))?;
+    fs::write(
+        repo.path().join("REVERT_HEAD"),
+        format!("{}\n", reverted.id()),
+    )?;
+
+    finish(&repo, &inverse, commit)
+}
+
+fn check_clean(repo: &Repository) -> Result<()> {
+    if repo.state() != RepositoryState::Clean {
+        return Err(Error::Generic(String::from(
+            "another operation is in progress",
+        )));
+    }
+
+    // the result is staged, it must not mix with other changes
+    if has_tracked_changes(repo)? {
+        return Err(Error::Generic(String::from(
+            "cannot apply a commit with uncommitted changes",
+        )));
+    }
+
+    Ok(())
+}
+
+fn finish(
+    repo: &Repository,
+    applied: &Commit,
+    commit: bool,
+) -> Result<PickOutcome> {
+    let mut index = repo.index()?;
+
+    if index.has_conflicts() {
+        return Ok(PickOutcome::Conflicts);
+    }
+
+    if !commit {
+        return Ok(PickOutcome::Staged);
+    }
+
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let msg = repo.message()?;
+
+    let id: Oid = repo.commit(
+        Some("HEAD"),
+        &applied.author(),
+        &repo.signature()?,
+        &msg,
+        &tree,
+        &[&head],
+    )?;
+
+    repo.cleanup_state()?;
+
+    Ok(PickOutcome::Committed(CommitId::new(id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, get_prepared_commit_msg, repo_state, stage_add_file,
+        tests::{repo_init, write_commit},
+        RepoState,
+    };
+    use git2::ResetType;
+    use std::path::Path;
+
+    fn reset_hard(repo: &Repository, id: Oid) {
+        repo.reset(
+            &repo.find_object(id, None).unwrap(),
+            ResetType::Hard,
+            None,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_cherry_pick() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = repo.head().unwrap().target().unwrap();
+        let picked = write_commit(repo_path, "a.txt", "a", "pick me");
+        reset_hard(&repo, base);
+
+        let res = cherry_pick(repo_path, picked, true).unwrap();
+        assert!(matches!(res, PickOutcome::Committed(_)));
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("pick me"));
+        assert_eq!(head.parent_id(0).unwrap(), base);
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+        assert!(root.join("a.txt").exists());
+    }
+
+    #[test]
+    fn test_cherry_pick_dirty() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = repo.head().unwrap().target().unwrap();
+        write_commit(repo_path, "b.txt", "b", "b");
+        let picked = write_commit(repo_path, "a.txt", "a", "pick me");
+        reset_hard(&repo, base);
+        write_commit(repo_path, "b.txt", "b", "b");
+
+        fs::write(root.join("b.txt"), "changed").unwrap();
+        assert!(cherry_pick(repo_path, picked, true).is_err());
+
+        stage_add_file(repo_path, Path::new("b.txt")).unwrap();
+        assert!(cherry_pick(repo_path, picked, true).is_err());
+        assert!(revert_commit(repo_path, picked, true).is_err());
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+    }
+
+    #[test]
+    fn test_cherry_pick_staged() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = repo.head().unwrap().target().unwrap();
+        let picked = write_commit(repo_path, "a.txt", "a", "pick me");
+        reset_hard(&repo, base);
+
+        assert_eq!(
+            cherry_pick(repo_path, picked, false).unwrap(),
+            PickOutcome::Staged
+        );
+        assert_eq!(
+            repo_state(repo_path).unwrap(),
+            RepoState::CherryPick
+        );
+        assert_eq!(
+            get_prepared_commit_msg(repo_path).unwrap().unwrap(),
+            "pick me"
+        );
+
+        commit(repo_path, "picked").unwrap();
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("picked"));
+    }
+
+    #[test]
+    fn test_cherry_pick_conflict() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = repo.head().unwrap().target().unwrap();
+        let picked = write_commit(repo_path, "a.txt", "theirs", "c1");
+        reset_hard(&repo, base);
+        write_commit(repo_path, "a.txt", "ours", "c2");
+
+        assert_eq!(
+            cherry_pick(repo_path, picked, true).unwrap(),
+            PickOutcome::Conflicts
+        );
+        assert_eq!(
+            repo_state(repo_path).unwrap(),
+            RepoState::CherryPick
+        );
+        assert!(cherry_pick(repo_path, picked, true).is_err());
+    }
+
+    #[test]
+    fn test_revert() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        write_commit(repo_path, "a.txt", "a", "c1");
+        let reverted = write_commit(repo_path, "a.txt", "b", "c2");
+        write_commit(repo_path, "b.txt", "b", "c3");
+
+        assert_eq!(
+            revert_commit(repo_path, reverted, false).unwrap(),
+            PickOutcome::Staged
+        );
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Revert);
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).unwrap(),
+            "a"
+        );
+
+        repo.cleanup_state().unwrap();
+        reset_hard(&repo, repo.head().unwrap().target().unwrap());
+
+        let res = revert_commit(repo_path, reverted, true).unwrap();
+        assert!(matches!(res, PickOutcome::Committed(_)));
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(
+            head.message().unwrap(),
+            format!(
+                "Revert \"c2\"\n\nThis reverts commit {}.\n",
+                reverted.to_string()
+            )
+        );
+        assert_eq!(head.author().name(), Some("name"));
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).unwrap(),
+            "a"
+        );
+        assert!(root.join("b.txt").exists());
+    }
+}
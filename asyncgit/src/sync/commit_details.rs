@@ -35,6 +35,7 @@ pub struct CommitMessage {
 }
 
 impl CommitMessage {
+    /// splits `s` into subject and body
     pub fn from(s: &str) -> Self {
         if let Some(idx) = s.find('\n') {
             let (first, rest) = s.split_at(idx);
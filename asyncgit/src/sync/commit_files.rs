@@ -1,19 +1,41 @@
-use super::{utils::repo, CommitId};
+use super::{diff::DiffOptions, utils::repo, CommitId};
 use crate::{error::Result, StatusItem, StatusItemType};
-use git2::{Diff, DiffDelta, DiffOptions, Repository};
+use git2::{Diff, DiffDelta, Repository, Tree};
 use scopetime::scope_time;
 
 /// get all files that are part of a commit
 pub fn get_commit_files(
     repo_path: &str,
     id: CommitId,
+    options: Option<DiffOptions>,
 ) -> Result<Vec<StatusItem>> {
     scope_time!("get_commit_files");
 
     let repo = repo(repo_path)?;
 
-    let diff = get_commit_diff(&repo, id, None)?;
+    let diff = get_commit_diff(&repo, id, None, options)?;
 
+    diff_files(&diff)
+}
+
+/// get all files that differ between the commits `from` and `to`, or
+/// between `from` and the workdir if `to` is `None`
+pub fn get_compare_files(
+    repo_path: &str,
+    from: CommitId,
+    to: Option<CommitId>,
+    options: Option<DiffOptions>,
+) -> Result<Vec<StatusItem>> {
+    scope_time!("get_compare_files");
+
+    let repo = repo(repo_path)?;
+
+    let diff = get_compare_diff(&repo, from, to, None, options)?;
+
+    diff_files(&diff)
+}
+
+fn diff_files(diff: &Diff) -> Result<Vec<StatusItem>> {
     let mut res = Vec::new();
 
     diff.foreach(
@@ -36,11 +58,14 @@ pub fn get_commit_files(
     Ok(res)
 }
 
-///
+/// diff of the commit `id` to its first parent, with rename or copy
+/// detection in `options` a pathspec'd diff includes the source of a
+/// renamed (or copied) file
 pub(crate) fn get_commit_diff(
     repo: &Repository,
     id: CommitId,
     pathspec: Option<String>,
+    options: Option<DiffOptions>,
 ) -> Result<Diff<'_>> {
     // scope_time!("get_commit_diff");
 
@@ -52,34 +77,131 @@ pub(crate) fn get_commit_diff(
         None
     };
 
-    let mut opt = pathspec.map(|p| {
-        let mut opts = DiffOptions::new();
-        opts.pathspec(p);
-        opts.show_binary(true);
-        opts
-    });
-
-    let diff = repo.diff_tree_to_tree(
+    get_tree_diff(
+        repo,
         parent.as_ref(),
         Some(&commit_tree),
-        opt.as_mut(),
-    )?;
+        pathspec,
+        options,
+    )
+}
+
+/// diff from the commit `from` to `to`, or to the workdir (and index)
+/// if `to` is `None`
+pub(crate) fn get_compare_diff(
+    repo: &Repository,
+    from: CommitId,
+    to: Option<CommitId>,
+    pathspec: Option<String>,
+    options: Option<DiffOptions>,
+) -> Result<Diff<'_>> {
+    // scope_time!("get_compare_diff");
+
+    let from_tree = repo.find_commit(from.into())?.tree()?;
+    let to_tree = match to {
+        Some(to) => Some(repo.find_commit(to.into())?.tree()?),
+        None => None,
+    };
+
+    get_tree_diff(
+        repo,
+        Some(&from_tree),
+        to_tree.as_ref(),
+        pathspec,
+        options,
+    )
+}
+
+/// diff of the trees `old` (empty if `None`) and `new` (the workdir if
+/// `None`), with rename or copy detection in `options` a pathspec'd
+/// diff includes the source of a renamed (or copied) file
+fn get_tree_diff<'a>(
+    repo: &'a Repository,
+    old: Option<&Tree>,
+    new: Option<&Tree>,
+    pathspec: Option<String>,
+    options: Option<DiffOptions>,
+) -> Result<Diff<'a>> {
+    let diff = |pathspecs: &[&str]| -> Result<Diff<'a>> {
+        let mut opt = git2::DiffOptions::new();
+        if let Some(options) = options {
+            options.apply(&mut opt);
+        }
+        for p in pathspecs {
+            opt.pathspec(p);
+        }
+        if !pathspecs.is_empty() {
+            opt.show_binary(true);
+        }
+
+        let mut diff = match new {
+            Some(new) => repo.diff_tree_to_tree(
+                old,
+                Some(new),
+                Some(&mut opt),
+            )?,
+            None => repo.diff_tree_to_workdir_with_index(
+                old,
+                Some(&mut opt),
+            )?,
+        };
+
+        if let Some(mut find) =
+            options.as_ref().and_then(DiffOptions::find_options)
+        {
+            diff.find_similar(Some(&mut find))?;
+        }
+
+        Ok(diff)
+    };
+
+    let path = match pathspec {
+        Some(path) => path,
+        None => return diff(&[]),
+    };
+
+    let detect = options
+        .as_ref()
+        .and_then(DiffOptions::find_options)
+        .is_some();
+
+    if detect {
+        // the source is only found comparing against all files
+        let source = diff(&[])?.deltas().find_map(|delta| {
+            let old = delta.old_file().path()?.to_str()?;
+            let new = delta.new_file().path()?.to_str()?;
+            if new == path && old != path {
+                Some(old.to_string())
+            } else {
+                None
+            }
+        });
 
-    Ok(diff)
+        if let Some(source) = source {
+            return diff(&[source.as_str(), path.as_str()]);
+        }
+    }
+
+    diff(&[path.as_str()])
 }
 
 #[cfg(test)]
 mod tests {
-    use super::get_commit_files;
+    use super::{get_commit_files, get_compare_files};
     use crate::{
         error::Result,
         sync::{
-            commit, stage_add_file, stash_save, tests::repo_init,
-            CommitId,
+            commit, get_diff_commit, get_diff_commits,
+            stage_add_file, stage_addremoved, stash_save,
+            tests::repo_init, CommitId, DiffOptions,
         },
-        StatusItemType,
+        DiffLineType, StatusItemType,
+    };
+    use std::{
+        fs::{self, File},
+        io::Write,
+        path::Path,
     };
-    use std::{fs::File, io::Write, path::Path};
 
     #[test]
     fn test_smoke() {
@@ -98,7 +220,8 @@ mod tests {
         let id = commit(repo_path, "commit msg").unwrap();
 
         let diff =
-            get_commit_files(repo_path, CommitId::new(id)).unwrap();
+            get_commit_files(repo_path, CommitId::new(id), None)
+                .unwrap();
 
         assert_eq!(diff.len(), 1);
         assert_eq!(diff[0].status, StatusItemType::New);
@@ -119,11 +242,116 @@ mod tests {
         //TODO: https://github.com/extrawurst/gitui/issues/130
         // `get_commit_diff` actually needs to merge the regular diff
         // and a third parent diff containing the untracked files
-        let _diff = get_commit_files(repo_path, id)?;
+        let _diff = get_commit_files(repo_path, id, None)?;
 
         // assert_eq!(diff.len(), 1);
         // assert_eq!(diff[0].status, StatusItemType::New);
 
         Ok(())
     }
+
+    #[test]
+    fn test_rename_detection() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("old.txt"))?
+            .write_all(b"a\nb\nc\nd\ne\n")?;
+        stage_add_file(repo_path, Path::new("old.txt"))?;
+        commit(repo_path, "add")?;
+
+        fs::remove_file(&root.join("old.txt"))?;
+        File::create(&root.join("new.txt"))?
+            .write_all(b"a\nb\nc\nd\nf\n")?;
+        stage_addremoved(repo_path, Path::new("old.txt"))?;
+        stage_add_file(repo_path, Path::new("new.txt"))?;
+        let id = CommitId::new(commit(repo_path, "rename")?);
+
+        let options = DiffOptions {
+            find_renames: true,
+            ..DiffOptions::default()
+        };
+
+        assert_eq!(get_commit_files(repo_path, id, None)?.len(), 2);
+
+        let files = get_commit_files(repo_path, id, Some(options))?;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "new.txt");
+        assert_eq!(files[0].status, StatusItemType::Renamed);
+
+        let diff = get_diff_commit(
+            repo_path,
+            id,
+            "new.txt".to_string(),
+            Some(options),
+        )?;
+        let changed = diff.hunks[0]
+            .lines
+            .iter()
+            .filter(|l| l.line_type != DiffLineType::None)
+            .count();
+        // header, `e` and `f`
+        assert_eq!(changed, 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let write = |name: &str, content: &[u8]| -> Result<()> {
+            File::create(&root.join(name))?.write_all(content)?;
+            stage_add_file(repo_path, Path::new(name))
+        };
+
+        write("a.txt", b"a\n")?;
+        let first = CommitId::new(commit(repo_path, "first")?);
+
+        write("b.txt", b"b\n")?;
+        commit(repo_path, "second")?;
+
+        write("a.txt", b"a\nc\n")?;
+        let third = CommitId::new(commit(repo_path, "third")?);
+
+        let files =
+            get_compare_files(repo_path, first, Some(third), None)?;
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "a.txt");
+        assert_eq!(files[0].status, StatusItemType::Modified);
+        assert_eq!(files[1].status, StatusItemType::New);
+
+        // staged and unstaged changes both count for the workdir
+        write("b.txt", b"b\nd\n")?;
+        File::create(&root.join("a.txt"))?.write_all(b"e\n")?;
+
+        let files = get_compare_files(repo_path, third, None, None)?;
+        assert_eq!(files.len(), 2);
+
+        let diff = get_diff_commits(
+            repo_path,
+            first,
+            None,
+            "a.txt".to_string(),
+            None,
+        )?;
+        let lines: Vec<_> = diff.hunks[0]
+            .lines
+            .iter()
+            .filter(|l| l.line_type != DiffLineType::Header)
+            .map(|l| (l.line_type, l.content.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (DiffLineType::Delete, "a\n"),
+                (DiffLineType::Add, "e\n")
+            ]
+        );
+
+        Ok(())
+    }
 }
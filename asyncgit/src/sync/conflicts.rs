@@ -0,0 +1,511 @@
+//! inspecting and resolving conflicted files of a merge, rebase,
+//! cherry-pick or revert
+
+use super::utils::{repo, work_dir};
+use crate::error::{Error, Result};
+use git2::{build::CheckoutBuilder, Repository};
+use scopetime::scope_time;
+use std::{fs, ops::Range, path::Path};
+
+/// bits of `IndexEntry::flags` holding the conflict stage
+const INDEX_ENTRY_STAGE_MASK: u16 = 0x3000;
+
+/// one version of a conflicted file
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConflictSide {
+    /// common ancestor
+    Base,
+    /// `HEAD`
+    Ours,
+    /// the commit being merged/applied
+    Theirs,
+}
+
+impl ConflictSide {
+    const fn stage(self) -> i32 {
+        match self {
+            Self::Base => 1,
+            Self::Ours => 2,
+            Self::Theirs => 3,
+        }
+    }
+}
+
+/// conflict markers of one conflict in a file,
+/// as line indices into `ConflictFile::lines`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ConflictHunk {
+    /// `<<<<<<<` line
+    pub start: usize,
+    /// `|||||||` line, only present with the diff3 conflict style
+    pub base: Option<usize>,
+    /// `=======` line
+    pub separator: usize,
+    /// `>>>>>>>` line
+    pub end: usize,
+}
+
+impl ConflictHunk {
+    /// lines of `side` inside the hunk (empty for `Base` without
+    /// diff3 markers)
+    pub fn side_lines(&self, side: ConflictSide) -> Range<usize> {
+        match side {
+            ConflictSide::Ours => {
+                self.start + 1..self.base.unwrap_or(self.separator)
+            }
+            ConflictSide::Base => self
+                .base
+                .map_or(self.separator..self.separator, |base| {
+                    base + 1..self.separator
+                }),
+            ConflictSide::Theirs => self.separator + 1..self.end,
+        }
+    }
+}
+
+/// workdir content of a conflicted file and its conflict markers
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct ConflictFile {
+    ///
+    pub lines: Vec<String>,
+    ///
+    pub hunks: Vec<ConflictHunk>,
+}
+
+impl ConflictFile {
+    fn parse(content: &str) -> Self {
+        let lines: Vec<String> =
+            content.lines().map(String::from).collect();
+
+        let mut hunks = Vec::new();
+        let mut start = None;
+        let mut base = None;
+        let mut separator = None;
+
+        for (idx, line) in lines.iter().enumerate() {
+            if is_marker(line, '<') {
+                start = Some(idx);
+                base = None;
+                separator = None;
+            } else if start.is_some() && is_marker(line, '|') {
+                base = Some(idx);
+            } else if start.is_some() && line == "=======" {
+                separator = Some(idx);
+            } else if is_marker(line, '>') {
+                if let (Some(start), Some(separator)) =
+                    (start, separator)
+                {
+                    hunks.push(ConflictHunk {
+                        start,
+                        base,
+                        separator,
+                        end: idx,
+                    });
+                }
+                start = None;
+            }
+        }
+
+        Self { lines, hunks }
+    }
+}
+
+fn is_marker(line: &str, c: char) -> bool {
+    let marker = c.to_string().repeat(7);
+    line == marker || line.starts_with(&format!("{} ", marker))
+}
+
+/// content of `path` in one side of the conflict,
+/// `None` if the file does not exist on that side
+pub fn get_conflict_version(
+    repo_path: &str,
+    path: &str,
+    side: ConflictSide,
+) -> Result<Option<String>> {
+    scope_time!("get_conflict_version");
+
+    let repo = repo(repo_path)?;
+
+    Ok(conflict_blob(&repo, path, side)?.map(|content| {
+        String::from_utf8_lossy(&content).into_owned()
+    }))
+}
+
+/// workdir content of the conflicted file `path`
+pub fn get_conflict_file(
+    repo_path: &str,
+    path: &str,
+) -> Result<ConflictFile> {
+    scope_time!("get_conflict_file");
+
+    let repo = repo(repo_path)?;
+    let content = fs::read(work_dir(&repo).join(path))?;
+
+    Ok(ConflictFile::parse(&String::from_utf8_lossy(&content)))
+}
+
+/// resolves the whole file with one side of the conflict
+/// (deleting it if it does not exist there) and stages the result
+pub fn resolve_conflict_file(
+    repo_path: &str,
+    path: &str,
+    side: ConflictSide,
+) -> Result<()> {
+    scope_time!("resolve_conflict_file");
+
+    let repo = repo(repo_path)?;
+    let file = work_dir(&repo).join(path);
+
+    let mut index = repo.index()?;
+
+    if let Some(mut entry) =
+        index.get_path(Path::new(path), side.stage())
+    {
+        // staging the side's entry (keeping its mode) resolves the
+        // conflict, checking it out runs it through the filters
+        entry.flags &= !INDEX_ENTRY_STAGE_MASK;
+        index.remove_path(Path::new(path))?;
+        index.add(&entry)?;
+        index.write()?;
+
+        let mut checkout = CheckoutBuilder::new();
+        checkout.path(path).force();
+        repo.checkout_index(Some(&mut index), Some(&mut checkout))?;
+    } else if index.get_path(Path::new(path), 0).is_some() {
+        return Err(Error::Generic(format!(
+            "'{}' is not conflicted",
+            path
+        )));
+    } else {
+        if file.exists() {
+            fs::remove_file(&file)?;
+        }
+        index.remove_path(Path::new(path))?;
+        index.write()?;
+    }
+
+    Ok(())
+}
+
+/// stages the workdir version of a conflicted file, marking it
+/// resolved. refuses to while it still contains conflict markers.
+pub fn mark_conflict_resolved(
+    repo_path: &str,
+    path: &str,
+) -> Result<()> {
+    scope_time!("mark_conflict_resolved");
+
+    let repo = repo(repo_path)?;
+    let file = work_dir(&repo).join(path);
+
+    let mut index = repo.index()?;
+
+    if file.exists() {
+        let content = fs::read(&file)?;
+        let conflict =
+            ConflictFile::parse(&String::from_utf8_lossy(&content));
+        if !conflict.hunks.is_empty() {
+            return Err(Error::Generic(format!(
+                "'{}' still contains {} conflict(s)",
+                path,
+                conflict.hunks.len()
+            )));
+        }
+
+        index.add_path(Path::new(path))?;
+    } else {
+        index.remove_path(Path::new(path))?;
+    }
+
+    index.write()?;
+
+    Ok(())
+}
+
+/// replaces the conflict `hunk` (index into `ConflictFile::hunks`) in
+/// the workdir file with one side of it. the file stays conflicted
+/// until it is staged.
+pub fn resolve_conflict_hunk(
+    repo_path: &str,
+    path: &str,
+    hunk: usize,
+    side: ConflictSide,
+) -> Result<()> {
+    scope_time!("resolve_conflict_hunk");
+
+    let repo = repo(repo_path)?;
+    let file = work_dir(&repo).join(path);
+
+    let content =
+        String::from_utf8_lossy(&fs::read(&file)?).into_owned();
+    let conflict = ConflictFile::parse(&content);
+
+    let hunk = conflict.hunks.get(hunk).ok_or_else(|| {
+        Error::Generic(String::from("conflict hunk not found"))
+    })?;
+
+    let mut lines = conflict.lines[..hunk.start].to_vec();
+    lines.extend_from_slice(&conflict.lines[hunk.side_lines(side)]);
+    lines.extend_from_slice(&conflict.lines[hunk.end + 1..]);
+
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut resolved = lines.join(newline);
+    if !lines.is_empty() && content.ends_with('\n') {
+        resolved.push_str(newline);
+    }
+
+    fs::write(&file, resolved)?;
+
+    Ok(())
+}
+
+fn conflict_blob(
+    repo: &Repository,
+    path: &str,
+    side: ConflictSide,
+) -> Result<Option<Vec<u8>>> {
+    let index = repo.index()?;
+
+    match index.get_path(Path::new(path), side.stage()) {
+        Some(entry) => {
+            Ok(Some(repo.find_blob(entry.id)?.content().to_vec()))
+        }
+        None if index.get_path(Path::new(path), 0).is_some() => Err(
+            Error::Generic(format!("'{}' is not conflicted", path)),
+        ),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, merge_branch,
+        status::{get_status, StatusItemType, StatusType},
+        tests::{repo_init, write_commit},
+        MergeOutcome,
+    };
+
+    fn has_conflicts(repo: &Repository) -> bool {
+        let mut index = repo.index().unwrap();
+        index.read(true).unwrap();
+        index.has_conflicts()
+    }
+
+    /// `a.txt` conflicting between `ours` and `theirs` after merging
+    /// branch `feature`
+    fn conflict(
+        repo: &Repository,
+        repo_path: &str,
+        base: &str,
+        ours: &str,
+        theirs: &str,
+    ) {
+        write_commit(repo_path, "a.txt", base, "msg");
+
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &base, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        write_commit(repo_path, "a.txt", theirs, "msg");
+        repo.set_head("refs/heads/master").unwrap();
+        repo.reset(base.as_object(), git2::ResetType::Hard, None)
+            .unwrap();
+        write_commit(repo_path, "a.txt", ours, "msg");
+
+        assert_eq!(
+            merge_branch(repo_path, "refs/heads/feature", false)
+                .unwrap(),
+            MergeOutcome::Conflicts
+        );
+    }
+
+    #[test]
+    fn test_conflict_versions() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        conflict(&repo, repo_path, "base\n", "ours\n", "theirs\n");
+
+        let status =
+            get_status(repo_path, StatusType::WorkingDir, true)
+                .unwrap();
+        assert_eq!(status.len(), 1);
+        assert_eq!(status[0].status, StatusItemType::Conflicted);
+        let stage =
+            get_status(repo_path, StatusType::Stage, true).unwrap();
+        assert_eq!(stage, status);
+
+        let version = |side| {
+            get_conflict_version(repo_path, "a.txt", side).unwrap()
+        };
+        assert_eq!(version(ConflictSide::Base).unwrap(), "base\n");
+        assert_eq!(version(ConflictSide::Ours).unwrap(), "ours\n");
+        assert_eq!(
+            version(ConflictSide::Theirs).unwrap(),
+            "theirs\n"
+        );
+
+        let file = get_conflict_file(repo_path, "a.txt").unwrap();
+        assert_eq!(file.hunks.len(), 1);
+        let hunk = file.hunks[0];
+        assert_eq!(
+            file.lines[hunk.side_lines(ConflictSide::Ours)],
+            ["ours"]
+        );
+        assert_eq!(
+            file.lines[hunk.side_lines(ConflictSide::Theirs)],
+            ["theirs"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_file() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        conflict(&repo, repo_path, "base\n", "ours\n", "theirs\n");
+
+        resolve_conflict_file(
+            repo_path,
+            "a.txt",
+            ConflictSide::Theirs,
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).unwrap(),
+            "theirs\n"
+        );
+        assert_eq!(has_conflicts(&repo), false);
+        assert!(get_conflict_version(
+            repo_path,
+            "a.txt",
+            ConflictSide::Ours
+        )
+        .is_err());
+
+        commit(repo_path, "merged").unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.parent_count(), 2);
+    }
+
+    #[test]
+    fn test_resolve_file_autocrlf() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        conflict(&repo, repo_path, "base\n", "ours\n", "theirs\n");
+        repo.config()
+            .unwrap()
+            .set_bool("core.autocrlf", true)
+            .unwrap();
+        {
+            // our side turned executable
+            let mut index = repo.index().unwrap();
+            index.read(true).unwrap();
+            let mut ours = index
+                .get_path(
+                    Path::new("a.txt"),
+                    ConflictSide::Ours.stage(),
+                )
+                .unwrap();
+            ours.mode = 0o100_755;
+            index.add(&ours).unwrap();
+            index.write().unwrap();
+        }
+
+        resolve_conflict_file(repo_path, "a.txt", ConflictSide::Ours)
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).unwrap(),
+            "ours\r\n"
+        );
+        assert_eq!(has_conflicts(&repo), false);
+
+        // the index keeps the blob of the side as it is
+        let mut index = repo.index().unwrap();
+        index.read(true).unwrap();
+        let entry = index.get_path(Path::new("a.txt"), 0).unwrap();
+        assert_eq!(
+            repo.find_blob(entry.id).unwrap().content(),
+            b"ours\n"
+        );
+        assert_eq!(entry.mode, 0o100_755);
+    }
+
+    #[test]
+    fn test_resolve_hunks() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        conflict(
+            &repo,
+            repo_path,
+            "1\n2\n3\n4\n5\n6\n7\n",
+            "1o\n2\n3\n4\n5\n6\n7o\n",
+            "1t\n2\n3\n4\n5\n6\n7t\n",
+        );
+
+        let file = get_conflict_file(repo_path, "a.txt").unwrap();
+        assert_eq!(file.hunks.len(), 2);
+        assert!(mark_conflict_resolved(repo_path, "a.txt").is_err());
+
+        resolve_conflict_hunk(
+            repo_path,
+            "a.txt",
+            1,
+            ConflictSide::Theirs,
+        )
+        .unwrap();
+        resolve_conflict_hunk(
+            repo_path,
+            "a.txt",
+            0,
+            ConflictSide::Ours,
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).unwrap(),
+            "1o\n2\n3\n4\n5\n6\n7t\n"
+        );
+        assert!(get_conflict_file(repo_path, "a.txt")
+            .unwrap()
+            .hunks
+            .is_empty());
+
+        // still conflicted until staged
+        assert_eq!(has_conflicts(&repo), true);
+
+        mark_conflict_resolved(repo_path, "a.txt").unwrap();
+        assert_eq!(has_conflicts(&repo), false);
+    }
+
+    #[test]
+    fn test_parse_diff3() {
+        let file = ConflictFile::parse(
+            "a\n<<<<<<< HEAD\no\n||||||| base\nb\n=======\nt\n>>>>>>> x\n",
+        );
+
+        assert_eq!(file.hunks.len(), 1);
+        let hunk = file.hunks[0];
+        assert_eq!(
+            file.lines[hunk.side_lines(ConflictSide::Base)],
+            ["b"]
+        );
+        assert_eq!(
+            file.lines[hunk.side_lines(ConflictSide::Ours)],
+            ["o"]
+        );
+    }
+}
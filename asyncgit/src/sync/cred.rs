@@ -0,0 +1,326 @@
+//! credentials for remote operations
+
+use super::utils::repo;
+use crate::error::Result;
+use git2::{
+    Config, Cred, CredentialHelper, CredentialType, Repository,
+};
+use scopetime::scope_time;
+use std::{collections::HashMap, env, path::PathBuf};
+
+/// username and password (or the passphrase of an ssh key)
+/// the user entered
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BasicAuthCredential {
+    ///
+    pub username: Option<String>,
+    ///
+    pub password: Option<String>,
+}
+
+impl BasicAuthCredential {
+    ///
+    pub const fn new(
+        username: Option<String>,
+        password: Option<String>,
+    ) -> Self {
+        Self { username, password }
+    }
+}
+
+/// credentials entered during this session by remote url
+pub type CredentialCache = HashMap<String, BasicAuthCredential>;
+
+/// url of `remote`, used to cache credentials
+pub fn get_remote_url(
+    repo_path: &str,
+    remote: &str,
+) -> Result<String> {
+    scope_time!("get_remote_url");
+
+    let repo = repo(repo_path)?;
+    let remote = repo.find_remote(remote)?;
+
+    Ok(remote.url().unwrap_or_default().to_string())
+}
+
+/// username and password `credential.helper` knows for `url`
+/// (username defaults to the one in the url)
+pub fn extract_username_password(
+    repo_path: &str,
+    url: &str,
+) -> Result<BasicAuthCredential> {
+    scope_time!("extract_username_password");
+
+    let repo = repo(repo_path)?;
+    let config = repo.config()?;
+
+    let mut helper = CredentialHelper::new(url);
+    helper.username(username_from_url(url).as_deref());
+    helper.config(&config);
+
+    Ok(match helper.execute() {
+        Some((username, password)) => {
+            BasicAuthCredential::new(Some(username), Some(password))
+        }
+        None => BasicAuthCredential::new(helper.username, None),
+    })
+}
+
+const NO_MORE_CREDENTIALS: &str = "no more credentials to try";
+
+/// hands out credentials to libgit2 one after another:
+/// ssh-agent, the default key files, `credential.helper` and
+/// finally the ones the user entered.
+/// libgit2 asks again as long as authentication fails.
+/// sources are used up, so every connection needs its own provider.
+pub(crate) struct CredentialProvider {
+    config: Config,
+    basic: Option<BasicAuthCredential>,
+    tried_agent: bool,
+    key_files: Vec<PathBuf>,
+    tried_helper: bool,
+    exhausted: bool,
+}
+
+impl CredentialProvider {
+    pub(crate) fn new(
+        repo: &Repository,
+        basic: Option<BasicAuthCredential>,
+    ) -> Result<Self> {
+        Ok(Self {
+            config: repo.config()?,
+            basic,
+            tried_agent: false,
+            key_files: default_key_files(),
+            tried_helper: false,
+            exhausted: false,
+        })
+    }
+
+    /// `true` if `e` is the failure of a connection we ran out of
+    /// credentials for (libgit2 asked again after all were handed out)
+    pub(crate) fn exhausted_by(&self, e: &git2::Error) -> bool {
+        self.exhausted && e.message() == NO_MORE_CREDENTIALS
+    }
+
+    pub(crate) fn get(
+        &mut self,
+        url: &str,
+        username_from_url: Option<&str>,
+        allowed: CredentialType,
+    ) -> std::result::Result<Cred, git2::Error> {
+        let username = username_from_url
+            .map(String::from)
+            .or_else(|| {
+                self.basic.as_ref().and_then(|b| b.username.clone())
+            })
+            .unwrap_or_else(|| String::from("git"));
+
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(username.as_str());
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            if let Some(cred) = self.next_ssh_key(username.as_str()) {
+                return Ok(cred);
+            }
+        }
+
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            if let Some(cred) =
+                self.next_user_pass(url, username_from_url)
+            {
+                return Ok(cred);
+            }
+        }
+
+        self.exhausted = true;
+
+        Err(git2::Error::from_str(NO_MORE_CREDENTIALS))
+    }
+
+    fn next_ssh_key(&mut self, username: &str) -> Option<Cred> {
+        if !self.tried_agent {
+            self.tried_agent = true;
+            if let Ok(cred) = Cred::ssh_key_from_agent(username) {
+                return Some(cred);
+            }
+        }
+
+        let passphrase =
+            self.basic.as_ref().and_then(|b| b.password.clone());
+
+        while !self.key_files.is_empty() {
+            let key = self.key_files.remove(0);
+            if key.exists() {
+                if let Ok(cred) = Cred::ssh_key(
+                    username,
+                    None,
+                    key.as_path(),
+                    passphrase.as_deref(),
+                ) {
+                    return Some(cred);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn next_user_pass(
+        &mut self,
+        url: &str,
+        username_from_url: Option<&str>,
+    ) -> Option<Cred> {
+        if !self.tried_helper {
+            self.tried_helper = true;
+            if let Ok(cred) = Cred::credential_helper(
+                &self.config,
+                url,
+                username_from_url,
+            ) {
+                return Some(cred);
+            }
+        }
+
+        if let Some(BasicAuthCredential {
+            username: Some(username),
+            password: Some(password),
+        }) = self.basic.take()
+        {
+            return Cred::userpass_plaintext(&username, &password)
+                .ok();
+        }
+
+        None
+    }
+}
+
+/// `user` of `https://user@host/..`
+fn username_from_url(url: &str) -> Option<String> {
+    let without_scheme = url.splitn(2, "://").nth(1)?;
+    let authority = without_scheme.split('/').next()?;
+    let (userinfo, _) = authority.split_at(authority.rfind('@')?);
+
+    userinfo.split(':').next().map(String::from)
+}
+
+fn default_key_files() -> Vec<PathBuf> {
+    let home =
+        env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"));
+
+    home.map_or_else(Vec::new, |home| {
+        let ssh_dir = PathBuf::from(home).join(".ssh");
+        ["id_ed25519", "id_ecdsa", "id_rsa", "id_dsa"]
+            .iter()
+            .map(|name| ssh_dir.join(name))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::tests::repo_init;
+    use git2::CredentialType;
+
+    #[test]
+    fn test_remote_url() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        repo.remote("origin", "https://user@example.com/repo.git")
+            .unwrap();
+
+        assert_eq!(
+            get_remote_url(repo_path, "origin").unwrap(),
+            "https://user@example.com/repo.git"
+        );
+        assert_eq!(get_remote_url(repo_path, "foo").is_err(), true);
+    }
+
+    #[test]
+    fn test_username_from_url() {
+        assert_eq!(
+            username_from_url("https://user@example.com/a@b.git"),
+            Some(String::from("user"))
+        );
+        assert_eq!(
+            username_from_url("https://user:pw@example.com"),
+            Some(String::from("user"))
+        );
+        assert_eq!(
+            username_from_url("https://example.com/a@b"),
+            None
+        );
+        assert_eq!(username_from_url("/local/path"), None);
+    }
+
+    #[test]
+    fn test_extract_from_helper() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let url = "https://example.com/repo.git";
+
+        assert_eq!(
+            extract_username_password(
+                repo_path,
+                "https://user@example.com/repo.git"
+            )
+            .unwrap(),
+            BasicAuthCredential::new(Some("user".into()), None)
+        );
+
+        repo.config()
+            .unwrap()
+            .set_str(
+                "credential.helper",
+                "!f() { echo username=a; echo password=b; }; f",
+            )
+            .unwrap();
+
+        if cfg!(not(windows)) {
+            assert_eq!(
+                extract_username_password(repo_path, url).unwrap(),
+                BasicAuthCredential::new(
+                    Some("a".into()),
+                    Some("b".into())
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_provider_order() {
+        let (_td, repo) = repo_init().unwrap();
+        let url = "https://example.com/repo.git";
+
+        let mut provider = CredentialProvider::new(
+            &repo,
+            Some(BasicAuthCredential::new(
+                Some("user".into()),
+                Some("pass".into()),
+            )),
+        )
+        .unwrap();
+
+        let cred = provider
+            .get(url, None, CredentialType::USER_PASS_PLAINTEXT)
+            .unwrap();
+        assert_eq!(cred.has_username(), true);
+
+        // entered credentials were rejected, nothing left to try
+        let err = provider
+            .get(url, None, CredentialType::USER_PASS_PLAINTEXT)
+            .err()
+            .unwrap();
+        assert_eq!(provider.exhausted_by(&err), true);
+        assert_eq!(
+            provider.exhausted_by(&git2::Error::from_str("other")),
+            false
+        );
+    }
+}
@@ -1,13 +1,24 @@
 //! sync git api for fetching a diff
 
-use super::{commit_files::get_commit_diff, utils, CommitId};
+use super::{
+    commit_files::{get_commit_diff, get_compare_diff},
+    utils, word_diff, CommitId,
+};
 use crate::{error::Error, error::Result, hash};
 use git2::{
-    Delta, Diff, DiffDelta, DiffFormat, DiffHunk, DiffOptions, Patch,
-    Repository,
+    Delta, Diff, DiffDelta, DiffFindOptions, DiffFormat, DiffHunk,
+    Patch, Repository,
 };
 use scopetime::scope_time;
-use std::{cell::RefCell, fs, path::Path, rc::Rc};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    fs::{self, File},
+    io::Read,
+    ops::Range,
+    path::Path,
+    rc::Rc,
+};
 use utils::{get_head_repo, work_dir};
 
 /// type of diff of a single line
@@ -29,6 +40,24 @@ impl Default for DiffLineType {
     }
 }
 
+/// line numbers of a diff line in the old and the new file
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct DiffLinePosition {
+    /// `None` for added lines
+    pub old_lineno: Option<u32>,
+    /// `None` for deleted lines
+    pub new_lineno: Option<u32>,
+}
+
+impl From<&git2::DiffLine<'_>> for DiffLinePosition {
+    fn from(line: &git2::DiffLine) -> Self {
+        Self {
+            old_lineno: line.old_lineno(),
+            new_lineno: line.new_lineno(),
+        }
+    }
+}
+
 ///
 #[derive(Default, Clone, Hash, Debug)]
 pub struct DiffLine {
@@ -36,6 +65,11 @@ pub struct DiffLine {
     pub content: String,
     ///
     pub line_type: DiffLineType,
+    ///
+    pub position: DiffLinePosition,
+    /// byte ranges of `content` that differ from the line it replaces
+    /// (or is replaced by), empty if unpaired or entirely different
+    pub changes: Vec<Range<usize>>,
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
@@ -79,6 +113,142 @@ pub struct FileDiff {
     pub sizes: (u64, u64),
     /// size delta in bytes
     pub size_delta: i64,
+    /// no lines, one of the versions is binary
+    pub binary: bool,
+    /// mime type guessed from the extension of a binary file
+    pub mime: Option<String>,
+    /// lines beyond the limits of the `DiffOptions` were left out
+    pub truncated: bool,
+}
+
+/// which whitespace changes to ignore
+#[derive(
+    Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize,
+)]
+pub enum IgnoreWhitespace {
+    /// show all whitespace changes
+    None,
+    /// ignore whitespace entirely when comparing lines
+    All,
+    /// ignore changes in the amount of whitespace
+    Change,
+    /// ignore whitespace at the end of lines
+    Eol,
+}
+
+impl Default for IgnoreWhitespace {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+/// algorithm used to find the differences
+/// (libgit2 has no histogram diff, patience is the closest)
+#[derive(
+    Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize,
+)]
+pub enum DiffAlgorithm {
+    /// git's default
+    Myers,
+    /// myers, spending extra time to find the smallest diff
+    Minimal,
+    /// patience diff
+    Patience,
+}
+
+impl Default for DiffAlgorithm {
+    fn default() -> Self {
+        Self::Myers
+    }
+}
+
+/// options affecting how diffs are generated
+#[derive(
+    Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct DiffOptions {
+    ///
+    pub ignore_whitespace: IgnoreWhitespace,
+    /// unchanged lines shown around each change
+    pub context: u32,
+    /// unchanged lines between changes up to which hunks are merged
+    pub interhunk_lines: u32,
+    ///
+    pub algorithm: DiffAlgorithm,
+    /// detect renamed files in commit diffs
+    pub find_renames: bool,
+    /// detect copied files in commit diffs
+    pub find_copies: bool,
+    /// diff lines a file diff is cut off after, 0 for no limit
+    pub max_lines: usize,
+    /// bytes of content a file diff is cut off after, 0 for no limit
+    pub max_bytes: usize,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            ignore_whitespace: IgnoreWhitespace::None,
+            context: 3,
+            interhunk_lines: 0,
+            algorithm: DiffAlgorithm::Myers,
+            find_renames: false,
+            find_copies: false,
+            max_lines: 10_000,
+            max_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl DiffOptions {
+    /// the same options without limiting the size of a diff
+    pub const fn unlimited(self) -> Self {
+        Self {
+            max_lines: 0,
+            max_bytes: 0,
+            ..self
+        }
+    }
+
+    /// whether `lines` lines of `bytes` bytes are beyond the limits
+    const fn exceeded(&self, lines: usize, bytes: usize) -> bool {
+        (self.max_lines > 0 && lines > self.max_lines)
+            || (self.max_bytes > 0 && bytes > self.max_bytes)
+    }
+
+    /// whether whitespace changes are left out of the diff
+    pub(crate) fn ignores_whitespace(&self) -> bool {
+        self.ignore_whitespace != IgnoreWhitespace::None
+    }
+
+    pub(crate) fn apply(&self, opt: &mut git2::DiffOptions) {
+        opt.ignore_whitespace(
+            self.ignore_whitespace == IgnoreWhitespace::All,
+        );
+        opt.ignore_whitespace_change(
+            self.ignore_whitespace == IgnoreWhitespace::Change,
+        );
+        opt.ignore_whitespace_eol(
+            self.ignore_whitespace == IgnoreWhitespace::Eol,
+        );
+        opt.context_lines(self.context);
+        opt.interhunk_lines(self.interhunk_lines);
+        opt.minimal(self.algorithm == DiffAlgorithm::Minimal);
+        opt.patience(self.algorithm == DiffAlgorithm::Patience);
+    }
+
+    /// `None` if neither renames nor copies are to be detected
+    pub(crate) fn find_options(&self) -> Option<DiffFindOptions> {
+        if !self.find_renames && !self.find_copies {
+            return None;
+        }
+
+        let mut opt = DiffFindOptions::new();
+        opt.renames(self.find_renames);
+        opt.copies(self.find_copies);
+        Some(opt)
+    }
 }
 
 pub(crate) fn get_diff_raw<'a>(
@@ -86,10 +256,14 @@ pub(crate) fn get_diff_raw<'a>(
     p: &str,
     stage: bool,
     reverse: bool,
+    options: Option<DiffOptions>,
 ) -> Result<Diff<'a>> {
     // scope_time!("get_diff_raw");
 
-    let mut opt = DiffOptions::new();
+    let mut opt = git2::DiffOptions::new();
+    if let Some(options) = options {
+        options.apply(&mut opt);
+    }
     opt.pathspec(p);
     opt.reverse(reverse);
 
@@ -125,14 +299,15 @@ pub fn get_diff(
     repo_path: &str,
     p: String,
     stage: bool,
+    options: Option<DiffOptions>,
 ) -> Result<FileDiff> {
     scope_time!("get_diff");
 
     let repo = utils::repo(repo_path)?;
     let work_dir = work_dir(&repo);
-    let diff = get_diff_raw(&repo, &p, stage, false)?;
+    let diff = get_diff_raw(&repo, &p, stage, false, options)?;
 
-    raw_diff_to_file_diff(&diff, work_dir)
+    raw_diff_to_file_diff(&diff, work_dir, options)
 }
 
 /// returns diff of a specific file inside a commit
@@ -141,38 +316,66 @@ pub fn get_diff_commit(
     repo_path: &str,
     id: CommitId,
     p: String,
+    options: Option<DiffOptions>,
 ) -> Result<FileDiff> {
     scope_time!("get_diff_commit");
 
     let repo = utils::repo(repo_path)?;
     let work_dir = work_dir(&repo);
-    let diff = get_commit_diff(&repo, id, Some(p))?;
+    let diff = get_commit_diff(&repo, id, Some(p), options)?;
 
-    raw_diff_to_file_diff(&diff, work_dir)
+    raw_diff_to_file_diff(&diff, work_dir, options)
 }
 
-///
+/// returns diff of a specific file between the commits `from` and
+/// `to`, or between `from` and the workdir if `to` is `None`
+/// see `get_compare_diff`
+pub fn get_diff_commits(
+    repo_path: &str,
+    from: CommitId,
+    to: Option<CommitId>,
+    p: String,
+    options: Option<DiffOptions>,
+) -> Result<FileDiff> {
+    scope_time!("get_diff_commits");
+
+    let repo = utils::repo(repo_path)?;
+    let work_dir = work_dir(&repo);
+    let diff = get_compare_diff(&repo, from, to, Some(p), options)?;
+
+    raw_diff_to_file_diff(&diff, work_dir, options)
+}
+
+/// converts the (single file) `diff` into a `FileDiff`, leaving out
+/// the lines beyond the limits of `options`
 fn raw_diff_to_file_diff<'a>(
     diff: &'a Diff,
     work_dir: &Path,
+    options: Option<DiffOptions>,
 ) -> Result<FileDiff> {
+    let options = options.unwrap_or_default();
     let res = Rc::new(RefCell::new(FileDiff::default()));
     {
         let mut current_lines = Vec::new();
         let mut current_hunk: Option<HunkHeader> = None;
+        let mut total_lines = 0_usize;
+        let mut total_bytes = 0_usize;
 
         let res_cell = Rc::clone(&res);
         let adder = move |header: &HunkHeader,
                           lines: &Vec<DiffLine>| {
             let mut res = res_cell.borrow_mut();
+            let mut lines = lines.clone();
+            word_diff::highlight_changes(&mut lines);
+            res.lines += lines.len();
             res.hunks.push(Hunk {
                 header_hash: hash(header),
-                lines: lines.clone(),
+                lines,
             });
-            res.lines += lines.len();
         };
 
         let res_cell = Rc::clone(&res);
+        // returns false once the limits are reached
         let mut put = |delta: DiffDelta,
                        hunk: Option<DiffHunk>,
                        line: git2::DiffLine| {
@@ -184,8 +387,19 @@ fn raw_diff_to_file_diff<'a>(
                 );
                 res.size_delta = (res.sizes.1 as i64)
                     .saturating_sub(res.sizes.0 as i64);
+                if delta.flags().is_binary() {
+                    res.binary = true;
+                    res.mime = guess_mime(&delta);
+                }
             }
             if let Some(hunk) = hunk {
+                total_lines += 1;
+                total_bytes += line.content().len();
+                if options.exceeded(total_lines, total_bytes) {
+                    res_cell.borrow_mut().truncated = true;
+                    return false;
+                }
+
                 let hunk_header = HunkHeader::from(hunk);
 
                 match current_hunk {
@@ -209,10 +423,14 @@ fn raw_diff_to_file_diff<'a>(
                     content: String::from_utf8_lossy(line.content())
                         .to_string(),
                     line_type,
+                    position: DiffLinePosition::from(&line),
+                    changes: Vec::new(),
                 };
 
                 current_lines.push(diff_line);
             }
+
+            true
         };
 
         let new_file_diff = if diff.deltas().len() == 1 {
@@ -230,26 +448,45 @@ fn raw_diff_to_file_diff<'a>(
 
                 let newfile_path = work_dir.join(relative_path);
 
-                if let Some(newfile_content) =
-                    new_file_content(&newfile_path)
-                {
-                    let mut patch = Patch::from_buffers(
-                        &[],
-                        None,
-                        newfile_content.as_bytes(),
-                        Some(&newfile_path),
-                        None,
-                    )?;
-
-                    patch
-                    .print(&mut |delta, hunk:Option<DiffHunk>, line: git2::DiffLine| {
-                        put(delta,hunk,line);
+                match new_file_content(
+                    &newfile_path,
+                    options.max_bytes,
+                ) {
+                    Some(NewFile::Text(
+                        newfile_content,
+                        truncated,
+                    )) => {
+                        let mut patch = Patch::from_buffers(
+                            &[],
+                            None,
+                            newfile_content.as_bytes(),
+                            Some(&newfile_path),
+                            None,
+                        )?;
+
+                        let printed = patch
+                        .print(&mut |delta, hunk:Option<DiffHunk>, line: git2::DiffLine| {
+                            put(delta,hunk,line)
+                        });
+                        // stopping at the limits is reported as an error
+                        if !res.borrow().truncated {
+                            printed?;
+                        }
+
+                        res.borrow_mut().truncated |= truncated;
+
                         true
-                    })?;
+                    }
+                    Some(NewFile::Binary(size)) => {
+                        let mut res = res.borrow_mut();
+                        res.binary = true;
+                        res.mime = guess_mime(&delta);
+                        res.sizes = (0, size);
+                        res.size_delta = size as i64;
 
-                    true
-                } else {
-                    false
+                        true
+                    }
+                    None => false,
                 }
             } else {
                 false
@@ -259,13 +496,16 @@ fn raw_diff_to_file_diff<'a>(
         };
 
         if !new_file_diff {
-            diff.print(
+            let printed = diff.print(
                 DiffFormat::Patch,
                 move |delta, hunk, line: git2::DiffLine| {
-                    put(delta, hunk, line);
-                    true
+                    put(delta, hunk, line)
                 },
-            )?;
+            );
+            // stopping at the limits is reported as an error
+            if !res.borrow().truncated {
+                printed?;
+            }
         }
 
         if !current_lines.is_empty() {
@@ -280,16 +520,65 @@ fn raw_diff_to_file_diff<'a>(
     Ok(res.into_inner())
 }
 
-fn new_file_content(path: &Path) -> Option<String> {
+/// mime type the extension of the file suggests
+fn guess_mime(delta: &DiffDelta) -> Option<String> {
+    delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())
+        .and_then(|path| mime_guess::from_path(path).first_raw())
+        .map(String::from)
+}
+
+/// content of an untracked file
+enum NewFile {
+    /// the text, cut off (at a line end) if longer than the limit
+    Text(String, bool),
+    /// size of a file that is not valid utf8
+    Binary(u64),
+}
+
+fn new_file_content(
+    path: &Path,
+    max_bytes: usize,
+) -> Option<NewFile> {
     if let Ok(meta) = fs::symlink_metadata(path) {
         if meta.file_type().is_symlink() {
             if let Ok(path) = fs::read_link(path) {
-                return Some(path.to_str()?.to_string());
+                return Some(NewFile::Text(
+                    path.to_str()?.to_string(),
+                    false,
+                ));
             }
         } else if meta.file_type().is_file() {
-            if let Ok(content) = fs::read_to_string(path) {
-                return Some(content);
+            let mut content = Vec::new();
+            let limit = if max_bytes > 0 {
+                max_bytes as u64 + 1
+            } else {
+                u64::MAX
+            };
+            File::open(path)
+                .ok()?
+                .take(limit)
+                .read_to_end(&mut content)
+                .ok()?;
+
+            let truncated =
+                max_bytes > 0 && content.len() > max_bytes;
+            if truncated {
+                let end = content[..max_bytes]
+                    .iter()
+                    .rposition(|b| *b == b'\n')
+                    .map_or(0, |idx| idx + 1);
+                content.truncate(end);
             }
+
+            return Some(match String::from_utf8(content) {
+                Ok(content) if !content.contains('\0') => {
+                    NewFile::Text(content, truncated)
+                }
+                _ => NewFile::Binary(meta.len()),
+            });
         }
     }
 
@@ -298,7 +587,9 @@ fn new_file_content(path: &Path) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::{get_diff, get_diff_commit};
+    use super::{
+        get_diff, get_diff_commit, DiffOptions, IgnoreWhitespace,
+    };
     use crate::error::Result;
     use crate::sync::{
         commit, stage_add_file,
@@ -328,9 +619,13 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (1, 0));
 
-        let diff =
-            get_diff(repo_path, "foo/bar.txt".to_string(), false)
-                .unwrap();
+        let diff = get_diff(
+            repo_path,
+            "foo/bar.txt".to_string(),
+            false,
+            None,
+        )
+        .unwrap();
 
         assert_eq!(diff.hunks.len(), 1);
         assert_eq!(diff.hunks[0].lines[1].content, "test\n");
@@ -360,6 +655,7 @@ mod tests {
             repo_path,
             String::from(file_path.to_str().unwrap()),
             true,
+            None,
         )
         .unwrap();
 
@@ -427,8 +723,9 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (1, 1));
 
-        let res = get_diff(repo_path, "bar.txt".to_string(), false)
-            .unwrap();
+        let res =
+            get_diff(repo_path, "bar.txt".to_string(), false, None)
+                .unwrap();
 
         assert_eq!(res.hunks.len(), 2)
     }
@@ -451,6 +748,7 @@ mod tests {
             sub_path.to_str().unwrap(),
             String::from(file_path.to_str().unwrap()),
             false,
+            None,
         )
         .unwrap();
 
@@ -471,6 +769,7 @@ mod tests {
             repo_path,
             String::from(file_path.to_str().unwrap()),
             false,
+            None,
         )
         .unwrap();
 
@@ -499,6 +798,7 @@ mod tests {
             repo_path,
             String::from(file_path.to_str().unwrap()),
             false,
+            None,
         )
         .unwrap();
 
@@ -533,6 +833,7 @@ mod tests {
             repo_path,
             CommitId::new(id),
             String::new(),
+            None,
         )
         .unwrap();
 
@@ -542,4 +843,144 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_diff_options() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("bar.txt"))?
+            .write_all(HUNK_A.as_bytes())?;
+        stage_add_file(repo_path, Path::new("bar.txt"))?;
+        commit(repo_path, "commit")?;
+
+        let content = HUNK_B.replace("middle", "middle  ");
+        File::create(&root.join("bar.txt"))?
+            .write_all(content.as_bytes())?;
+
+        let hunks = |options| -> Result<usize> {
+            let diff = get_diff(
+                repo_path,
+                "bar.txt".to_string(),
+                false,
+                Some(options),
+            )?;
+            Ok(diff.hunks.len())
+        };
+
+        let eol = DiffOptions {
+            ignore_whitespace: IgnoreWhitespace::Eol,
+            ..DiffOptions::default()
+        };
+
+        // the trailing whitespace joins both changes into one hunk
+        assert_eq!(hunks(DiffOptions::default())?, 1);
+        assert_eq!(hunks(eol)?, 2);
+        assert_eq!(
+            hunks(DiffOptions {
+                context: 0,
+                ..DiffOptions::default()
+            })?,
+            3
+        );
+        assert_eq!(
+            hunks(DiffOptions {
+                interhunk_lines: 10,
+                ..eol
+            })?,
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_binary() -> Result<()> {
+        let (_td, repo) = repo_init_empty()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("img.png"))?
+            .write_all(b"\x00\x01")?;
+        stage_add_file(repo_path, Path::new("img.png"))?;
+        commit(repo_path, "commit")?;
+        File::create(&root.join("img.png"))?
+            .write_all(b"\x00\x01\x02")?;
+
+        let diff =
+            get_diff(repo_path, "img.png".into(), false, None)?;
+
+        assert!(diff.binary);
+        assert!(diff.hunks.is_empty());
+        assert_eq!(diff.mime.as_deref(), Some("image/png"));
+        assert_eq!(diff.sizes, (2, 3));
+
+        File::create(&root.join("new.bin"))?.write_all(b"a\x00b")?;
+
+        let diff =
+            get_diff(repo_path, "new.bin".into(), false, None)?;
+
+        assert!(diff.binary);
+        assert!(diff.untracked);
+        assert_eq!(diff.sizes, (0, 3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_limits() -> Result<()> {
+        let (_td, repo) = repo_init_empty()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let content = |prefix: &str| {
+            (0..100)
+                .map(|i| format!("{} {}\n", prefix, i))
+                .collect::<String>()
+        };
+
+        File::create(&root.join("foo"))?
+            .write_all(content("a").as_bytes())?;
+        stage_add_file(repo_path, Path::new("foo"))?;
+        commit(repo_path, "commit")?;
+        File::create(&root.join("foo"))?
+            .write_all(content("b").as_bytes())?;
+
+        let limited = DiffOptions {
+            max_lines: 10,
+            ..DiffOptions::default()
+        };
+
+        let diff =
+            get_diff(repo_path, "foo".into(), false, Some(limited))?;
+        assert!(diff.truncated);
+        assert_eq!(diff.lines, 10);
+
+        let diff = get_diff(
+            repo_path,
+            "foo".into(),
+            false,
+            Some(limited.unlimited()),
+        )?;
+        assert!(!diff.truncated);
+        assert_eq!(diff.lines, 201);
+
+        // untracked files are only read up to the limit
+        File::create(&root.join("bar"))?
+            .write_all(content("c").as_bytes())?;
+
+        let limited = DiffOptions {
+            max_bytes: 20,
+            ..DiffOptions::default()
+        };
+
+        let diff =
+            get_diff(repo_path, "bar".into(), false, Some(limited))?;
+        assert!(diff.truncated);
+        assert_eq!(diff.hunks[0].lines[1].content, "c 0\n");
+        assert_eq!(diff.lines, 2);
+
+        Ok(())
+    }
 }
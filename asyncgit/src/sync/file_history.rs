@@ -0,0 +1,195 @@
+//! commits touching a single file
+
+use super::{utils::repo, CommitId};
+use crate::error::Result;
+use git2::{
+    Commit, Delta, DiffFindOptions, DiffOptions, Oid, Repository,
+    Sort, Tree,
+};
+use scopetime::scope_time;
+use std::path::Path;
+
+/// a commit changing the file, with the path the file has in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileHistoryEntry {
+    ///
+    pub id: CommitId,
+    /// path of the file in this commit
+    pub path: String,
+    /// path before this commit if it renamed the file
+    pub old_path: Option<String>,
+}
+
+/// returns the commits reachable from `HEAD` that changed `path`
+/// (newest first), following renames like `git log --follow`
+pub fn get_file_history(
+    repo_path: &str,
+    path: &str,
+) -> Result<Vec<FileHistoryEntry>> {
+    scope_time!("get_file_history");
+
+    let repo = repo(repo_path)?;
+
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    // children before parents, so a rename is seen before the
+    // commits using the old name
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+    let mut res = Vec::new();
+    let mut path = String::from(path);
+
+    for id in walk {
+        let commit = repo.find_commit(id?)?;
+        let tree = commit.tree()?;
+        let current = entry_id(&tree, &path);
+
+        let parents = commit
+            .parents()
+            .map(|p| p.tree().map(|t| entry_id(&t, &path)))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let changed = if parents.is_empty() {
+            current.is_some()
+        } else {
+            // unchanged compared to any parent (e.g. merged in as is)
+            !parents.contains(&current)
+        };
+
+        if !changed {
+            continue;
+        }
+
+        let old_path = match (current, parents.first()) {
+            (Some(_), Some(None)) => {
+                find_rename_source(&repo, &commit, &path)?
+            }
+            _ => None,
+        };
+
+        res.push(FileHistoryEntry {
+            id: CommitId::new(commit.id()),
+            path: path.clone(),
+            old_path: old_path.clone(),
+        });
+
+        if let Some(old_path) = old_path {
+            path = old_path;
+        }
+    }
+
+    Ok(res)
+}
+
+fn entry_id(tree: &Tree, path: &str) -> Option<Oid> {
+    tree.get_path(Path::new(path)).ok().map(|e| e.id())
+}
+
+/// path `path` had in the first parent, if `commit` renamed it
+fn find_rename_source(
+    repo: &Repository,
+    commit: &Commit,
+    path: &str,
+) -> Result<Option<String>> {
+    let parent = commit.parent(0)?.tree()?;
+    let tree = commit.tree()?;
+
+    let mut opt = DiffOptions::new();
+    let mut diff = repo.diff_tree_to_tree(
+        Some(&parent),
+        Some(&tree),
+        Some(&mut opt),
+    )?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+    let source = diff
+        .deltas()
+        .find(|d| {
+            d.status() == Delta::Renamed
+                && d.new_file().path() == Some(Path::new(path))
+        })
+        .and_then(|d| {
+            d.old_file()
+                .path()
+                .and_then(Path::to_str)
+                .map(String::from)
+        });
+
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, stage_add_all, stage_add_file, stage_addremoved,
+        tests::repo_init,
+    };
+    use std::{
+        fs::{self, File},
+        io::Write,
+    };
+
+    #[test]
+    fn test_follow_rename() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let content = "line 1\nline 2\nline 3\nline 4\nline 5\n";
+
+        File::create(&root.join("foo"))
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+        stage_add_file(repo_path, Path::new("foo")).unwrap();
+        let c1 = commit(repo_path, "add foo").unwrap();
+
+        File::create(&root.join("other"))
+            .unwrap()
+            .write_all(b"a")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("other")).unwrap();
+        commit(repo_path, "add other").unwrap();
+
+        fs::rename(root.join("foo"), root.join("bar")).unwrap();
+        stage_addremoved(repo_path, Path::new("foo")).unwrap();
+        stage_add_file(repo_path, Path::new("bar")).unwrap();
+        let c3 = commit(repo_path, "rename").unwrap();
+
+        File::create(&root.join("bar"))
+            .unwrap()
+            .write_all(format!("{}line 6\n", content).as_bytes())
+            .unwrap();
+        stage_add_all(repo_path, "*").unwrap();
+        let c4 = commit(repo_path, "change bar").unwrap();
+
+        let res = get_file_history(repo_path, "bar").unwrap();
+
+        assert_eq!(
+            res,
+            vec![
+                FileHistoryEntry {
+                    id: CommitId::new(c4),
+                    path: String::from("bar"),
+                    old_path: None,
+                },
+                FileHistoryEntry {
+                    id: CommitId::new(c3),
+                    path: String::from("bar"),
+                    old_path: Some(String::from("foo")),
+                },
+                FileHistoryEntry {
+                    id: CommitId::new(c1),
+                    path: String::from("foo"),
+                    old_path: None,
+                },
+            ]
+        );
+
+        assert_eq!(
+            get_file_history(repo_path, "nope").unwrap(),
+            vec![]
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! lane layout for drawing the commit graph next to the log
+
+use git2::Oid;
+
+/// one lane of a graph row: which directions a line leaves the
+/// cell to and whether the commit of the row sits in it
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct GraphCell(u8);
+
+impl GraphCell {
+    /// the commit of this row
+    pub const COMMIT: u8 = 0b00001;
+    /// line to the row above (towards the children)
+    pub const UP: u8 = 0b00010;
+    /// line to the row below (towards the parents)
+    pub const DOWN: u8 = 0b00100;
+    /// line to the lane on the left
+    pub const LEFT: u8 = 0b01000;
+    /// line to the lane on the right
+    pub const RIGHT: u8 = 0b10000;
+
+    ///
+    pub const fn has(self, flags: u8) -> bool {
+        self.0 & flags == flags
+    }
+
+    ///
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    fn set(&mut self, flags: u8) {
+        self.0 |= flags;
+    }
+
+    fn unset(&mut self, flags: u8) {
+        self.0 &= !flags;
+    }
+}
+
+/// graph column of a single commit
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphRow {
+    /// lane the commit itself is drawn in
+    pub commit_lane: usize,
+    /// cells from left to right, trailing empty lanes are omitted
+    pub cells: Box<[GraphCell]>,
+}
+
+/// assigns commits to lanes one after another.
+///
+/// commits have to be added in the order of the log (children before
+/// parents), so batches of the walk can be laid out as they arrive.
+/// every lane remembers the commit it waits for next.
+#[derive(Default)]
+pub struct CommitGraph {
+    lanes: Vec<Option<Oid>>,
+}
+
+impl CommitGraph {
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// lays out `id` and reserves lanes for its `parents`
+    pub fn add(&mut self, id: Oid, parents: &[Oid]) -> GraphRow {
+        let mut cells: Vec<GraphCell> = self
+            .lanes
+            .iter()
+            .map(|lane| {
+                let mut cell = GraphCell::default();
+                if lane.is_some() {
+                    cell.set(GraphCell::UP | GraphCell::DOWN);
+                }
+                cell
+            })
+            .collect();
+
+        let waiting: Vec<usize> = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter(|(_, lane)| **lane == Some(id))
+            .map(|(idx, _)| idx)
+            .collect();
+
+        let commit_lane = match waiting.first() {
+            Some(idx) => *idx,
+            None => self.free_lane(0, &mut cells),
+        };
+
+        cells[commit_lane] = GraphCell(GraphCell::COMMIT);
+        if !waiting.is_empty() {
+            cells[commit_lane].set(GraphCell::UP);
+        }
+
+        // other children's lanes end in this commit
+        for idx in waiting.iter().skip(1) {
+            self.lanes[*idx] = None;
+            cells[*idx].unset(GraphCell::DOWN);
+            connect(&mut cells, commit_lane, *idx);
+        }
+
+        self.lanes[commit_lane] = parents.first().copied();
+        if !parents.is_empty() {
+            cells[commit_lane].set(GraphCell::DOWN);
+        }
+
+        for (i, parent) in parents.iter().enumerate().skip(1) {
+            // a merge may list the same parent twice
+            if parents[..i].contains(parent) {
+                continue;
+            }
+
+            let idx = match self
+                .lanes
+                .iter()
+                .position(|lane| *lane == Some(*parent))
+            {
+                Some(idx) => idx,
+                None => {
+                    let idx =
+                        self.free_lane(commit_lane + 1, &mut cells);
+                    self.lanes[idx] = Some(*parent);
+                    idx
+                }
+            };
+
+            cells[idx].set(GraphCell::DOWN);
+            connect(&mut cells, commit_lane, idx);
+        }
+
+        while self.lanes.last() == Some(&None) {
+            self.lanes.pop();
+        }
+        while cells.last().map_or(false, |c| c.is_empty()) {
+            cells.pop();
+        }
+
+        GraphRow {
+            commit_lane,
+            cells: cells.into_boxed_slice(),
+        }
+    }
+
+    /// first unused lane starting at `from`, opens a new one if needed
+    fn free_lane(
+        &mut self,
+        from: usize,
+        cells: &mut Vec<GraphCell>,
+    ) -> usize {
+        if let Some(idx) =
+            self.lanes.iter().skip(from).position(Option::is_none)
+        {
+            return from + idx;
+        }
+
+        self.lanes.push(None);
+        cells.resize(self.lanes.len(), GraphCell::default());
+
+        self.lanes.len() - 1
+    }
+}
+
+/// horizontal line between lane `from` and lane `to`
+fn connect(cells: &mut [GraphCell], from: usize, to: usize) {
+    if from == to {
+        return;
+    }
+
+    let (left, right) =
+        if from < to { (from, to) } else { (to, from) };
+
+    cells[left].set(GraphCell::RIGHT);
+    for cell in &mut cells[left + 1..right] {
+        cell.set(GraphCell::LEFT | GraphCell::RIGHT);
+    }
+    cells[right].set(GraphCell::LEFT);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    fn draw(row: &GraphRow) -> String {
+        row.cells
+            .iter()
+            .map(|c| {
+                if c.has(GraphCell::COMMIT) {
+                    '*'
+                } else if c.has(GraphCell::UP | GraphCell::DOWN) {
+                    if c.has(GraphCell::LEFT)
+                        || c.has(GraphCell::RIGHT)
+                    {
+                        '+'
+                    } else {
+                        '|'
+                    }
+                } else if c.has(GraphCell::UP) {
+                    '/'
+                } else if c.has(GraphCell::DOWN) {
+                    '\\'
+                } else if c.is_empty() {
+                    ' '
+                } else {
+                    '-'
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_linear() {
+        let mut graph = CommitGraph::new();
+
+        let rows = vec![
+            graph.add(oid(3), &[oid(2)]),
+            graph.add(oid(2), &[oid(1)]),
+            graph.add(oid(1), &[]),
+        ];
+
+        for row in &rows {
+            assert_eq!(row.commit_lane, 0);
+            assert_eq!(draw(row), "*");
+        }
+
+        assert_eq!(rows[0].cells[0].has(GraphCell::UP), false);
+        assert_eq!(rows[1].cells[0].has(GraphCell::UP), true);
+        assert_eq!(rows[2].cells[0].has(GraphCell::DOWN), false);
+        assert_eq!(graph.lanes.is_empty(), true);
+    }
+
+    #[test]
+    fn test_merge() {
+        // 4 merges 3 into 2, both based on 1
+        let mut graph = CommitGraph::new();
+
+        let merge = graph.add(oid(4), &[oid(2), oid(3)]);
+        assert_eq!(draw(&merge), "*\\");
+        assert_eq!(merge.cells[0].has(GraphCell::RIGHT), true);
+        assert_eq!(merge.cells[1].has(GraphCell::LEFT), true);
+
+        let side = graph.add(oid(3), &[oid(1)]);
+        assert_eq!(side.commit_lane, 1);
+        assert_eq!(draw(&side), "|*");
+
+        let main = graph.add(oid(2), &[oid(1)]);
+        assert_eq!(draw(&main), "*|");
+
+        let base = graph.add(oid(1), &[]);
+        assert_eq!(draw(&base), "*/");
+        assert_eq!(base.cells[1].has(GraphCell::LEFT), true);
+
+        assert_eq!(graph.lanes.is_empty(), true);
+    }
+
+    #[test]
+    fn test_duplicate_parent() {
+        let mut graph = CommitGraph::new();
+
+        let merge = graph.add(oid(2), &[oid(1), oid(1)]);
+        assert_eq!(draw(&merge), "*");
+        assert_eq!(merge.cells[0].has(GraphCell::RIGHT), false);
+
+        assert_eq!(draw(&graph.add(oid(1), &[])), "*");
+        assert_eq!(graph.lanes.is_empty(), true);
+    }
+
+    #[test]
+    fn test_incremental_tips() {
+        // two unrelated tips get their own lanes, a lane that
+        // ends frees its slot for the next branch
+        let mut graph = CommitGraph::new();
+
+        assert_eq!(draw(&graph.add(oid(5), &[oid(1)])), "*");
+        assert_eq!(draw(&graph.add(oid(4), &[oid(2)])), "|*");
+        assert_eq!(draw(&graph.add(oid(1), &[])), "*|");
+        assert_eq!(draw(&graph.add(oid(3), &[])), "*|");
+        assert_eq!(draw(&graph.add(oid(2), &[])), " *");
+        assert_eq!(graph.lanes.is_empty(), true);
+    }
+
+    #[test]
+    fn test_crossing() {
+        // merge connecting to a lane beyond a passing lane
+        let mut graph = CommitGraph::new();
+
+        graph.add(oid(9), &[oid(8)]);
+        graph.add(oid(7), &[oid(6)]);
+        graph.add(oid(5), &[oid(4)]);
+
+        let row = graph.add(oid(8), &[oid(1), oid(4)]);
+        assert_eq!(draw(&row), "*++");
+        assert_eq!(row.cells[2].has(GraphCell::LEFT), true);
+    }
+}
@@ -1,25 +1,36 @@
 use super::{
-    diff::{get_diff_raw, HunkHeader},
-    utils::repo,
+    diff::{get_diff_raw, DiffLinePosition, DiffOptions, HunkHeader},
+    utils::{get_head_repo, repo, work_dir},
 };
 use crate::{
     error::{Error, Result},
     hash,
 };
-use git2::{ApplyLocation, ApplyOptions, Diff};
+use git2::{
+    build::CheckoutBuilder, ApplyLocation, ApplyOptions, Diff, Index,
+    IndexEntry, IndexTime, Patch, Repository,
+};
 use scopetime::scope_time;
+use std::{fs, path::Path};
 
 ///
 pub fn stage_hunk(
     repo_path: &str,
     file_path: String,
     hunk_hash: u64,
+    options: Option<DiffOptions>,
 ) -> Result<()> {
     scope_time!("stage_hunk");
 
     let repo = repo(repo_path)?;
 
-    let diff = get_diff_raw(&repo, &file_path, false, false)?;
+    let diff =
+        get_diff_raw(&repo, &file_path, false, false, options)?;
+
+    if ignores_whitespace(options) {
+        let lines = hunk_changes(&diff, hunk_hash)?;
+        return stage_lines(repo_path, &file_path, &lines, options);
+    }
 
     let mut opt = ApplyOptions::new();
     opt.hunk_callback(|hunk| {
@@ -37,12 +48,19 @@ pub fn reset_hunk(
     repo_path: &str,
     file_path: String,
     hunk_hash: u64,
+    options: Option<DiffOptions>,
 ) -> Result<()> {
     scope_time!("reset_hunk");
 
     let repo = repo(repo_path)?;
 
-    let diff = get_diff_raw(&repo, &file_path, false, false)?;
+    let diff =
+        get_diff_raw(&repo, &file_path, false, false, options)?;
+
+    if ignores_whitespace(options) {
+        let lines = hunk_changes(&diff, hunk_hash)?;
+        return reset_lines(repo_path, &file_path, &lines, options);
+    }
 
     let hunk_index = find_hunk_index(&diff, hunk_hash);
     if let Some(hunk_index) = hunk_index {
@@ -54,7 +72,8 @@ pub fn reset_hunk(
             res
         });
 
-        let diff = get_diff_raw(&repo, &file_path, false, true)?;
+        let diff =
+            get_diff_raw(&repo, &file_path, false, true, options)?;
 
         repo.apply(&diff, ApplyLocation::WorkDir, Some(&mut opt))?;
 
@@ -90,17 +109,60 @@ fn find_hunk_index(diff: &Diff, hunk_hash: u64) -> Option<usize> {
     }
 }
 
+/// a patch ignoring whitespace does not apply: its context lines can
+/// differ from the file, so hunks are applied line by line instead
+fn ignores_whitespace(options: Option<DiffOptions>) -> bool {
+    options.map_or(false, |options| options.ignores_whitespace())
+}
+
+/// positions of the added and deleted lines of the hunk `hunk_hash`
+fn hunk_changes(
+    diff: &Diff,
+    hunk_hash: u64,
+) -> Result<Vec<DiffLinePosition>> {
+    let mut lines = Vec::new();
+
+    diff.foreach(
+        &mut |_, _| true,
+        None,
+        None,
+        Some(&mut |_, hunk, line| {
+            let in_hunk = hunk.map_or(false, |hunk| {
+                hash(&HunkHeader::from(hunk)) == hunk_hash
+            });
+            if in_hunk && matches!(line.origin(), '+' | '-') {
+                lines.push(DiffLinePosition::from(&line));
+            }
+            true
+        }),
+    )?;
+
+    if lines.is_empty() {
+        Err(Error::Generic("hunk not found".to_string()))
+    } else {
+        Ok(lines)
+    }
+}
+
 ///
 pub fn unstage_hunk(
     repo_path: &str,
     file_path: String,
     hunk_hash: u64,
+    options: Option<DiffOptions>,
 ) -> Result<bool> {
     scope_time!("revert_hunk");
 
     let repo = repo(repo_path)?;
 
-    let diff = get_diff_raw(&repo, &file_path, true, false)?;
+    let diff = get_diff_raw(&repo, &file_path, true, false, options)?;
+
+    if ignores_whitespace(options) {
+        let lines = hunk_changes(&diff, hunk_hash)?;
+        unstage_lines(repo_path, &file_path, &lines, options)?;
+        return Ok(true);
+    }
+
     let diff_count_positive = diff.deltas().len();
 
     let hunk_index = find_hunk_index(&diff, hunk_hash);
@@ -108,7 +170,7 @@ pub fn unstage_hunk(
         return Err(Error::Generic("hunk not found".to_string()));
     }
 
-    let diff = get_diff_raw(&repo, &file_path, true, true)?;
+    let diff = get_diff_raw(&repo, &file_path, true, true, options)?;
 
     assert_eq!(diff.deltas().len(), diff_count_positive);
 
@@ -135,18 +197,311 @@ pub fn unstage_hunk(
     Ok(count == 1)
 }
 
+/// stages only the selected `lines` of the workdir diff of `file_path`
+pub fn stage_lines(
+    repo_path: &str,
+    file_path: &str,
+    lines: &[DiffLinePosition],
+    options: Option<DiffOptions>,
+) -> Result<()> {
+    scope_time!("stage_lines");
+
+    let repo = repo(repo_path)?;
+
+    let index = index_content(&repo, file_path)?.unwrap_or_default();
+    let workdir = workdir_content(&repo, file_path)?;
+
+    let content =
+        apply_selection(&index, &workdir, lines, false, options)?;
+
+    write_index(&repo, file_path, &content)
+}
+
+/// removes only the selected `lines` of the staged diff of `file_path`
+/// from the index
+pub fn unstage_lines(
+    repo_path: &str,
+    file_path: &str,
+    lines: &[DiffLinePosition],
+    options: Option<DiffOptions>,
+) -> Result<()> {
+    scope_time!("unstage_lines");
+
+    let repo = repo(repo_path)?;
+
+    let head = head_content(&repo, file_path)?.unwrap_or_default();
+    let index =
+        index_content(&repo, file_path)?.ok_or_else(|| {
+            Error::Generic("file not in index".to_string())
+        })?;
+
+    let content =
+        apply_selection(&index, &head, lines, true, options)?;
+
+    write_index(&repo, file_path, &content)
+}
+
+/// reverts only the selected `lines` of the workdir diff of `file_path`
+/// in the workdir
+pub fn reset_lines(
+    repo_path: &str,
+    file_path: &str,
+    lines: &[DiffLinePosition],
+    options: Option<DiffOptions>,
+) -> Result<()> {
+    scope_time!("reset_lines");
+
+    let repo = repo(repo_path)?;
+
+    let index = index_content(&repo, file_path)?.unwrap_or_default();
+    let workdir = workdir_content(&repo, file_path)?;
+
+    let content =
+        apply_selection(&workdir, &index, lines, true, options)?;
+
+    write_workdir(&repo, file_path, &content)
+}
+
+/// builds `old` with only those changes towards `new` applied that are
+/// in `lines`, which refer to the diff of `new` to `old` if `reverse`
+fn apply_selection(
+    old: &[u8],
+    new: &[u8],
+    lines: &[DiffLinePosition],
+    reverse: bool,
+    options: Option<DiffOptions>,
+) -> Result<Vec<u8>> {
+    let is_selected = |line: &git2::DiffLine| {
+        let pos = DiffLinePosition::from(line);
+        let pos = if reverse {
+            DiffLinePosition {
+                old_lineno: pos.new_lineno,
+                new_lineno: pos.old_lineno,
+            }
+        } else {
+            pos
+        };
+        lines.contains(&pos)
+    };
+
+    let old_lines: Vec<&[u8]> =
+        old.split_inclusive(|b| *b == b'\n').collect();
+    let mut old_idx = 0_usize;
+    let mut res = Vec::with_capacity(new.len());
+
+    let mut opt = git2::DiffOptions::new();
+    if let Some(options) = options {
+        options.apply(&mut opt);
+    }
+    let patch =
+        Patch::from_buffers(old, None, new, None, Some(&mut opt))?;
+
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_idx)?;
+
+        // pure insertions start after `old_start`
+        let start = if hunk.old_lines() == 0 {
+            hunk.old_start()
+        } else {
+            hunk.old_start().saturating_sub(1)
+        };
+        copy_lines(
+            &mut res,
+            &old_lines,
+            &mut old_idx,
+            start as usize,
+        );
+
+        for line_idx in 0..line_count {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+            let old_lineno =
+                line.old_lineno().unwrap_or_default() as usize;
+
+            match line.origin() {
+                ' ' => copy_lines(
+                    &mut res,
+                    &old_lines,
+                    &mut old_idx,
+                    old_lineno,
+                ),
+                '-' => {
+                    copy_lines(
+                        &mut res,
+                        &old_lines,
+                        &mut old_idx,
+                        old_lineno.saturating_sub(1),
+                    );
+                    if is_selected(&line) {
+                        old_idx = old_lineno;
+                    } else {
+                        copy_lines(
+                            &mut res,
+                            &old_lines,
+                            &mut old_idx,
+                            old_lineno,
+                        );
+                    }
+                }
+                '+' if is_selected(&line) => {
+                    // old file might have lacked the newline at eof
+                    if !res.is_empty() && !res.ends_with(b"\n") {
+                        res.push(b'\n');
+                    }
+                    res.extend_from_slice(line.content());
+                }
+                _ => (),
+            }
+        }
+    }
+
+    copy_lines(&mut res, &old_lines, &mut old_idx, old_lines.len());
+
+    Ok(res)
+}
+
+/// copies the old lines up to (excluding) index `until`
+fn copy_lines(
+    res: &mut Vec<u8>,
+    old_lines: &[&[u8]],
+    old_idx: &mut usize,
+    until: usize,
+) {
+    let until = until.min(old_lines.len());
+    while *old_idx < until {
+        res.extend_from_slice(old_lines[*old_idx]);
+        *old_idx += 1;
+    }
+}
+
+fn index_content(
+    repo: &Repository,
+    file_path: &str,
+) -> Result<Option<Vec<u8>>> {
+    let index = repo.index()?;
+
+    match index.get_path(Path::new(file_path), 0) {
+        Some(entry) => {
+            Ok(Some(repo.find_blob(entry.id)?.content().to_vec()))
+        }
+        None => Ok(None),
+    }
+}
+
+/// content of the workdir file as it would be staged, run through the
+/// clean filters (e.g. `core.autocrlf`)
+fn workdir_content(
+    repo: &Repository,
+    file_path: &str,
+) -> Result<Vec<u8>> {
+    let id = repo.blob_path(&work_dir(repo).join(file_path))?;
+
+    Ok(repo.find_blob(id)?.content().to_vec())
+}
+
+/// writes `content` to the workdir file, run through the smudge
+/// filters (e.g. `core.autocrlf`) by checking it out
+fn write_workdir(
+    repo: &Repository,
+    file_path: &str,
+    content: &[u8],
+) -> Result<()> {
+    let mut entry =
+        match repo.index()?.get_path(Path::new(file_path), 0) {
+            Some(entry) => entry,
+            None => {
+                fs::write(work_dir(repo).join(file_path), content)?;
+                return Ok(());
+            }
+        };
+
+    entry.id = repo.blob(content)?;
+    entry.file_size = content.len() as u32;
+
+    // a detached index, the staged version stays untouched
+    let mut index = Index::new()?;
+    index.add(&entry)?;
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.path(file_path).force().update_index(false);
+    repo.checkout_index(Some(&mut index), Some(&mut checkout))?;
+
+    Ok(())
+}
+
+fn head_content(
+    repo: &Repository,
+    file_path: &str,
+) -> Result<Option<Vec<u8>>> {
+    let head = match get_head_repo(repo) {
+        Ok(head) => head,
+        Err(_) => return Ok(None),
+    };
+
+    let tree = repo.find_commit(head.into())?.tree()?;
+
+    match tree.get_path(Path::new(file_path)) {
+        Ok(entry) => {
+            Ok(Some(repo.find_blob(entry.id())?.content().to_vec()))
+        }
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_index(
+    repo: &Repository,
+    file_path: &str,
+    content: &[u8],
+) -> Result<()> {
+    let mut index = repo.index()?;
+
+    let mut entry = index
+        .get_path(Path::new(file_path), 0)
+        .unwrap_or_else(|| IndexEntry {
+            ctime: IndexTime::new(0, 0),
+            mtime: IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode: 0o100_644,
+            uid: 0,
+            gid: 0,
+            file_size: 0,
+            id: git2::Oid::zero(),
+            flags: 0,
+            flags_extended: 0,
+            path: file_path.as_bytes().to_vec(),
+        });
+
+    // the stat data no longer matches the workdir file
+    entry.ctime = IndexTime::new(0, 0);
+    entry.mtime = IndexTime::new(0, 0);
+
+    index.add_frombuffer(&entry, content)?;
+    index.write()?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         error::Result,
-        sync::{diff::get_diff, tests::repo_init_empty},
+        sync::{
+            commit,
+            diff::{
+                get_diff, DiffLineType, FileDiff, IgnoreWhitespace,
+            },
+            stage_add_file,
+            tests::{repo_init, repo_init_empty},
+        },
     };
     use std::{
         fs::{self, File},
         io::Write,
         path::Path,
     };
+    use tempfile::TempDir;
 
     #[test]
     fn reset_untracked_file_which_will_not_find_hunk() -> Result<()> {
@@ -164,15 +519,261 @@ mod tests {
             sub_path.to_str().unwrap(),
             String::from(file_path.to_str().unwrap()),
             false,
+            None,
         )?;
 
         assert!(reset_hunk(
             repo_path,
             String::from(file_path.to_str().unwrap()),
             diff.hunks[0].header_hash,
+            None,
         )
         .is_err());
 
         Ok(())
     }
+
+    fn line_position(
+        diff: &FileDiff,
+        content: &str,
+    ) -> DiffLinePosition {
+        diff.hunks
+            .iter()
+            .flat_map(|hunk| hunk.lines.iter())
+            .find(|line| line.content == content)
+            .unwrap()
+            .position
+    }
+
+    fn lines_changed(diff: &FileDiff) -> Vec<String> {
+        diff.hunks
+            .iter()
+            .flat_map(|hunk| hunk.lines.iter())
+            .filter(|line| {
+                matches!(
+                    line.line_type,
+                    DiffLineType::Add | DiffLineType::Delete
+                )
+            })
+            .map(|line| line.content.clone())
+            .collect()
+    }
+
+    fn repo_with_changes() -> Result<(TempDir, String)> {
+        let (td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path =
+            root.as_os_str().to_str().unwrap().to_string();
+
+        File::create(&root.join("foo.txt"))?
+            .write_all(b"1\n2\n3\n4\n")?;
+        stage_add_file(&repo_path, Path::new("foo.txt"))?;
+        commit(&repo_path, "commit")?;
+
+        File::create(&root.join("foo.txt"))?
+            .write_all(b"1\nx\n2\n4\ny\n")?;
+
+        Ok((td, repo_path))
+    }
+
+    #[test]
+    fn test_stage_lines() -> Result<()> {
+        let (_td, repo_path) = repo_with_changes()?;
+
+        let diff =
+            get_diff(&repo_path, "foo.txt".to_string(), false, None)?;
+        let lines = vec![
+            line_position(&diff, "x\n"),
+            line_position(&diff, "3\n"),
+        ];
+
+        stage_lines(&repo_path, "foo.txt", &lines, None)?;
+
+        let staged =
+            get_diff(&repo_path, "foo.txt".to_string(), true, None)?;
+        assert_eq!(lines_changed(&staged), vec!["x\n", "3\n"]);
+
+        let diff =
+            get_diff(&repo_path, "foo.txt".to_string(), false, None)?;
+        assert_eq!(lines_changed(&diff), vec!["y\n"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unstage_lines() -> Result<()> {
+        let (_td, repo_path) = repo_with_changes()?;
+
+        stage_add_file(&repo_path, Path::new("foo.txt"))?;
+
+        let staged =
+            get_diff(&repo_path, "foo.txt".to_string(), true, None)?;
+        let lines = vec![line_position(&staged, "y\n")];
+
+        unstage_lines(&repo_path, "foo.txt", &lines, None)?;
+
+        let staged =
+            get_diff(&repo_path, "foo.txt".to_string(), true, None)?;
+        assert_eq!(lines_changed(&staged), vec!["x\n", "3\n"]);
+
+        let diff =
+            get_diff(&repo_path, "foo.txt".to_string(), false, None)?;
+        assert_eq!(lines_changed(&diff), vec!["y\n"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_lines() -> Result<()> {
+        let (_td, repo_path) = repo_with_changes()?;
+
+        let diff =
+            get_diff(&repo_path, "foo.txt".to_string(), false, None)?;
+        let lines = vec![
+            line_position(&diff, "3\n"),
+            line_position(&diff, "y\n"),
+        ];
+
+        reset_lines(&repo_path, "foo.txt", &lines, None)?;
+
+        assert_eq!(
+            fs::read_to_string(
+                Path::new(&repo_path).join("foo.txt")
+            )?,
+            "1\nx\n2\n3\n4\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stage_lines_autocrlf() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        repo.config()?.set_bool("core.autocrlf", true)?;
+
+        File::create(&root.join("foo.txt"))?.write_all(b"1\n2\n")?;
+        stage_add_file(repo_path, Path::new("foo.txt"))?;
+        commit(repo_path, "commit")?;
+
+        File::create(&root.join("foo.txt"))?
+            .write_all(b"1\r\n2\r\nx\r\ny\r\n")?;
+
+        let diff =
+            get_diff(repo_path, "foo.txt".to_string(), false, None)?;
+        let lines = vec![line_position(&diff, "x\n")];
+
+        stage_lines(repo_path, "foo.txt", &lines, None)?;
+
+        // line endings were converted, only the line is staged
+        let staged =
+            get_diff(repo_path, "foo.txt".to_string(), true, None)?;
+        assert_eq!(lines_changed(&staged), vec!["x\n"]);
+        let diff =
+            get_diff(repo_path, "foo.txt".to_string(), false, None)?;
+        assert_eq!(lines_changed(&diff), vec!["y\n"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stage_hunk_ignoring_whitespace() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("foo.txt"))?
+            .write_all(b"1\n2\n3\n4\n")?;
+        stage_add_file(repo_path, Path::new("foo.txt"))?;
+        commit(repo_path, "commit")?;
+
+        File::create(&root.join("foo.txt"))?
+            .write_all(b"1\n 2\nx\n3\n4\n")?;
+
+        let options = DiffOptions {
+            ignore_whitespace: IgnoreWhitespace::All,
+            ..DiffOptions::default()
+        };
+        let diff = get_diff(
+            repo_path,
+            "foo.txt".to_string(),
+            false,
+            Some(options),
+        )?;
+        assert_eq!(lines_changed(&diff), vec!["x\n"]);
+
+        stage_hunk(
+            repo_path,
+            "foo.txt".to_string(),
+            diff.hunks[0].header_hash,
+            Some(options),
+        )?;
+
+        // the hidden whitespace change stays unstaged
+        let staged =
+            get_diff(repo_path, "foo.txt".to_string(), true, None)?;
+        assert_eq!(lines_changed(&staged), vec!["x\n"]);
+        let diff =
+            get_diff(repo_path, "foo.txt".to_string(), false, None)?;
+        assert_eq!(lines_changed(&diff), vec!["2\n", " 2\n"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_lines_autocrlf() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        repo.config()?.set_bool("core.autocrlf", true)?;
+
+        File::create(&root.join("foo.txt"))?
+            .write_all(b"1\n2\n3\n")?;
+        stage_add_file(repo_path, Path::new("foo.txt"))?;
+        commit(repo_path, "commit")?;
+
+        File::create(&root.join("foo.txt"))?
+            .write_all(b"1\r\n3\r\ny\r\n")?;
+
+        let diff =
+            get_diff(repo_path, "foo.txt".to_string(), false, None)?;
+        let lines = vec![line_position(&diff, "2\n")];
+
+        reset_lines(repo_path, "foo.txt", &lines, None)?;
+
+        // the line is back with the line endings of the file
+        assert_eq!(
+            fs::read_to_string(root.join("foo.txt"))?,
+            "1\r\n2\r\n3\r\ny\r\n"
+        );
+        let diff =
+            get_diff(repo_path, "foo.txt".to_string(), false, None)?;
+        assert_eq!(lines_changed(&diff), vec!["y\n"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stage_lines_untracked() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("bar.txt"))?.write_all(b"a\nb\n")?;
+
+        let diff =
+            get_diff(repo_path, "bar.txt".to_string(), false, None)?;
+        let lines = vec![line_position(&diff, "b\n")];
+
+        stage_lines(repo_path, "bar.txt", &lines, None)?;
+
+        let staged =
+            get_diff(repo_path, "bar.txt".to_string(), true, None)?;
+        assert_eq!(lines_changed(&staged), vec!["b\n"]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,268 @@
+//! matching commits against a search query
+
+use crate::error::{Error, Result};
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use git2::Commit;
+use regex::{Regex, RegexBuilder};
+
+/// part of a commit the text of a `LogFilter` is searched in
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FilterField {
+    /// message, author and hash
+    All,
+    /// summary and body
+    Message,
+    /// author name and email
+    Author,
+    /// hash prefix
+    Hash,
+}
+
+#[derive(Debug, Clone)]
+enum TextMatcher {
+    /// lowercase, matched case insensitive
+    Substring(String),
+    Regex(Regex),
+}
+
+impl TextMatcher {
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Self::Substring(s) => text.to_lowercase().contains(s),
+            Self::Regex(re) => re.is_match(text),
+        }
+    }
+
+    fn is_hash_match(&self, hash: &str) -> bool {
+        match self {
+            Self::Substring(s) => hash.starts_with(s),
+            Self::Regex(re) => re.is_match(hash),
+        }
+    }
+}
+
+/// commits to keep when searching the log, parsed from a query like
+/// `author:jon date:2020-01-01..2020-02-01 /fix(es)?/`
+#[derive(Debug, Clone)]
+pub struct LogFilter {
+    query: String,
+    field: FilterField,
+    text: Option<TextMatcher>,
+    since: Option<i64>,
+    until: Option<i64>,
+}
+
+impl LogFilter {
+    /// parses a query: terms `date:FROM..TO` (`YYYY-MM-DD`, either
+    /// side optional) restrict the commit time, the remaining text
+    /// is searched for (case insensitive), in a single field if it
+    /// starts with `msg:`, `author:` or `hash:`, as regex if it
+    /// is enclosed in `/` (`^` and `$` match at line breaks, the
+    /// regex is taken verbatim)
+    pub fn parse(query: &str) -> Result<Self> {
+        let mut since = None;
+        let mut until = None;
+        let mut text = String::new();
+        let mut rest_start = 0;
+
+        for word in query.split_whitespace() {
+            if let Some(range) = word.strip_prefix("date:") {
+                let (from, to) = parse_date_range(range)?;
+                since = from;
+                until = to;
+
+                // cut the term out, keep the text around it as is
+                let start =
+                    word.as_ptr() as usize - query.as_ptr() as usize;
+                text.push_str(&query[rest_start..start]);
+                rest_start = start + word.len();
+            }
+        }
+        text.push_str(&query[rest_start..]);
+
+        let text = text.trim();
+        let (field, text) = [
+            ("msg:", FilterField::Message),
+            ("author:", FilterField::Author),
+            ("hash:", FilterField::Hash),
+        ]
+        .iter()
+        .find_map(|(prefix, field)| {
+            text.strip_prefix(prefix).map(|rest| (*field, rest))
+        })
+        .unwrap_or((FilterField::All, text));
+
+        let text = if text.is_empty() {
+            None
+        } else if text.len() > 1
+            && text.starts_with('/')
+            && text.ends_with('/')
+        {
+            let re = RegexBuilder::new(&text[1..text.len() - 1])
+                .case_insensitive(true)
+                .multi_line(true)
+                .build()
+                .map_err(|e| Error::Generic(e.to_string()))?;
+            Some(TextMatcher::Regex(re))
+        } else {
+            let words: Vec<_> = text.split_whitespace().collect();
+            Some(TextMatcher::Substring(
+                words.join(" ").to_lowercase(),
+            ))
+        };
+
+        Ok(Self {
+            query: String::from(query.trim()),
+            field,
+            text,
+            since,
+            until,
+        })
+    }
+
+    /// the query this was parsed from
+    pub fn query(&self) -> &str {
+        self.query.as_str()
+    }
+
+    ///
+    pub fn is_match(&self, commit: &Commit) -> bool {
+        let time = commit.time().seconds();
+        if self.since.map_or(false, |since| time < since)
+            || self.until.map_or(false, |until| time >= until)
+        {
+            return false;
+        }
+
+        let text = match &self.text {
+            Some(text) => text,
+            None => return true,
+        };
+
+        let in_message = || {
+            text.is_match(&String::from_utf8_lossy(
+                commit.message_bytes(),
+            ))
+        };
+        let in_author = || {
+            let author = commit.author();
+            text.is_match(&String::from_utf8_lossy(
+                author.name_bytes(),
+            )) || text.is_match(&String::from_utf8_lossy(
+                author.email_bytes(),
+            ))
+        };
+        let in_hash = || text.is_hash_match(&commit.id().to_string());
+
+        match self.field {
+            FilterField::All => {
+                in_message() || in_author() || in_hash()
+            }
+            FilterField::Message => in_message(),
+            FilterField::Author => in_author(),
+            FilterField::Hash => in_hash(),
+        }
+    }
+}
+
+/// start of `from` and end of `to` (local time)
+fn parse_date_range(
+    range: &str,
+) -> Result<(Option<i64>, Option<i64>)> {
+    let mut sides = range.splitn(2, "..");
+    let from = sides.next().unwrap_or_default();
+    let to = sides.next().unwrap_or(from);
+
+    let day_start = |s: &str, days: i64| -> Result<Option<i64>> {
+        if s.is_empty() {
+            return Ok(None);
+        }
+
+        let date = NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(
+            |_| Error::Generic(format!("invalid date '{}'", s)),
+        )? + Duration::days(days);
+
+        Ok(Local
+            .from_local_datetime(&date.and_hms(0, 0, 0))
+            .earliest()
+            .map(|d| d.timestamp()))
+    };
+
+    Ok((day_start(from, 0)?, day_start(to, 1)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{commit, stage_add_file, tests::repo_init};
+    use std::{fs::File, io::Write, path::Path};
+
+    #[test]
+    fn test_parse() {
+        let filter = LogFilter::parse("author:Jon  Doe").unwrap();
+        assert_eq!(filter.field, FilterField::Author);
+        assert!(matches!(
+            filter.text,
+            Some(TextMatcher::Substring(ref s)) if s == "jon doe"
+        ));
+
+        let filter =
+            LogFilter::parse("date:2020-01-01..2020-01-31 /fix/")
+                .unwrap();
+        assert_eq!(filter.field, FilterField::All);
+        assert!(matches!(filter.text, Some(TextMatcher::Regex(_))));
+        assert_eq!(
+            filter.until.unwrap() - filter.since.unwrap() > 0,
+            true
+        );
+
+        // whitespace in a regex is kept
+        let filter =
+            LogFilter::parse("/a  b\tc/ date:2020-01-01..").unwrap();
+        assert!(matches!(
+            filter.text,
+            Some(TextMatcher::Regex(ref re)) if re.as_str() == "a  b\tc"
+        ));
+        assert_eq!(filter.since.is_some(), true);
+
+        let filter = LogFilter::parse("date:2020-01-01..").unwrap();
+        assert_eq!(filter.since.is_some(), true);
+        assert_eq!(filter.until, None);
+        assert_eq!(filter.text.is_none(), true);
+
+        assert_eq!(LogFilter::parse("/(/").is_err(), true);
+        assert_eq!(LogFilter::parse("date:yesterday").is_err(), true);
+    }
+
+    #[test]
+    fn test_match() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("foo"))
+            .unwrap()
+            .write_all(b"a")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("foo")).unwrap();
+        let id =
+            commit(repo_path, "Fix the thing\n\nsome body").unwrap();
+        let c = repo.find_commit(id).unwrap();
+        let hash = id.to_string();
+
+        let is_match =
+            |q: &str| LogFilter::parse(q).unwrap().is_match(&c);
+
+        assert_eq!(is_match("fix"), true);
+        assert_eq!(is_match("BODY"), true);
+        assert_eq!(is_match("msg:name"), false);
+        assert_eq!(is_match("author:name"), true);
+        assert_eq!(is_match("author:email"), true);
+        assert_eq!(is_match(&format!("hash:{}", &hash[..6])), true);
+        assert_eq!(is_match(&format!("hash:{}", &hash[1..7])), false);
+        assert_eq!(is_match("/^fix .* thing$/"), true);
+        assert_eq!(is_match("/^thing/"), false);
+        assert_eq!(is_match("date:2000-01-01..2000-12-31"), false);
+        assert_eq!(is_match("date:2000-01-01.. fix"), true);
+    }
+}
@@ -0,0 +1,352 @@
+//! merging commits into `HEAD`
+
+use super::{utils::repo, CommitId};
+use crate::error::{Error, Result};
+use git2::{
+    build::CheckoutBuilder, Oid, Repository, RepositoryState,
+    ResetType,
+};
+use scopetime::scope_time;
+use std::fs;
+
+/// what happened when integrating a commit into `HEAD`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MergeOutcome {
+    /// `HEAD` already contains the commit
+    UpToDate,
+    /// `HEAD` was moved forward to the commit
+    FastForward,
+    /// a merge commit was created
+    Merged(CommitId),
+    /// index and workdir contain the merge result, `MERGE_HEAD` and
+    /// `MERGE_MSG` are set to commit it (see `commit`)
+    Staged,
+    /// merging stopped on conflicts, index and workdir contain the
+    /// conflict markers, `MERGE_HEAD` is set
+    Conflicts,
+}
+
+/// merges `commit` into the branch `HEAD` points to.
+/// fast-forwards if possible, creates a merge commit with `msg` otherwise.
+pub fn merge_commit(
+    repo_path: &str,
+    commit: CommitId,
+    msg: &str,
+) -> Result<MergeOutcome> {
+    scope_time!("merge_commit");
+
+    let repo = repo(repo_path)?;
+
+    merge_commit_repo(&repo, commit.into(), msg)
+}
+
+/// merges the branch `branch_ref` (e.g. `refs/heads/feature`) into
+/// `HEAD`. fast-forwards if possible unless `no_ff` is set, otherwise
+/// leaves the merge staged with a prepared message to commit it.
+pub fn merge_branch(
+    repo_path: &str,
+    branch_ref: &str,
+    no_ff: bool,
+) -> Result<MergeOutcome> {
+    scope_time!("merge_branch");
+
+    let repo = repo(repo_path)?;
+
+    if repo.state() != RepositoryState::Clean {
+        return Err(Error::Generic(String::from(
+            "another operation is in progress",
+        )));
+    }
+
+    let reference = repo.find_reference(branch_ref)?;
+    let annotated = repo.reference_to_annotated_commit(&reference)?;
+    let (analysis, _) = repo.merge_analysis(&[&annotated])?;
+
+    if analysis.is_up_to_date() {
+        return Ok(MergeOutcome::UpToDate);
+    }
+
+    if analysis.is_fast_forward() && !no_ff {
+        fast_forward(&repo, annotated.id())?;
+        return Ok(MergeOutcome::FastForward);
+    }
+
+    if !analysis.is_normal() && !analysis.is_fast_forward() {
+        return Err(Error::Generic(String::from("cannot merge")));
+    }
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe();
+
+    repo.merge(&[&annotated], None, Some(&mut checkout))?;
+
+    fs::write(
+        repo.path().join("MERGE_MSG"),
+        format!(
+            "Merge branch '{}'\n",
+            reference.shorthand().unwrap_or(branch_ref)
+        ),
+    )?;
+
+    let index = repo.index()?;
+
+    Ok(if index.has_conflicts() {
+        MergeOutcome::Conflicts
+    } else {
+        MergeOutcome::Staged
+    })
+}
+
+/// aborts the merge, cherry-pick or revert in progress, restoring
+/// index and workdir to `HEAD`
+pub fn abort_merge(repo_path: &str) -> Result<()> {
+    scope_time!("abort_merge");
+
+    let repo = repo(repo_path)?;
+
+    if !matches!(
+        repo.state(),
+        RepositoryState::Merge
+            | RepositoryState::CherryPick
+            | RepositoryState::Revert
+    ) {
+        return Err(Error::Generic(String::from(
+            "no merge in progress",
+        )));
+    }
+
+    let head = repo.head()?.peel_to_commit()?;
+    repo.reset(head.as_object(), ResetType::Hard, None)?;
+    repo.cleanup_state()?;
+
+    Ok(())
+}
+
+pub(crate) fn merge_commit_repo(
+    repo: &Repository,
+    commit: Oid,
+    msg: &str,
+) -> Result<MergeOutcome> {
+    let annotated = repo.find_annotated_commit(commit)?;
+    let (analysis, _) = repo.merge_analysis(&[&annotated])?;
+
+    if analysis.is_up_to_date() {
+        return Ok(MergeOutcome::UpToDate);
+    }
+
+    if analysis.is_fast_forward() {
+        fast_forward(repo, commit)?;
+        return Ok(MergeOutcome::FastForward);
+    }
+
+    if !analysis.is_normal() {
+        return Err(Error::Generic(String::from("cannot merge")));
+    }
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe();
+
+    repo.merge(&[&annotated], None, Some(&mut checkout))?;
+
+    let mut index = repo.index()?;
+
+    if index.has_conflicts() {
+        return Ok(MergeOutcome::Conflicts);
+    }
+
+    let signature = repo.signature()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let theirs = repo.find_commit(commit)?;
+
+    let id = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        msg,
+        &tree,
+        &[&head, &theirs],
+    )?;
+
+    repo.cleanup_state()?;
+
+    Ok(MergeOutcome::Merged(CommitId::new(id)))
+}
+
+/// moves the branch `HEAD` points to (or `HEAD` itself if detached)
+/// to `target` and updates index and workdir,
+/// fails without changes if local modifications would be overwritten
+pub(crate) fn fast_forward(
+    repo: &Repository,
+    target: Oid,
+) -> Result<()> {
+    let commit = repo.find_commit(target)?;
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe();
+
+    repo.checkout_tree(commit.as_object(), Some(&mut checkout))?;
+
+    let mut head = repo.head()?;
+    if head.is_branch() {
+        head.set_target(target, "fast-forward")?;
+    } else {
+        repo.set_head_detached(target)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, get_prepared_commit_msg,
+        tests::{repo_init, write_commit},
+    };
+
+    #[test]
+    fn test_fast_forward() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = repo.head().unwrap().target().unwrap();
+        let next = write_commit(repo_path, "a.txt", "a", "next");
+
+        repo.reset(
+            &repo.find_object(base, None).unwrap(),
+            git2::ResetType::Hard,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            merge_commit(repo_path, next, "").unwrap(),
+            MergeOutcome::FastForward
+        );
+        assert_eq!(repo.head().unwrap().target(), Some(next.into()));
+        assert_eq!(root.join("a.txt").exists(), true);
+
+        assert_eq!(
+            merge_commit(repo_path, next, "").unwrap(),
+            MergeOutcome::UpToDate
+        );
+    }
+
+    #[test]
+    fn test_merge_and_conflict() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = repo.head().unwrap().target().unwrap();
+        let theirs = write_commit(repo_path, "a.txt", "a", "theirs");
+        let conflicting =
+            write_commit(repo_path, "b.txt", "theirs", "conflicting");
+
+        repo.reset(
+            &repo.find_object(base, None).unwrap(),
+            git2::ResetType::Hard,
+            None,
+        )
+        .unwrap();
+        write_commit(repo_path, "b.txt", "ours", "ours");
+
+        let res = merge_commit(repo_path, theirs, "merge").unwrap();
+        assert!(matches!(res, MergeOutcome::Merged(_)));
+
+        let merge = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(merge.parent_count(), 2);
+        assert_eq!(merge.message(), Some("merge"));
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+
+        assert_eq!(
+            merge_commit(repo_path, conflicting, "merge").unwrap(),
+            MergeOutcome::Conflicts
+        );
+        assert_eq!(repo.state(), git2::RepositoryState::Merge);
+        let mut index = repo.index().unwrap();
+        index.read(true).unwrap();
+        assert_eq!(index.has_conflicts(), true);
+
+        abort_merge(repo_path).unwrap();
+
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        index.read(true).unwrap();
+        assert_eq!(index.has_conflicts(), false);
+        assert_eq!(
+            fs::read_to_string(root.join("b.txt")).unwrap(),
+            "ours"
+        );
+        assert!(abort_merge(repo_path).is_err());
+    }
+
+    #[test]
+    fn test_merge_branch() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &base, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        let theirs = write_commit(repo_path, "a.txt", "a", "theirs");
+        repo.set_head("refs/heads/master").unwrap();
+        repo.reset(base.as_object(), git2::ResetType::Hard, None)
+            .unwrap();
+
+        // fast-forward possible, but not wanted
+        assert_eq!(
+            merge_branch(repo_path, "refs/heads/feature", true)
+                .unwrap(),
+            MergeOutcome::Staged
+        );
+        assert_eq!(repo.state(), git2::RepositoryState::Merge);
+        assert_eq!(
+            get_prepared_commit_msg(repo_path).unwrap().unwrap(),
+            "Merge branch 'feature'\n"
+        );
+        assert!(root.join("a.txt").exists());
+
+        commit(repo_path, "merged").unwrap();
+
+        let merge = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(
+            merge.parent_ids().collect::<Vec<_>>(),
+            vec![base.id(), theirs.into()]
+        );
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        assert_eq!(
+            merge_branch(repo_path, "refs/heads/feature", false)
+                .unwrap(),
+            MergeOutcome::UpToDate
+        );
+    }
+
+    #[test]
+    fn test_merge_branch_fast_forward() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &base, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        let theirs = write_commit(repo_path, "a.txt", "a", "theirs");
+        repo.set_head("refs/heads/master").unwrap();
+        repo.reset(base.as_object(), git2::ResetType::Hard, None)
+            .unwrap();
+
+        assert_eq!(
+            merge_branch(repo_path, "refs/heads/feature", false)
+                .unwrap(),
+            MergeOutcome::FastForward
+        );
+        assert_eq!(
+            repo.head().unwrap().target(),
+            Some(theirs.into())
+        );
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+    }
+}
@@ -1,45 +1,111 @@
 //! sync git api
 
+mod blame;
 mod branch;
+mod branch_compare;
+mod cherry_pick;
 mod commit;
 mod commit_details;
 mod commit_files;
 mod commits_info;
+mod conflicts;
+mod cred;
 pub mod diff;
+mod file_history;
+mod graph;
 mod hooks;
 mod hunks;
 mod ignore;
+mod log_filter;
 mod logwalker;
+mod merge;
+mod pull;
+mod rebase;
+mod rebase_interactive;
+mod refs;
+mod remotes;
 mod reset;
 mod stash;
+mod state;
 pub mod status;
 mod tags;
 pub mod utils;
+mod word_diff;
 
-pub use branch::get_branch_name;
+pub use blame::{blame_file, BlameHunk, FileBlame};
+pub use branch::{
+    checkout_branch, create_branch, delete_branch, get_branch_name,
+    get_branch_upstream, get_branches_info, get_head_ref,
+    get_head_upstream, rename_branch, BranchInfo, UpstreamInfo,
+};
+pub use branch_compare::{compare_branch, BranchCompare};
+pub use cherry_pick::{cherry_pick, revert_commit, PickOutcome};
 pub use commit::amend;
-pub use commit_details::{get_commit_details, CommitDetails};
-pub use commit_files::get_commit_files;
+pub use commit_details::{
+    get_commit_details, CommitDetails, CommitMessage,
+};
+pub use commit_files::{get_commit_files, get_compare_files};
 pub use commits_info::{get_commits_info, CommitId, CommitInfo};
-pub use diff::get_diff_commit;
+pub use conflicts::{
+    get_conflict_file, get_conflict_version, mark_conflict_resolved,
+    resolve_conflict_file, resolve_conflict_hunk, ConflictFile,
+    ConflictHunk, ConflictSide,
+};
+pub use cred::{
+    extract_username_password, get_remote_url, BasicAuthCredential,
+    CredentialCache,
+};
+pub use diff::{
+    get_diff_commit, get_diff_commits, DiffAlgorithm, DiffOptions,
+    IgnoreWhitespace,
+};
+pub use file_history::{get_file_history, FileHistoryEntry};
+pub use graph::{CommitGraph, GraphCell, GraphRow};
 pub use hooks::{hooks_commit_msg, hooks_post_commit, HookResult};
-pub use hunks::{reset_hunk, stage_hunk, unstage_hunk};
+pub use hunks::{
+    reset_hunk, reset_lines, stage_hunk, stage_lines, unstage_hunk,
+    unstage_lines,
+};
 pub use ignore::add_to_ignore;
-pub use logwalker::LogWalker;
+pub use log_filter::{FilterField, LogFilter};
+pub(crate) use logwalker::resolve_walk_spec;
+pub use logwalker::{parse_walk_spec, LogWalkSpec, LogWalker};
+pub use merge::{
+    abort_merge, merge_branch, merge_commit, MergeOutcome,
+};
+pub use pull::{
+    get_pull_strategy, pull_upstream, PullOutcome, PullStrategy,
+};
+pub use rebase::{rebase_branch, RebaseOutcome};
+pub use rebase_interactive::{
+    get_rebase_status, get_rebase_todo, rebase_abort,
+    rebase_continue, rebase_interactive, RebaseAction, RebaseStatus,
+    RebaseStop, RebaseTodoItem,
+};
+pub use refs::{get_refs, RefInfo, RefKind, Refs};
+pub use remotes::{
+    fetch_remote, get_branch_remote, get_default_remote, get_remotes,
+    push_branch, FetchProgress, PushProgress, PushStage,
+};
 pub use reset::{reset_stage, reset_workdir};
 pub use stash::{get_stashes, stash_apply, stash_drop, stash_save};
+pub use state::{get_prepared_commit_msg, repo_state, RepoState};
 pub use tags::{get_tags, Tags};
 pub use utils::{
-    commit, commit_new, get_head, is_bare_repo, is_repo,
-    stage_add_all, stage_add_file, stage_addremoved,
+    commit, commit_new, get_config_string, get_head, is_bare_repo,
+    is_repo, stage_add_all, stage_add_file, stage_addremoved,
 };
 
 #[cfg(test)]
 mod tests {
-    use super::status::{get_status, StatusType};
+    use super::{
+        commit, stage_add_file,
+        status::{get_status, StatusType},
+        CommitId,
+    };
     use crate::error::Result;
     use git2::Repository;
-    use std::process::Command;
+    use std::{fs::File, io::Write, path::Path, process::Command};
     use tempfile::TempDir;
 
     ///
@@ -54,6 +120,13 @@ mod tests {
         Ok((td, repo))
     }
 
+    ///
+    pub fn repo_init_bare() -> Result<(TempDir, Repository)> {
+        let td = TempDir::new()?;
+        let repo = Repository::init_bare(td.path())?;
+        Ok((td, repo))
+    }
+
     ///
     pub fn repo_init() -> Result<(TempDir, Repository)> {
         let td = TempDir::new()?;
@@ -80,6 +153,21 @@ mod tests {
         Ok((td, repo))
     }
 
+    /// writes `content` to `file`, stages and commits it with `msg`
+    pub fn write_commit(
+        repo_path: &str,
+        file: &str,
+        content: &str,
+        msg: &str,
+    ) -> CommitId {
+        File::create(&Path::new(repo_path).join(file))
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+        stage_add_file(repo_path, Path::new(file)).unwrap();
+        CommitId::new(commit(repo_path, msg).unwrap())
+    }
+
     /// helper returning amount of files with changes in the (wd,stage)
     pub fn get_statuses(repo_path: &str) -> (usize, usize) {
         (
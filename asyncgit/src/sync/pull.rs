@@ -0,0 +1,372 @@
+//! integrating the upstream into the current branch
+
+use super::{
+    merge::{merge_commit_repo, MergeOutcome},
+    rebase::{rebase_branch_repo, RebaseOutcome},
+    utils::repo,
+};
+use crate::error::{Error, Result};
+use git2::{Config, Repository};
+use scopetime::scope_time;
+
+/// how to integrate the upstream if fast-forwarding is not possible
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PullStrategy {
+    /// create a merge commit
+    Merge,
+    /// rebase the local commits onto the upstream
+    Rebase,
+}
+
+/// what happened when pulling
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PullOutcome {
+    /// nothing new on the upstream
+    UpToDate,
+    /// branch was moved forward to the upstream
+    FastForward,
+    /// upstream was merged
+    Merged,
+    /// local commits were rebased onto the upstream
+    Rebased,
+    /// merge or rebase stopped on conflicts and is still in progress
+    Conflicts,
+}
+
+/// strategy configured via `branch.<name>.rebase` of the current
+/// branch or else `pull.rebase`
+pub fn get_pull_strategy(repo_path: &str) -> Result<PullStrategy> {
+    scope_time!("get_pull_strategy");
+
+    let repo = repo(repo_path)?;
+    let config = repo.config()?;
+
+    let branch_key = repo
+        .head()
+        .ok()
+        .filter(git2::Reference::is_branch)
+        .and_then(|head| {
+            head.shorthand()
+                .map(|name| format!("branch.{}.rebase", name))
+        });
+
+    let branch_rebase = match branch_key {
+        Some(key) => rebase_config(&config, &key)?,
+        None => None,
+    };
+    let rebase = match branch_rebase {
+        Some(rebase) => rebase,
+        None => {
+            rebase_config(&config, "pull.rebase")?.unwrap_or_default()
+        }
+    };
+
+    Ok(if rebase {
+        PullStrategy::Rebase
+    } else {
+        PullStrategy::Merge
+    })
+}
+
+/// parses `key` like git parses `pull.rebase`, `None` if unset
+fn rebase_config(config: &Config, key: &str) -> Result<Option<bool>> {
+    let value = match config.get_string(key) {
+        Ok(value) => value,
+        Err(_) => return Ok(None),
+    };
+
+    if let Ok(rebase) = config.get_bool(key) {
+        return Ok(Some(rebase));
+    }
+
+    match value.as_str() {
+        "merges" | "m" | "interactive" | "i" => Ok(Some(true)),
+        _ => Err(Error::Generic(format!(
+            "invalid value for {}: {}",
+            key, value
+        ))),
+    }
+}
+
+/// integrates the (already fetched) upstream of `HEAD` into it.
+/// fast-forwards if possible and otherwise uses `strategy`.
+pub fn pull_upstream(
+    repo_path: &str,
+    strategy: PullStrategy,
+) -> Result<PullOutcome> {
+    scope_time!("pull_upstream");
+
+    let repo = repo(repo_path)?;
+
+    let (upstream_name, upstream) = get_head_upstream_commit(&repo)?;
+
+    let annotated = repo.find_annotated_commit(upstream)?;
+    let (analysis, _) = repo.merge_analysis(&[&annotated])?;
+
+    if analysis.is_up_to_date() {
+        return Ok(PullOutcome::UpToDate);
+    }
+
+    if analysis.is_fast_forward() || strategy == PullStrategy::Merge {
+        let msg = format!("Merge branch '{}'", upstream_name);
+
+        return Ok(match merge_commit_repo(&repo, upstream, &msg)? {
+            MergeOutcome::UpToDate => PullOutcome::UpToDate,
+            MergeOutcome::FastForward => PullOutcome::FastForward,
+            MergeOutcome::Conflicts => PullOutcome::Conflicts,
+            // `merge_commit_repo` commits the merge itself, it never
+            // leaves it staged
+            _ => PullOutcome::Merged,
+        });
+    }
+
+    Ok(match rebase_branch_repo(&repo, upstream)? {
+        RebaseOutcome::Finished => PullOutcome::Rebased,
+        // only interactive rebases stop to edit
+        RebaseOutcome::Conflicts | RebaseOutcome::Edit(_) => {
+            PullOutcome::Conflicts
+        }
+    })
+}
+
+fn get_head_upstream_commit(
+    repo: &Repository,
+) -> Result<(String, git2::Oid)> {
+    let head = repo.head()?;
+
+    if !head.is_branch() {
+        return Err(Error::NoHead);
+    }
+
+    let head_ref = head.name().unwrap_or_default();
+    let upstream_ref = repo.branch_upstream_name(head_ref)?;
+    let upstream_ref = upstream_ref.as_str().unwrap_or_default();
+
+    let upstream = repo.find_reference(upstream_ref)?;
+    let name = upstream.shorthand().unwrap_or_default().to_string();
+    let commit = upstream.peel_to_commit()?;
+
+    Ok((name, commit.id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        fetch_remote, push_branch,
+        tests::{repo_init, repo_init_bare, write_commit},
+    };
+    use git2::RepositoryState;
+    use tempfile::TempDir;
+
+    fn get_path(repo: &Repository) -> &str {
+        repo.path().parent().unwrap().as_os_str().to_str().unwrap()
+    }
+
+    fn push(repo: &Repository) {
+        push_branch(
+            get_path(repo),
+            "origin",
+            "refs/heads/master",
+            false,
+            None,
+            |_| (),
+        )
+        .unwrap();
+    }
+
+    /// returns (remote, ours, theirs) where ours and theirs are clones
+    /// of remote tracking `origin/master`
+    fn setup() -> (
+        (TempDir, Repository),
+        (TempDir, Repository),
+        (TempDir, Repository),
+    ) {
+        let ours = repo_init().unwrap();
+        let remote = repo_init_bare().unwrap();
+        let remote_path = remote.0.path().to_str().unwrap();
+
+        ours.1.remote("origin", remote_path).unwrap();
+        push(&ours.1);
+        fetch_remote(get_path(&ours.1), "origin", None, |_| ())
+            .unwrap();
+        ours.1
+            .find_branch("master", git2::BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("origin/master"))
+            .unwrap();
+
+        let td = TempDir::new().unwrap();
+        let theirs =
+            Repository::clone(remote_path, td.path()).unwrap();
+        {
+            let mut config = theirs.config().unwrap();
+            config.set_str("user.name", "name").unwrap();
+            config.set_str("user.email", "email").unwrap();
+        }
+
+        (remote, ours, (td, theirs))
+    }
+
+    #[test]
+    fn test_strategy() {
+        let (_td, repo) = repo_init().unwrap();
+        let repo_path = get_path(&repo);
+
+        assert_eq!(
+            get_pull_strategy(repo_path).unwrap(),
+            PullStrategy::Merge
+        );
+
+        repo.config()
+            .unwrap()
+            .set_bool("pull.rebase", true)
+            .unwrap();
+        assert_eq!(
+            get_pull_strategy(repo_path).unwrap(),
+            PullStrategy::Rebase
+        );
+
+        repo.config()
+            .unwrap()
+            .set_str("pull.rebase", "merges")
+            .unwrap();
+        assert_eq!(
+            get_pull_strategy(repo_path).unwrap(),
+            PullStrategy::Rebase
+        );
+
+        for value in &["0", "False"] {
+            repo.config()
+                .unwrap()
+                .set_str("pull.rebase", value)
+                .unwrap();
+            assert_eq!(
+                get_pull_strategy(repo_path).unwrap(),
+                PullStrategy::Merge
+            );
+        }
+
+        repo.config()
+            .unwrap()
+            .set_str("pull.rebase", "nonsense")
+            .unwrap();
+        assert!(get_pull_strategy(repo_path).is_err());
+    }
+
+    #[test]
+    fn test_strategy_branch_override() {
+        let (_td, repo) = repo_init().unwrap();
+        let repo_path = get_path(&repo);
+
+        repo.config()
+            .unwrap()
+            .set_bool("pull.rebase", true)
+            .unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("branch.master.rebase", "false")
+            .unwrap();
+        assert_eq!(
+            get_pull_strategy(repo_path).unwrap(),
+            PullStrategy::Merge
+        );
+
+        repo.config()
+            .unwrap()
+            .set_bool("pull.rebase", false)
+            .unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("branch.master.rebase", "interactive")
+            .unwrap();
+        assert_eq!(
+            get_pull_strategy(repo_path).unwrap(),
+            PullStrategy::Rebase
+        );
+    }
+
+    #[test]
+    fn test_pull_fast_forward() {
+        let (_remote, (_td1, ours), (_td2, theirs)) = setup();
+        let ours_path = get_path(&ours);
+
+        assert_eq!(
+            pull_upstream(ours_path, PullStrategy::Merge).unwrap(),
+            PullOutcome::UpToDate
+        );
+
+        write_commit(get_path(&theirs), "a.txt", "a", "a.txt");
+        push(&theirs);
+
+        fetch_remote(ours_path, "origin", None, |_| ()).unwrap();
+        assert_eq!(
+            pull_upstream(ours_path, PullStrategy::Rebase).unwrap(),
+            PullOutcome::FastForward
+        );
+        assert_eq!(
+            ours.head().unwrap().target(),
+            theirs.head().unwrap().target()
+        );
+    }
+
+    #[test]
+    fn test_pull_merge_and_rebase() {
+        let (_remote, (_td1, ours), (_td2, theirs)) = setup();
+        let ours_path = get_path(&ours);
+
+        write_commit(get_path(&theirs), "a.txt", "a", "a.txt");
+        push(&theirs);
+        write_commit(get_path(&ours), "b.txt", "b", "b.txt");
+        fetch_remote(ours_path, "origin", None, |_| ()).unwrap();
+
+        let head_before = ours.head().unwrap().target().unwrap();
+
+        assert_eq!(
+            pull_upstream(ours_path, PullStrategy::Rebase).unwrap(),
+            PullOutcome::Rebased
+        );
+        let head = ours.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.parent_count(), 1);
+        assert_eq!(
+            Some(head.parent_id(0).unwrap()),
+            theirs.head().unwrap().target()
+        );
+
+        // undo the rebase and merge instead
+        ours.reset(
+            &ours.find_object(head_before, None).unwrap(),
+            git2::ResetType::Hard,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            pull_upstream(ours_path, PullStrategy::Merge).unwrap(),
+            PullOutcome::Merged
+        );
+        let head = ours.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.parent_count(), 2);
+        assert_eq!(
+            head.message(),
+            Some("Merge branch 'origin/master'")
+        );
+    }
+
+    #[test]
+    fn test_pull_conflict() {
+        let (_remote, (_td1, ours), (_td2, theirs)) = setup();
+        let ours_path = get_path(&ours);
+
+        write_commit(get_path(&theirs), "a.txt", "theirs", "a.txt");
+        push(&theirs);
+        write_commit(get_path(&ours), "a.txt", "ours", "a.txt");
+        fetch_remote(ours_path, "origin", None, |_| ()).unwrap();
+
+        assert_eq!(
+            pull_upstream(ours_path, PullStrategy::Merge).unwrap(),
+            PullOutcome::Conflicts
+        );
+        assert_eq!(ours.state(), RepositoryState::Merge);
+    }
+}
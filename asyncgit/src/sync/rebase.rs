@@ -0,0 +1,153 @@
+//! rebasing the current branch
+
+use super::{utils::repo, CommitId};
+use crate::error::Result;
+use git2::{ErrorCode, Oid, Rebase, RebaseOptions, Repository};
+use scopetime::scope_time;
+
+/// what happened when rebasing `HEAD`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RebaseOutcome {
+    /// all commits were replayed
+    Finished,
+    /// replaying a commit conflicted, the rebase stays in progress
+    /// with the conflicts in index and workdir
+    Conflicts,
+    /// stopped after the commit to `edit` it (interactive rebases)
+    Edit(CommitId),
+}
+
+/// rebases the branch `HEAD` points to onto `onto`
+pub fn rebase_branch(
+    repo_path: &str,
+    onto: CommitId,
+) -> Result<RebaseOutcome> {
+    scope_time!("rebase_branch");
+
+    let repo = repo(repo_path)?;
+
+    rebase_branch_repo(&repo, onto.into())
+}
+
+pub(crate) fn rebase_branch_repo(
+    repo: &Repository,
+    onto: Oid,
+) -> Result<RebaseOutcome> {
+    let onto = repo.find_annotated_commit(onto)?;
+
+    let mut options = RebaseOptions::new();
+    let mut rebase =
+        repo.rebase(None, Some(&onto), None, Some(&mut options))?;
+
+    run_rebase(repo, &mut rebase)
+}
+
+/// commits the (resolved) current operation of a rebase stopped on
+/// conflicts and replays the rest
+pub(crate) fn continue_rebase_repo(
+    repo: &Repository,
+) -> Result<RebaseOutcome> {
+    let mut rebase = repo.open_rebase(None)?;
+
+    commit_operation(repo, &mut rebase)?;
+
+    run_rebase(repo, &mut rebase)
+}
+
+fn run_rebase(
+    repo: &Repository,
+    rebase: &mut Rebase,
+) -> Result<RebaseOutcome> {
+    let signature = repo.signature()?;
+
+    while let Some(op) = rebase.next() {
+        op?;
+
+        if repo.index()?.has_conflicts() {
+            return Ok(RebaseOutcome::Conflicts);
+        }
+
+        commit_operation(repo, rebase)?;
+    }
+
+    rebase.finish(Some(&signature))?;
+
+    Ok(RebaseOutcome::Finished)
+}
+
+fn commit_operation(
+    repo: &Repository,
+    rebase: &mut Rebase,
+) -> Result<()> {
+    match rebase.commit(None, &repo.signature()?, None) {
+        // patch already upstream, nothing to commit
+        Err(e) if e.code() == ErrorCode::Applied => Ok(()),
+        res => res.map(|_| ()).map_err(Into::into),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::tests::{repo_init, write_commit};
+    use git2::{RepositoryState, ResetType};
+
+    #[test]
+    fn test_rebase() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = repo.head().unwrap().target().unwrap();
+        let upstream = write_commit(repo_path, "a.txt", "a", "a.txt");
+
+        repo.reset(
+            &repo.find_object(base, None).unwrap(),
+            ResetType::Hard,
+            None,
+        )
+        .unwrap();
+        write_commit(repo_path, "b.txt", "b", "b.txt");
+
+        assert_eq!(
+            rebase_branch(repo_path, upstream).unwrap(),
+            RebaseOutcome::Finished
+        );
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("b.txt"));
+        assert_eq!(head.parent_id(0).unwrap(), upstream.get_oid());
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert_eq!(root.join("a.txt").exists(), true);
+        assert_eq!(root.join("b.txt").exists(), true);
+    }
+
+    #[test]
+    fn test_rebase_conflict() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = repo.head().unwrap().target().unwrap();
+        let upstream =
+            write_commit(repo_path, "a.txt", "theirs", "a.txt");
+
+        repo.reset(
+            &repo.find_object(base, None).unwrap(),
+            ResetType::Hard,
+            None,
+        )
+        .unwrap();
+        write_commit(repo_path, "a.txt", "ours", "a.txt");
+
+        assert_eq!(
+            rebase_branch(repo_path, upstream).unwrap(),
+            RebaseOutcome::Conflicts
+        );
+
+        assert_eq!(repo.state(), RepositoryState::RebaseMerge);
+        let mut index = repo.index().unwrap();
+        index.read(true).unwrap();
+        assert_eq!(index.has_conflicts(), true);
+    }
+}
@@ -0,0 +1,1060 @@
+//! interactive rebase driven by an editable todo list,
+//! continuing and aborting rebases in progress
+
+use super::{
+    merge::fast_forward,
+    rebase::{continue_rebase_repo, RebaseOutcome},
+    utils::{has_tracked_changes, repo},
+    CommitId,
+};
+use crate::error::{Error, Result};
+use git2::{
+    build::CheckoutBuilder, Commit, Oid, Repository, RepositoryState,
+    ResetType, Sort,
+};
+use scopetime::scope_time;
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+/// state dir in the layout of git, `git rebase --continue` and
+/// `--abort` can take over a rebase stopped by gitui
+const REBASE_DIR: &str = "rebase-merge";
+const TODO_FILE: &str = "git-rebase-todo";
+const DONE_FILE: &str = "done";
+
+/// what to do with a commit when rebasing interactively
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RebaseAction {
+    /// use the commit
+    Pick,
+    /// use the commit with a new message
+    Reword,
+    /// use the commit and stop to amend it
+    Edit,
+    /// meld into the previous commit, keeping both messages
+    Squash,
+    /// meld into the previous commit, discarding this message
+    Fixup,
+    /// remove the commit
+    Drop,
+}
+
+impl RebaseAction {
+    ///
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pick => "pick",
+            Self::Reword => "reword",
+            Self::Edit => "edit",
+            Self::Squash => "squash",
+            Self::Fixup => "fixup",
+            Self::Drop => "drop",
+        }
+    }
+
+    /// parses long and short (`p`, `r`, ..) action names
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pick" | "p" => Some(Self::Pick),
+            "reword" | "r" => Some(Self::Reword),
+            "edit" | "e" => Some(Self::Edit),
+            "squash" | "s" => Some(Self::Squash),
+            "fixup" | "f" => Some(Self::Fixup),
+            "drop" | "d" => Some(Self::Drop),
+            _ => None,
+        }
+    }
+
+    const fn melds(self) -> bool {
+        matches!(self, Self::Squash | Self::Fixup)
+    }
+}
+
+impl fmt::Display for RebaseAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// one line of the todo list
+#[derive(Clone, Debug, PartialEq)]
+pub struct RebaseTodoItem {
+    ///
+    pub action: RebaseAction,
+    ///
+    pub id: CommitId,
+    /// first line of the commit message
+    pub summary: String,
+    /// new message for `Reword` (keeps the old one if `None`)
+    pub message: Option<String>,
+}
+
+/// why a rebase in progress stopped
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RebaseStop {
+    /// to amend a commit marked `edit`
+    Edit,
+    /// on conflicts that need to be resolved
+    Conflicts,
+}
+
+/// progress of a rebase in progress
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RebaseStatus {
+    /// 1-based number of the current step
+    pub step: usize,
+    ///
+    pub total: usize,
+    ///
+    pub interactive: bool,
+    ///
+    pub stop: Option<RebaseStop>,
+}
+
+/// the commits rebasing `HEAD` onto `base` would replay (oldest first),
+/// all picked
+pub fn get_rebase_todo(
+    repo_path: &str,
+    base: CommitId,
+) -> Result<Vec<RebaseTodoItem>> {
+    scope_time!("get_rebase_todo");
+
+    let repo = repo(repo_path)?;
+    let head = repo.head()?.peel_to_commit()?.id();
+    let base: Oid = base.into();
+
+    if head != base && !repo.graph_descendant_of(head, base)? {
+        return Err(Error::Generic(String::from(
+            "base is not an ancestor of HEAD",
+        )));
+    }
+
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    walk.push(head)?;
+    walk.hide(base)?;
+
+    walk.map(|id| {
+        let commit = repo.find_commit(id?)?;
+
+        if commit.parent_count() > 1 {
+            return Err(Error::Generic(String::from(
+                "cannot rebase merge commits",
+            )));
+        }
+
+        Ok(RebaseTodoItem {
+            action: RebaseAction::Pick,
+            id: CommitId::new(commit.id()),
+            summary: commit.summary().unwrap_or_default().to_string(),
+            message: None,
+        })
+    })
+    .collect()
+}
+
+/// rewrites the commits after `base` following `todo`.
+/// stops on conflicts and on commits to `edit`,
+/// see `rebase_continue` and `rebase_abort`
+pub fn rebase_interactive(
+    repo_path: &str,
+    base: CommitId,
+    todo: &[RebaseTodoItem],
+) -> Result<RebaseOutcome> {
+    scope_time!("rebase_interactive");
+
+    let repo = repo(repo_path)?;
+
+    if repo.state() != RepositoryState::Clean {
+        return Err(Error::Generic(String::from(
+            "another operation is in progress",
+        )));
+    }
+
+    if has_tracked_changes(&repo)? {
+        return Err(Error::Generic(String::from(
+            "cannot rebase with uncommitted changes",
+        )));
+    }
+
+    if todo
+        .iter()
+        .find(|item| item.action != RebaseAction::Drop)
+        .map_or(false, |item| item.action.melds())
+    {
+        return Err(Error::Generic(String::from(
+            "cannot squash without a previous commit",
+        )));
+    }
+
+    let dir = state_dir(&repo);
+    if let Err(e) = start_rebase(&repo, &dir, base, todo) {
+        // a leftover state dir would look like a rebase in progress
+        fs::remove_dir_all(&dir).ok();
+        return Err(e);
+    }
+
+    run_todo(&repo)
+}
+
+/// writes the state of a new rebase and checks out `base`
+fn start_rebase(
+    repo: &Repository,
+    dir: &Path,
+    base: CommitId,
+    todo: &[RebaseTodoItem],
+) -> Result<()> {
+    let head = repo.head()?;
+    let head_name = if head.is_branch() {
+        head.name().unwrap_or_default().to_string()
+    } else {
+        String::from("detached HEAD")
+    };
+    let orig_head = head.peel_to_commit()?.id();
+
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join("head-name"), head_name)?;
+    fs::write(dir.join("onto"), base.to_string())?;
+    fs::write(dir.join("orig-head"), orig_head.to_string())?;
+    fs::write(dir.join("interactive"), "")?;
+    fs::write(dir.join(DONE_FILE), "")?;
+    fs::write(dir.join("msgnum"), "0")?;
+    fs::write(dir.join("end"), todo.len().to_string())?;
+
+    let todo = todo
+        .iter()
+        .map(|item| match (&item.action, &item.message) {
+            (RebaseAction::Reword, Some(msg)) => Ok((
+                RebaseAction::Pick,
+                reworded(repo, item.id, msg)?,
+            )),
+            _ => Ok((item.action, item.id.into())),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    write_todo(&dir.join(TODO_FILE), todo.into_iter())?;
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe();
+    repo.checkout_tree(
+        repo.find_commit(base.into())?.as_object(),
+        Some(&mut checkout),
+    )?;
+    repo.set_head_detached(base.into())?;
+
+    Ok(())
+}
+
+/// copy of the commit `id` with the message `msg`, picking it
+/// rewords the commit the way git would pick it up too
+fn reworded(
+    repo: &Repository,
+    id: CommitId,
+    msg: &str,
+) -> Result<Oid> {
+    let commit = repo.find_commit(id.into())?;
+    let parents = commit.parents().collect::<Vec<_>>();
+    let id = repo.commit(
+        None,
+        &commit.author(),
+        &commit.committer(),
+        msg,
+        &commit.tree()?,
+        &parents.iter().collect::<Vec<_>>(),
+    )?;
+
+    Ok(id)
+}
+
+/// continues the rebase in progress after conflicts were resolved
+/// (and staged) or a commit was edited
+pub fn rebase_continue(repo_path: &str) -> Result<RebaseOutcome> {
+    scope_time!("rebase_continue");
+
+    let repo = repo(repo_path)?;
+
+    let mut index = repo.index()?;
+    index.read(true)?;
+    if index.has_conflicts() {
+        return Err(Error::Generic(String::from(
+            "resolve all conflicts first",
+        )));
+    }
+
+    match repo.state() {
+        RepositoryState::RebaseInteractive => (),
+        RepositoryState::RebaseMerge => {
+            return continue_rebase_repo(&repo);
+        }
+        _ => {
+            return Err(Error::Generic(String::from(
+                "no rebase in progress",
+            )))
+        }
+    }
+
+    let dir = state_dir(&repo);
+
+    if let Ok(stopped) = fs::read_to_string(dir.join("stopped-sha")) {
+        // finish the step that conflicted
+        let commit =
+            repo.find_commit(Oid::from_str(stopped.trim())?)?;
+        let msg = fs::read_to_string(dir.join("message"))?;
+        let action = read_todo(&dir.join(DONE_FILE))?
+            .last()
+            .map_or(RebaseAction::Pick, |(action, _)| *action);
+
+        fs::remove_file(dir.join("stopped-sha"))?;
+        remove_stop_files(&dir)?;
+
+        commit_step(&repo, action, &commit, &msg)?;
+
+        if action == RebaseAction::Edit {
+            return stop_for_edit(&repo);
+        }
+    } else if dir.join("amend").exists() {
+        // take over changes staged while stopped to edit
+        let head = repo.head()?.peel_to_commit()?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+
+        if tree.id() != head.tree_id() {
+            let parents = head.parents().collect::<Vec<_>>();
+            let id = repo.commit(
+                None,
+                &head.author(),
+                &repo.signature()?,
+                head.message().unwrap_or_default(),
+                &tree,
+                &parents.iter().collect::<Vec<_>>(),
+            )?;
+            repo.set_head_detached(id)?;
+        }
+
+        remove_stop_files(&dir)?;
+    }
+
+    run_todo(&repo)
+}
+
+/// aborts the rebase in progress, restoring the branch, index and
+/// workdir as before
+pub fn rebase_abort(repo_path: &str) -> Result<()> {
+    scope_time!("rebase_abort");
+
+    let repo = repo(repo_path)?;
+
+    match repo.state() {
+        RepositoryState::RebaseInteractive => {
+            let dir = state_dir(&repo);
+            let head_name =
+                fs::read_to_string(dir.join("head-name"))?;
+            let orig_head = Oid::from_str(
+                fs::read_to_string(dir.join("orig-head"))?.trim(),
+            )?;
+
+            let head_name = head_name.trim();
+            if head_name.starts_with("refs/") {
+                repo.set_head(head_name)?;
+            } else {
+                repo.set_head_detached(orig_head)?;
+            }
+
+            fs::remove_dir_all(dir)?;
+
+            repo.reset(
+                repo.find_commit(orig_head)?.as_object(),
+                ResetType::Hard,
+                None,
+            )?;
+        }
+        RepositoryState::RebaseMerge => {
+            repo.open_rebase(None)?.abort()?;
+        }
+        _ => {
+            return Err(Error::Generic(String::from(
+                "no rebase in progress",
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+/// progress of the rebase in progress, if any
+pub fn get_rebase_status(
+    repo_path: &str,
+) -> Result<Option<RebaseStatus>> {
+    scope_time!("get_rebase_status");
+
+    let repo = repo(repo_path)?;
+
+    let interactive = match repo.state() {
+        RepositoryState::RebaseInteractive => true,
+        RepositoryState::RebaseMerge => false,
+        _ => return Ok(None),
+    };
+
+    let dir = state_dir(&repo);
+    let read_number = |file: &str| {
+        fs::read_to_string(dir.join(file))
+            .ok()
+            .and_then(|s| s.trim().parse::<usize>().ok())
+            .unwrap_or_default()
+    };
+
+    let mut index = repo.index()?;
+    index.read(true)?;
+
+    let stop = if index.has_conflicts() {
+        Some(RebaseStop::Conflicts)
+    } else if dir.join("amend").exists()
+        && !dir.join("stopped-sha").exists()
+    {
+        Some(RebaseStop::Edit)
+    } else {
+        None
+    };
+
+    Ok(Some(RebaseStatus {
+        step: read_number("msgnum"),
+        total: read_number("end"),
+        interactive,
+        stop,
+    }))
+}
+
+fn state_dir(repo: &Repository) -> PathBuf {
+    repo.path().join(REBASE_DIR)
+}
+
+fn read_todo(path: &Path) -> Result<Vec<(RebaseAction, Oid)>> {
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| {
+            !line.trim().is_empty() && !line.starts_with('#')
+        })
+        .map(|line| {
+            let mut parts = line.split_whitespace();
+            let action = parts.next().and_then(RebaseAction::parse);
+            let id =
+                parts.next().and_then(|id| Oid::from_str(id).ok());
+
+            match (action, id) {
+                (Some(action), Some(id)) => Ok((action, id)),
+                _ => Err(Error::Generic(format!(
+                    "invalid rebase todo line: '{}'",
+                    line
+                ))),
+            }
+        })
+        .collect()
+}
+
+fn write_todo(
+    path: &Path,
+    items: impl Iterator<Item = (RebaseAction, Oid)>,
+) -> Result<()> {
+    let content: String = items
+        .map(|(action, id)| format!("{} {}\n", action, id))
+        .collect();
+
+    fs::write(path, content)?;
+
+    Ok(())
+}
+
+/// applies the todo list step by step until it is done or a step
+/// needs the user
+fn run_todo(repo: &Repository) -> Result<RebaseOutcome> {
+    let dir = state_dir(repo);
+
+    loop {
+        let mut todo = read_todo(&dir.join(TODO_FILE))?;
+        if todo.is_empty() {
+            break;
+        }
+        let (action, id) = todo.remove(0);
+
+        let mut done = read_todo(&dir.join(DONE_FILE))?;
+        done.push((action, id));
+        write_todo(&dir.join(DONE_FILE), done.iter().copied())?;
+        write_todo(&dir.join(TODO_FILE), todo.into_iter())?;
+        fs::write(dir.join("msgnum"), done.len().to_string())?;
+
+        if action == RebaseAction::Drop {
+            continue;
+        }
+
+        let commit = repo.find_commit(id)?;
+        let head = repo.head()?.peel_to_commit()?;
+
+        // unchanged commits are reused as they are
+        if matches!(action, RebaseAction::Pick | RebaseAction::Edit)
+            && commit.parent_id(0).ok() == Some(head.id())
+        {
+            fast_forward(repo, id)?;
+        } else {
+            let msg = step_message(action, &commit, &head);
+
+            repo.cherrypick(&commit, None)?;
+            // the sequencer commits itself
+            for file in &["CHERRY_PICK_HEAD", "MERGE_MSG"] {
+                let _ = fs::remove_file(repo.path().join(file));
+            }
+
+            if repo.index()?.has_conflicts() {
+                fs::write(dir.join("stopped-sha"), id.to_string())?;
+                if action.melds() {
+                    // git amends `HEAD` continuing a squash/fixup
+                    write_stop_files(&dir, &head, &msg)?;
+                } else {
+                    fs::write(dir.join("message"), &msg)?;
+                    write_author_script(&dir, &commit)?;
+                }
+                return Ok(RebaseOutcome::Conflicts);
+            }
+
+            commit_step(repo, action, &commit, &msg)?;
+        }
+
+        if action == RebaseAction::Edit {
+            return stop_for_edit(repo);
+        }
+    }
+
+    finish(repo)?;
+
+    Ok(RebaseOutcome::Finished)
+}
+
+fn step_message(
+    action: RebaseAction,
+    commit: &Commit,
+    head: &Commit,
+) -> String {
+    let own = commit.message().unwrap_or_default();
+    let prev = head.message().unwrap_or_default();
+
+    match action {
+        RebaseAction::Squash => {
+            format!("{}\n\n{}", prev.trim_end(), own)
+        }
+        RebaseAction::Fixup => prev.to_string(),
+        _ => own.to_string(),
+    }
+}
+
+/// commits the index as the result of a step (empty ones too),
+/// melding into `HEAD` for squash/fixup
+fn commit_step(
+    repo: &Repository,
+    action: RebaseAction,
+    commit: &Commit,
+    msg: &str,
+) -> Result<()> {
+    let head = repo.head()?.peel_to_commit()?;
+    let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+    let committer = repo.signature()?;
+
+    if action.melds() {
+        let parents = head.parents().collect::<Vec<_>>();
+        let id = repo.commit(
+            None,
+            &head.author(),
+            &committer,
+            msg,
+            &tree,
+            &parents.iter().collect::<Vec<_>>(),
+        )?;
+        repo.set_head_detached(id)?;
+    } else {
+        // kept even if empty (or already applied), nothing is
+        // dropped that was not marked to drop
+        repo.commit(
+            Some("HEAD"),
+            &commit.author(),
+            &committer,
+            msg,
+            &tree,
+            &[&head],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn stop_for_edit(repo: &Repository) -> Result<RebaseOutcome> {
+    let head = repo.head()?.peel_to_commit()?;
+    write_stop_files(
+        &state_dir(repo),
+        &head,
+        head.message().unwrap_or_default(),
+    )?;
+
+    Ok(RebaseOutcome::Edit(CommitId::new(head.id())))
+}
+
+/// state git needs to amend `head` with `msg` on continuing
+fn write_stop_files(
+    dir: &Path,
+    head: &Commit,
+    msg: &str,
+) -> Result<()> {
+    fs::write(dir.join("amend"), head.id().to_string())?;
+    fs::write(dir.join("message"), msg)?;
+    write_author_script(dir, head)
+}
+
+fn remove_stop_files(dir: &Path) -> Result<()> {
+    for file in &["amend", "message", "author-script"] {
+        let path = dir.join(file);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// the author of `commit` the way git reads it to commit a resolved
+/// conflict
+fn write_author_script(dir: &Path, commit: &Commit) -> Result<()> {
+    let author = commit.author();
+    let time = author.when();
+    let quote = |s: Option<&str>| {
+        format!("'{}'", s.unwrap_or_default().replace('\'', "'\\''"))
+    };
+    let offset = time.offset_minutes();
+
+    fs::write(
+        dir.join("author-script"),
+        format!(
+            "GIT_AUTHOR_NAME={}\nGIT_AUTHOR_EMAIL={}\nGIT_AUTHOR_DATE='@{} {}{:02}{:02}'\n",
+            quote(author.name()),
+            quote(author.email()),
+            time.seconds(),
+            if offset < 0 { '-' } else { '+' },
+            offset.abs() / 60,
+            offset.abs() % 60,
+        ),
+    )?;
+
+    Ok(())
+}
+
+fn get_head_id(repo: &Repository) -> Result<Oid> {
+    Ok(repo.head()?.peel_to_commit()?.id())
+}
+
+/// moves the rebased branch to the result and leaves rebase state
+fn finish(repo: &Repository) -> Result<()> {
+    let dir = state_dir(repo);
+    let head_name = fs::read_to_string(dir.join("head-name"))?;
+    let head_name = head_name.trim();
+
+    if head_name.starts_with("refs/") {
+        let head = get_head_id(repo)?;
+        repo.reference(head_name, head, true, "rebase finished")?;
+        repo.set_head(head_name)?;
+    }
+
+    fs::remove_dir_all(dir)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, stage_add_file, stage_addremoved,
+        tests::{repo_init, write_commit},
+    };
+    use std::{fs::File, io::Write, process::Command};
+
+    fn head_messages(repo: &Repository, count: usize) -> Vec<String> {
+        let mut commit =
+            repo.head().unwrap().peel_to_commit().unwrap();
+        let mut res = Vec::new();
+        loop {
+            res.push(commit.message().unwrap().to_string());
+            if res.len() == count {
+                break;
+            }
+            commit = commit.parent(0).unwrap();
+        }
+        res
+    }
+
+    fn with_action(
+        mut item: RebaseTodoItem,
+        action: RebaseAction,
+    ) -> RebaseTodoItem {
+        item.action = action;
+        item
+    }
+
+    /// continues the rebase with the git cli, `true` if it finished
+    fn git_continue(root: &Path) -> bool {
+        let output = Command::new("git")
+            .args(&["rebase", "--continue"])
+            .current_dir(root)
+            .env("GIT_EDITOR", "true")
+            .output()
+            .unwrap();
+        output.status.success()
+    }
+
+    #[test]
+    fn test_todo() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = CommitId::new(get_head_id(&repo).unwrap());
+        let a = write_commit(repo_path, "a.txt", "a", "a.txt");
+        let b = write_commit(repo_path, "b.txt", "b", "b.txt");
+
+        let todo = get_rebase_todo(repo_path, base).unwrap();
+        assert_eq!(
+            todo.iter().map(|i| i.id).collect::<Vec<_>>(),
+            vec![a, b]
+        );
+        assert_eq!(todo[0].summary, "a.txt");
+        assert_eq!(todo[0].action, RebaseAction::Pick);
+
+        assert!(get_rebase_todo(repo_path, b).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reorder_squash_drop_reword() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = CommitId::new(get_head_id(&repo).unwrap());
+        write_commit(repo_path, "a.txt", "a", "a.txt");
+        write_commit(repo_path, "b.txt", "b", "b.txt");
+        write_commit(repo_path, "c.txt", "c", "c.txt");
+        write_commit(repo_path, "d.txt", "d", "d.txt");
+
+        let todo = get_rebase_todo(repo_path, base).unwrap();
+        let mut reword =
+            with_action(todo[3].clone(), RebaseAction::Reword);
+        reword.message = Some(String::from("reworded"));
+        let todo = vec![
+            with_action(todo[1].clone(), RebaseAction::Pick),
+            with_action(todo[0].clone(), RebaseAction::Squash),
+            with_action(todo[2].clone(), RebaseAction::Drop),
+            reword,
+        ];
+
+        assert_eq!(
+            rebase_interactive(repo_path, base, &todo).unwrap(),
+            RebaseOutcome::Finished
+        );
+
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert!(repo.head().unwrap().is_branch());
+        assert_eq!(
+            head_messages(&repo, 3),
+            vec!["reworded", "b.txt\n\na.txt", "initial"]
+        );
+        assert!(root.join("a.txt").exists());
+        assert!(!root.join("c.txt").exists());
+    }
+
+    #[test]
+    fn test_failed_start_leaves_no_state() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        write_commit(repo_path, "a.txt", "a", "a.txt");
+        let base = CommitId::new(get_head_id(&repo).unwrap());
+        fs::remove_file(root.join("a.txt")).unwrap();
+        stage_addremoved(repo_path, Path::new("a.txt")).unwrap();
+        commit(repo_path, "remove a.txt").unwrap();
+
+        // untracked, would be overwritten checking out `base`
+        fs::write(root.join("a.txt"), "untracked").unwrap();
+
+        let todo = get_rebase_todo(repo_path, base).unwrap();
+        assert!(rebase_interactive(repo_path, base, &todo).is_err());
+
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert!(!state_dir(&repo).exists());
+        assert_eq!(get_rebase_status(repo_path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_fixup_first_fails() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = CommitId::new(get_head_id(&repo).unwrap());
+        write_commit(repo_path, "a.txt", "a", "a.txt");
+
+        let todo = get_rebase_todo(repo_path, base).unwrap();
+        let todo =
+            vec![with_action(todo[0].clone(), RebaseAction::Fixup)];
+
+        assert!(rebase_interactive(repo_path, base, &todo).is_err());
+        assert_eq!(repo.state(), RepositoryState::Clean);
+    }
+
+    #[test]
+    fn test_edit_continue() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = CommitId::new(get_head_id(&repo).unwrap());
+        let a = write_commit(repo_path, "a.txt", "a", "a.txt");
+        write_commit(repo_path, "b.txt", "b", "b.txt");
+
+        let todo = get_rebase_todo(repo_path, base).unwrap();
+        let todo = vec![
+            with_action(todo[0].clone(), RebaseAction::Edit),
+            todo[1].clone(),
+        ];
+
+        assert_eq!(
+            rebase_interactive(repo_path, base, &todo).unwrap(),
+            RebaseOutcome::Edit(a)
+        );
+        assert_eq!(
+            get_rebase_status(repo_path).unwrap(),
+            Some(RebaseStatus {
+                step: 1,
+                total: 2,
+                interactive: true,
+                stop: Some(RebaseStop::Edit),
+            })
+        );
+
+        File::create(&root.join("a.txt"))
+            .unwrap()
+            .write_all(b"edited")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+
+        assert_eq!(
+            rebase_continue(repo_path).unwrap(),
+            RebaseOutcome::Finished
+        );
+        assert_eq!(get_rebase_status(repo_path).unwrap(), None);
+        assert_eq!(head_messages(&repo, 2), vec!["b.txt", "a.txt"]);
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).unwrap(),
+            "edited"
+        );
+    }
+
+    #[test]
+    fn test_conflict_continue_abort() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = CommitId::new(get_head_id(&repo).unwrap());
+        write_commit(repo_path, "a.txt", "a", "a.txt");
+        write_commit(repo_path, "a.txt", "b", "a.txt");
+        let orig = get_head_id(&repo).unwrap();
+
+        // swapping both commits conflicts
+        let todo = get_rebase_todo(repo_path, base).unwrap();
+        let swapped = vec![todo[1].clone(), todo[0].clone()];
+
+        assert_eq!(
+            rebase_interactive(repo_path, base, &swapped).unwrap(),
+            RebaseOutcome::Conflicts
+        );
+        assert_eq!(
+            get_rebase_status(repo_path).unwrap().unwrap().stop,
+            Some(RebaseStop::Conflicts)
+        );
+        assert!(rebase_continue(repo_path).is_err());
+
+        rebase_abort(repo_path).unwrap();
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert_eq!(get_head_id(&repo).unwrap(), orig);
+        assert!(repo.head().unwrap().is_branch());
+
+        assert_eq!(
+            rebase_interactive(repo_path, base, &swapped).unwrap(),
+            RebaseOutcome::Conflicts
+        );
+
+        let resolve = |content: &[u8]| {
+            File::create(&root.join("a.txt"))
+                .unwrap()
+                .write_all(content)
+                .unwrap();
+            stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+        };
+
+        // the second step conflicts again
+        resolve(b"resolved");
+        assert_eq!(
+            rebase_continue(repo_path).unwrap(),
+            RebaseOutcome::Conflicts
+        );
+
+        resolve(b"a");
+        assert_eq!(
+            rebase_continue(repo_path).unwrap(),
+            RebaseOutcome::Finished
+        );
+        assert_eq!(
+            head_messages(&repo, 3),
+            vec!["a.txt", "a.txt", "initial"]
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).unwrap(),
+            "a"
+        );
+    }
+
+    #[test]
+    fn test_git_continues_conflict() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = CommitId::new(get_head_id(&repo).unwrap());
+        write_commit(repo_path, "a.txt", "a", "a.txt");
+        write_commit(repo_path, "a.txt", "b", "a.txt");
+        write_commit(repo_path, "c.txt", "c", "c.txt");
+
+        let todo = get_rebase_todo(repo_path, base).unwrap();
+        let mut reword =
+            with_action(todo[2].clone(), RebaseAction::Reword);
+        reword.message = Some(String::from("reworded"));
+        let todo = vec![todo[1].clone(), todo[0].clone(), reword];
+
+        assert_eq!(
+            rebase_interactive(repo_path, base, &todo).unwrap(),
+            RebaseOutcome::Conflicts
+        );
+
+        let resolve = |content: &[u8]| {
+            File::create(&root.join("a.txt"))
+                .unwrap()
+                .write_all(content)
+                .unwrap();
+            stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+        };
+
+        // the second step conflicts again
+        resolve(b"resolved");
+        assert!(!git_continue(root));
+        resolve(b"a");
+        assert!(git_continue(root));
+
+        // git terminates the messages of commits it makes
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert_eq!(
+            head_messages(&repo, 4),
+            vec!["reworded", "a.txt\n", "a.txt\n", "initial"]
+        );
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let resolved = head.parent(0).unwrap();
+        assert_eq!(resolved.author().name(), Some("name"));
+    }
+
+    #[test]
+    fn test_git_continues_edit() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = CommitId::new(get_head_id(&repo).unwrap());
+        let a = write_commit(repo_path, "a.txt", "a", "a.txt");
+        write_commit(repo_path, "b.txt", "b", "b.txt");
+
+        let todo = get_rebase_todo(repo_path, base).unwrap();
+        let todo = vec![
+            with_action(todo[0].clone(), RebaseAction::Edit),
+            todo[1].clone(),
+        ];
+
+        assert_eq!(
+            rebase_interactive(repo_path, base, &todo).unwrap(),
+            RebaseOutcome::Edit(a)
+        );
+
+        File::create(&root.join("a.txt"))
+            .unwrap()
+            .write_all(b"edited")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+
+        assert!(git_continue(root));
+
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert_eq!(
+            head_messages(&repo, 3),
+            vec!["b.txt", "a.txt\n", "initial"]
+        );
+        let edited = repo
+            .head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .parent(0)
+            .unwrap();
+        assert_eq!(
+            repo.find_blob(
+                edited
+                    .tree()
+                    .unwrap()
+                    .get_name("a.txt")
+                    .unwrap()
+                    .id()
+            )
+            .unwrap()
+            .content(),
+            b"edited"
+        );
+    }
+
+    #[test]
+    fn test_keep_empty() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let base = CommitId::new(get_head_id(&repo).unwrap());
+        write_commit(repo_path, "a.txt", "a", "a.txt");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = repo.signature().unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            "empty",
+            &head.tree().unwrap(),
+            &[&head],
+        )
+        .unwrap();
+
+        // moving the empty commit first replays it
+        let todo = get_rebase_todo(repo_path, base).unwrap();
+        let mut reword =
+            with_action(todo[1].clone(), RebaseAction::Reword);
+        reword.message = Some(String::from("reworded"));
+        let todo = vec![reword, todo[0].clone()];
+
+        assert_eq!(
+            rebase_interactive(repo_path, base, &todo).unwrap(),
+            RebaseOutcome::Finished
+        );
+        assert_eq!(
+            head_messages(&repo, 3),
+            vec!["a.txt", "reworded", "initial"]
+        );
+    }
+}
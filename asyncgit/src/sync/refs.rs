@@ -0,0 +1,141 @@
+use super::{utils::repo, CommitId};
+use crate::error::Result;
+use git2::{Oid, ReferenceType};
+use scopetime::scope_time;
+use std::collections::HashMap;
+
+/// kind of a reference, in the order they are listed
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RefKind {
+    /// `HEAD`
+    Head,
+    /// `refs/heads/..`
+    LocalBranch,
+    /// `refs/remotes/..`
+    RemoteBranch,
+    /// `refs/tags/..`
+    Tag,
+}
+
+/// a reference pointing (maybe via a tag object) to a commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefInfo {
+    /// short name (e.g. `master`, `origin/master`, `v1.0`)
+    pub name: String,
+    ///
+    pub kind: RefKind,
+}
+
+/// hashmap of commit to all refs pointing to it (sorted by kind)
+pub type Refs = HashMap<CommitId, Vec<RefInfo>>;
+
+/// returns `HEAD`, local and remote branches and tags by the commit
+/// they point to
+pub fn get_refs(repo_path: &str) -> Result<Refs> {
+    scope_time!("get_refs");
+
+    let mut res = Refs::new();
+    let mut adder = |id: Oid, name: &str, kind: RefKind| {
+        res.entry(CommitId::new(id)).or_default().push(RefInfo {
+            name: String::from(name),
+            kind,
+        });
+    };
+
+    let repo = repo(repo_path)?;
+
+    if let Ok(head) = repo.head() {
+        if let Ok(commit) = head.peel_to_commit() {
+            adder(commit.id(), "HEAD", RefKind::Head);
+        }
+    }
+
+    for reference in repo.references()? {
+        let reference = reference?;
+
+        // e.g. `origin/HEAD`
+        if reference.kind() == Some(ReferenceType::Symbolic) {
+            continue;
+        }
+
+        let kind = if reference.is_branch() {
+            RefKind::LocalBranch
+        } else if reference.is_remote() {
+            RefKind::RemoteBranch
+        } else if reference.is_tag() {
+            RefKind::Tag
+        } else {
+            continue;
+        };
+
+        // tags may point to trees or blobs
+        if let Ok(commit) = reference.peel_to_commit() {
+            adder(
+                commit.id(),
+                reference.shorthand().unwrap_or_default(),
+                kind,
+            );
+        }
+    }
+
+    for refs in res.values_mut() {
+        refs.sort_by(|a, b| {
+            a.kind.cmp(&b.kind).then_with(|| a.name.cmp(&b.name))
+        });
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{create_branch, tests::repo_init};
+
+    #[test]
+    fn test_refs() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let head_id = repo.head().unwrap().target().unwrap();
+        let target = repo.find_object(head_id, None).unwrap();
+        let sig = repo.signature().unwrap();
+
+        repo.tag("v1", &target, &sig, "", false).unwrap();
+        repo.tag_lightweight("light", &target, false).unwrap();
+        create_branch(repo_path, "feature", None).unwrap();
+        repo.reference(
+            "refs/remotes/origin/master",
+            head_id,
+            false,
+            "",
+        )
+        .unwrap();
+        repo.reference_symbolic(
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/master",
+            false,
+            "",
+        )
+        .unwrap();
+
+        let refs = get_refs(repo_path).unwrap();
+        let names = refs[&CommitId::new(head_id)]
+            .iter()
+            .map(|r| (r.kind, r.name.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            vec![
+                (RefKind::Head, "HEAD"),
+                (RefKind::LocalBranch, "feature"),
+                (RefKind::LocalBranch, "master"),
+                (RefKind::RemoteBranch, "origin/master"),
+                (RefKind::Tag, "light"),
+                (RefKind::Tag, "v1"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,655 @@
+use super::{
+    branch::get_head_ref,
+    cred::{BasicAuthCredential, CredentialProvider},
+    utils::repo,
+};
+use crate::error::{Error, Result};
+use git2::{
+    Direction, FetchOptions, Oid, PushOptions, Remote,
+    RemoteCallbacks, Repository,
+};
+use scopetime::scope_time;
+
+/// transfer progress of a running fetch
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FetchProgress {
+    /// objects the remote announced
+    pub total_objects: usize,
+    /// objects downloaded so far
+    pub received_objects: usize,
+    /// objects already indexed
+    pub indexed_objects: usize,
+    /// bytes downloaded so far
+    pub received_bytes: usize,
+}
+
+impl FetchProgress {
+    /// overall progress in percent (receiving and indexing)
+    pub fn progress_percent(&self) -> u8 {
+        if self.total_objects == 0 {
+            return 100;
+        }
+
+        let done = self.received_objects + self.indexed_objects;
+        let total = self.total_objects * 2;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let percent = (done * 100 / total) as u8;
+
+        percent.min(100)
+    }
+}
+
+/// stage of a running push
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PushStage {
+    /// connecting to the remote and checking the lease (when forcing)
+    Connecting,
+    /// packing and uploading objects
+    Pushing,
+}
+
+/// progress of a running push
+///
+/// git2 does not expose packbuilder or upload counters (yet),
+/// so this reports the stage and the last progress line the remote sent
+#[derive(Clone, Debug, PartialEq)]
+pub struct PushProgress {
+    ///
+    pub stage: PushStage,
+    /// last sideband message of the remote (e.g. `Resolving deltas`)
+    pub remote_msg: Option<String>,
+}
+
+/// names of all configured remotes
+pub fn get_remotes(repo_path: &str) -> Result<Vec<String>> {
+    scope_time!("get_remotes");
+
+    let repo = repo(repo_path)?;
+    let remotes = repo.remotes()?;
+
+    Ok(remotes.iter().filter_map(|r| r.map(String::from)).collect())
+}
+
+/// remote to use when none was chosen explicitly:
+/// the remote of the upstream of `HEAD`, `origin` or the only remote
+pub fn get_default_remote(repo_path: &str) -> Result<String> {
+    scope_time!("get_default_remote");
+
+    let head_ref = get_head_ref(repo_path).unwrap_or_default();
+
+    get_branch_remote(repo_path, head_ref.as_str())
+}
+
+/// remote to push `branch_ref` to:
+/// the remote of its upstream, `origin` or the only remote
+pub fn get_branch_remote(
+    repo_path: &str,
+    branch_ref: &str,
+) -> Result<String> {
+    scope_time!("get_branch_remote");
+
+    let repo = repo(repo_path)?;
+
+    if let Some(remote) = get_upstream_remote(&repo, branch_ref) {
+        return Ok(remote);
+    }
+
+    let remotes = get_remotes(repo_path)?;
+
+    if remotes.iter().any(|r| r == "origin") {
+        Ok(String::from("origin"))
+    } else if remotes.len() == 1 {
+        Ok(remotes[0].clone())
+    } else {
+        Err(Error::Generic(String::from("no default remote found")))
+    }
+}
+
+fn get_upstream_remote(
+    repo: &Repository,
+    branch_ref: &str,
+) -> Option<String> {
+    let remote = repo.branch_upstream_remote(branch_ref).ok()?;
+
+    remote.as_str().map(String::from)
+}
+
+/// fetches `remote` using its configured refspecs,
+/// `progress` is called whenever the transfer advances.
+/// returns the amount of bytes received.
+///
+/// see `CredentialProvider` for how authentication is attempted,
+/// `basic_credential` is tried last.
+pub fn fetch_remote<F>(
+    repo_path: &str,
+    remote: &str,
+    basic_credential: Option<BasicAuthCredential>,
+    mut progress: F,
+) -> Result<usize>
+where
+    F: FnMut(FetchProgress),
+{
+    scope_time!("fetch_remote");
+
+    let repo = repo(repo_path)?;
+    let mut remote = repo.find_remote(remote)?;
+    let mut provider =
+        CredentialProvider::new(&repo, basic_credential)?;
+
+    let res = {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(|url, username, allowed| {
+            provider.get(url, username, allowed)
+        });
+        callbacks.transfer_progress(|p| {
+            progress(FetchProgress {
+                total_objects: p.total_objects(),
+                received_objects: p.received_objects(),
+                indexed_objects: p.indexed_objects(),
+                received_bytes: p.received_bytes(),
+            });
+            true
+        });
+
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(callbacks);
+
+        remote.fetch::<&str>(&[], Some(&mut options), None)
+    };
+
+    auth_error(res, &provider)?;
+
+    Ok(remote.stats().received_bytes())
+}
+
+/// libgit2 only reports that the credentials callback failed,
+/// turn that into `Error::Auth` if it failed because we ran out of
+/// credentials
+fn auth_error<T>(
+    res: std::result::Result<T, git2::Error>,
+    provider: &CredentialProvider,
+) -> Result<T> {
+    match res {
+        Err(e)
+            if provider.exhausted_by(&e)
+                || e.code() == git2::ErrorCode::Auth =>
+        {
+            Err(Error::Auth(e.message().to_string()))
+        }
+        res => Ok(res?),
+    }
+}
+
+/// pushes the local branch `branch_ref` to `remote`: to the branch
+/// it tracks there (`branch.<name>.merge`) or, without an upstream on
+/// `remote`, to the same name.
+///
+/// `force` behaves like `--force-with-lease`: the remote branch is only
+/// overwritten if it still points to what our remote tracking branch
+/// (`refs/remotes/<remote>/<branch>`) last saw, otherwise
+/// `Error::StaleLease` is returned.
+/// refs the remote refused (non-fast-forward, declined by a hook..)
+/// are returned as `Error::PushRejected`.
+/// authentication works like in `fetch_remote`.
+pub fn push_branch<F>(
+    repo_path: &str,
+    remote: &str,
+    branch_ref: &str,
+    force: bool,
+    basic_credential: Option<BasicAuthCredential>,
+    mut progress: F,
+) -> Result<()>
+where
+    F: FnMut(PushProgress),
+{
+    scope_time!("push_branch");
+
+    let repo = repo(repo_path)?;
+    let mut remote = repo.find_remote(remote)?;
+
+    progress(PushProgress {
+        stage: PushStage::Connecting,
+        remote_msg: None,
+    });
+
+    let destination = push_destination(&repo, &remote, branch_ref);
+
+    if force {
+        // the lease check connects on its own, that uses up its
+        // credentials sources
+        let mut provider =
+            CredentialProvider::new(&repo, basic_credential.clone())?;
+        check_lease(&repo, &mut remote, &destination, &mut provider)?;
+    }
+
+    let mut provider =
+        CredentialProvider::new(&repo, basic_credential)?;
+
+    let refspec = format!(
+        "{}{}:{}",
+        if force { "+" } else { "" },
+        branch_ref,
+        destination
+    );
+
+    let mut rejections = Vec::new();
+
+    let res = {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(|url, username, allowed| {
+            provider.get(url, username, allowed)
+        });
+        callbacks.sideband_progress(|data| {
+            let msg = String::from_utf8_lossy(data);
+            progress(PushProgress {
+                stage: PushStage::Pushing,
+                remote_msg: msg
+                    .lines()
+                    .last()
+                    .map(|l| l.trim().to_string()),
+            });
+            true
+        });
+        callbacks.push_update_reference(|reference, status| {
+            if let Some(status) = status {
+                rejections.push(format!("{}: {}", reference, status));
+            }
+            Ok(())
+        });
+
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+
+        remote.push(&[refspec.as_str()], Some(&mut options))
+    };
+
+    auth_error(res, &provider)?;
+
+    if rejections.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::PushRejected(rejections))
+    }
+}
+
+/// the remote ref `branch_ref` is pushed to on `remote`
+fn push_destination(
+    repo: &Repository,
+    remote: &Remote,
+    branch_ref: &str,
+) -> String {
+    if get_upstream_remote(repo, branch_ref).as_deref()
+        != remote.name()
+    {
+        return branch_ref.to_string();
+    }
+
+    let branch = branch_ref.trim_start_matches("refs/heads/");
+
+    repo.config()
+        .and_then(|config| {
+            config.get_string(&format!("branch.{}.merge", branch))
+        })
+        .unwrap_or_else(|_| branch_ref.to_string())
+}
+
+/// `destination` is the ref on the remote
+fn check_lease(
+    repo: &Repository,
+    remote: &mut Remote,
+    destination: &str,
+    provider: &mut CredentialProvider,
+) -> Result<()> {
+    let branch = destination.trim_start_matches("refs/heads/");
+    let tracking_ref = format!(
+        "refs/remotes/{}/{}",
+        remote.name().unwrap_or_default(),
+        branch
+    );
+
+    let expected = repo
+        .find_reference(tracking_ref.as_str())
+        .ok()
+        .and_then(|r| r.target());
+
+    let res = {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(|url, username, allowed| {
+            provider.get(url, username, allowed)
+        });
+
+        remote
+            .connect_auth(Direction::Push, Some(callbacks), None)
+            .and_then(|connection| {
+                Ok(connection
+                    .list()?
+                    .iter()
+                    .find(|head| head.name() == destination)
+                    .map(|head| head.oid()))
+            })
+    };
+
+    let current: Option<Oid> = auth_error(res, provider)?;
+
+    match current {
+        Some(current) if Some(current) != expected => {
+            Err(Error::StaleLease(destination.to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit,
+        tests::{repo_init, repo_init_bare, repo_init_empty},
+    };
+    use git2::{CredentialType, Repository};
+    use tempfile::TempDir;
+
+    fn clone_remote(remote_path: &str) -> (TempDir, Repository) {
+        let td = TempDir::new().unwrap();
+        let repo = Repository::clone(remote_path, td.path()).unwrap();
+        {
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "name").unwrap();
+            config.set_str("user.email", "email").unwrap();
+        }
+        (td, repo)
+    }
+
+    fn get_path(repo: &Repository) -> &str {
+        repo.path().parent().unwrap().as_os_str().to_str().unwrap()
+    }
+
+    #[test]
+    fn test_progress_percent() {
+        let mut p = FetchProgress::default();
+        assert_eq!(p.progress_percent(), 100);
+
+        p.total_objects = 10;
+        assert_eq!(p.progress_percent(), 0);
+
+        p.received_objects = 10;
+        assert_eq!(p.progress_percent(), 50);
+
+        p.indexed_objects = 10;
+        assert_eq!(p.progress_percent(), 100);
+    }
+
+    #[test]
+    fn test_no_remote() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert_eq!(get_remotes(repo_path).unwrap().is_empty(), true);
+        assert_eq!(get_default_remote(repo_path).is_err(), true);
+        assert_eq!(
+            fetch_remote(repo_path, "origin", None, |_| ()).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn test_auth_error() {
+        let (_td, repo) = repo_init().unwrap();
+        let mut provider =
+            CredentialProvider::new(&repo, None).unwrap();
+
+        let unrelated = || -> std::result::Result<(), git2::Error> {
+            Err(git2::Error::from_str("connection refused"))
+        };
+        assert!(matches!(
+            auth_error(unrelated(), &provider),
+            Err(Error::Git(_))
+        ));
+
+        let exhausted = provider
+            .get("https://example.com", None, CredentialType::empty())
+            .map(|_| ());
+        assert!(matches!(
+            auth_error(exhausted, &provider),
+            Err(Error::Auth(_))
+        ));
+
+        // running out of credentials earlier does not make every
+        // later error an authentication failure
+        assert!(matches!(
+            auth_error(unrelated(), &provider),
+            Err(Error::Git(_))
+        ));
+    }
+
+    #[test]
+    fn test_fetch() {
+        let (_td, repo) = repo_init().unwrap();
+        let (remote_dir, _remote) = repo_init_bare().unwrap();
+        let remote_path = remote_dir.path().to_str().unwrap();
+
+        repo.remote("origin", remote_path)
+            .unwrap()
+            .push(&["refs/heads/master:refs/heads/master"], None)
+            .unwrap();
+
+        let (_td2, clone) = repo_init_empty().unwrap();
+        let root = clone.path().parent().unwrap();
+        let clone_path = root.as_os_str().to_str().unwrap();
+
+        clone.remote("upstream", remote_path).unwrap();
+
+        assert_eq!(
+            get_remotes(clone_path).unwrap(),
+            vec!["upstream"]
+        );
+        assert_eq!(
+            get_default_remote(clone_path).unwrap(),
+            "upstream"
+        );
+
+        let mut last_progress = FetchProgress::default();
+        let bytes = fetch_remote(clone_path, "upstream", None, |p| {
+            last_progress = p
+        })
+        .unwrap();
+
+        assert_eq!(bytes > 0, true);
+        assert_eq!(last_progress.progress_percent(), 100);
+        assert_eq!(last_progress.received_bytes, bytes);
+
+        let fetched = clone
+            .find_reference("refs/remotes/upstream/master")
+            .unwrap()
+            .target()
+            .unwrap();
+        assert_eq!(fetched, repo.head().unwrap().target().unwrap());
+    }
+
+    #[test]
+    fn test_push() {
+        let (_td, repo) = repo_init().unwrap();
+        let (remote_dir, remote) = repo_init_bare().unwrap();
+        let remote_path = remote_dir.path().to_str().unwrap();
+        let repo_path = get_path(&repo);
+
+        repo.remote("origin", remote_path).unwrap();
+
+        let mut stages = Vec::new();
+        push_branch(
+            repo_path,
+            "origin",
+            "refs/heads/master",
+            false,
+            None,
+            |p| stages.push(p.stage),
+        )
+        .unwrap();
+
+        assert_eq!(stages.first(), Some(&PushStage::Connecting));
+
+        let head = repo.head().unwrap().target().unwrap();
+        assert_eq!(
+            remote
+                .find_reference("refs/heads/master")
+                .unwrap()
+                .target(),
+            Some(head)
+        );
+        assert_eq!(
+            repo.find_reference("refs/remotes/origin/master")
+                .unwrap()
+                .target(),
+            Some(head)
+        );
+    }
+
+    #[test]
+    fn test_push_to_upstream_branch() {
+        let (_td, repo) = repo_init().unwrap();
+        let (remote_dir, remote) = repo_init_bare().unwrap();
+        let remote_path = remote_dir.path().to_str().unwrap();
+        let repo_path = get_path(&repo);
+
+        repo.remote("origin", remote_path).unwrap();
+
+        let head = repo.head().unwrap().target().unwrap();
+        repo.branch(
+            "feature",
+            &repo.find_commit(head).unwrap(),
+            false,
+        )
+        .unwrap();
+        {
+            let mut config = repo.config().unwrap();
+            config
+                .set_str("branch.feature.remote", "origin")
+                .unwrap();
+            config
+                .set_str("branch.feature.merge", "refs/heads/other")
+                .unwrap();
+        }
+
+        push_branch(
+            repo_path,
+            "origin",
+            "refs/heads/feature",
+            false,
+            None,
+            |_| (),
+        )
+        .unwrap();
+
+        assert_eq!(
+            remote
+                .find_reference("refs/heads/other")
+                .unwrap()
+                .target(),
+            Some(head)
+        );
+        assert!(remote.find_reference("refs/heads/feature").is_err());
+
+        // without an upstream on that remote the name is kept
+        repo.remote("fork", remote_path).unwrap();
+        push_branch(
+            repo_path,
+            "fork",
+            "refs/heads/feature",
+            false,
+            None,
+            |_| (),
+        )
+        .unwrap();
+
+        assert_eq!(
+            remote
+                .find_reference("refs/heads/feature")
+                .unwrap()
+                .target(),
+            Some(head)
+        );
+    }
+
+    #[test]
+    fn test_push_rejected_and_force_with_lease() {
+        let (_td, repo) = repo_init().unwrap();
+        let (remote_dir, _remote) = repo_init_bare().unwrap();
+        let remote_path = remote_dir.path().to_str().unwrap();
+        let repo_path = get_path(&repo);
+
+        repo.remote("origin", remote_path).unwrap();
+        push_branch(
+            repo_path,
+            "origin",
+            "refs/heads/master",
+            false,
+            None,
+            |_| (),
+        )
+        .unwrap();
+
+        // someone else pushes in the meantime
+        let (_td2, other) = clone_remote(remote_path);
+        commit(get_path(&other), "other").unwrap();
+        push_branch(
+            get_path(&other),
+            "origin",
+            "refs/heads/master",
+            false,
+            None,
+            |_| (),
+        )
+        .unwrap();
+
+        commit(repo_path, "diverged").unwrap();
+
+        assert_eq!(
+            push_branch(
+                repo_path,
+                "origin",
+                "refs/heads/master",
+                false,
+                None,
+                |_| ()
+            )
+            .is_err(),
+            true
+        );
+
+        // we did not see the other commit yet
+        assert!(matches!(
+            push_branch(
+                repo_path,
+                "origin",
+                "refs/heads/master",
+                true,
+                None,
+                |_| ()
+            ),
+            Err(Error::StaleLease(_))
+        ));
+
+        fetch_remote(repo_path, "origin", None, |_| ()).unwrap();
+
+        push_branch(
+            repo_path,
+            "origin",
+            "refs/heads/master",
+            true,
+            None,
+            |_| (),
+        )
+        .unwrap();
+
+        let remote = Repository::open_bare(remote_path).unwrap();
+        assert_eq!(
+            remote
+                .find_reference("refs/heads/master")
+                .unwrap()
+                .target(),
+            repo.head().unwrap().target()
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! operation in progress (merge, cherry-pick, ..) and its prepared
+//! commit message
+
+use super::utils::repo;
+use crate::error::Result;
+use git2::RepositoryState;
+use scopetime::scope_time;
+use std::fs;
+
+/// operation the repository is in the middle of
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RepoState {
+    /// nothing in progress
+    Clean,
+    ///
+    Merge,
+    ///
+    CherryPick,
+    ///
+    Revert,
+    /// interactive or not
+    Rebase,
+    /// bisect, applying mailboxes, ..
+    Other,
+}
+
+impl From<RepositoryState> for RepoState {
+    fn from(state: RepositoryState) -> Self {
+        match state {
+            RepositoryState::Clean => Self::Clean,
+            RepositoryState::Merge => Self::Merge,
+            RepositoryState::CherryPick
+            | RepositoryState::CherryPickSequence => Self::CherryPick,
+            RepositoryState::Revert
+            | RepositoryState::RevertSequence => Self::Revert,
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge => Self::Rebase,
+            _ => Self::Other,
+        }
+    }
+}
+
+///
+pub fn repo_state(repo_path: &str) -> Result<RepoState> {
+    scope_time!("repo_state");
+
+    let repo = repo(repo_path)?;
+
+    Ok(repo.state().into())
+}
+
+/// message prepared for the next commit (`MERGE_MSG`) by a merge,
+/// cherry-pick or revert, if any
+pub fn get_prepared_commit_msg(
+    repo_path: &str,
+) -> Result<Option<String>> {
+    scope_time!("get_prepared_commit_msg");
+
+    let repo = repo(repo_path)?;
+
+    Ok(fs::read_to_string(repo.path().join("MERGE_MSG"))
+        .ok()
+        .filter(|msg| !msg.trim().is_empty()))
+}
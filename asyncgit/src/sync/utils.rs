@@ -2,9 +2,12 @@
 
 use super::CommitId;
 use crate::error::{Error, Result};
-use git2::{IndexAddOption, Oid, Repository, RepositoryOpenFlags};
+use git2::{
+    ErrorCode, IndexAddOption, Oid, Repository, RepositoryOpenFlags,
+    RepositoryState, StatusOptions,
+};
 use scopetime::scope_time;
-use std::path::Path;
+use std::{fs, path::Path};
 
 ///
 pub fn is_repo(repo_path: &str) -> bool {
@@ -47,6 +50,24 @@ pub fn work_dir(repo: &Repository) -> &Path {
     repo.workdir().expect("unable to query workdir")
 }
 
+/// value of `key` in the repo's git config (including the global and
+/// system one), `None` if it is not set
+pub fn get_config_string(
+    repo_path: &str,
+    key: &str,
+) -> Result<Option<String>> {
+    scope_time!("get_config_string");
+
+    let repo = repo(repo_path)?;
+    let config = repo.config()?;
+
+    match config.get_string(key) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
 ///
 pub fn get_head(repo_path: &str) -> Result<CommitId> {
     let repo = repo(repo_path)?;
@@ -66,38 +87,78 @@ pub fn get_head_repo(repo: &Repository) -> Result<CommitId> {
     }
 }
 
+/// staged or unstaged changes to tracked files
+pub(crate) fn has_tracked_changes(repo: &Repository) -> Result<bool> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(false).include_ignored(false);
+
+    Ok(!repo.statuses(Some(&mut opts))?.is_empty())
+}
+
 /// ditto
 pub fn commit_new(repo_path: &str, msg: &str) -> Result<CommitId> {
     commit(repo_path, msg).map(CommitId::new)
 }
 
-/// this does not run any git hooks
+/// this does not run any git hooks.
+/// concludes a merge, cherry-pick or revert in progress
 pub fn commit(repo_path: &str, msg: &str) -> Result<Oid> {
     scope_time!("commit");
 
     let repo = repo(repo_path)?;
 
     let signature = repo.signature()?;
+    let state = repo.state();
+
+    // a cherry-picked commit keeps its author
+    let author = if state == RepositoryState::CherryPick {
+        let picked = repo.refname_to_id("CHERRY_PICK_HEAD")?;
+        repo.find_commit(picked)?.author().to_owned()
+    } else {
+        signature.clone()
+    };
+
     let mut index = repo.index()?;
     let tree_id = index.write_tree()?;
     let tree = repo.find_tree(tree_id)?;
 
-    let parents = if let Ok(id) = get_head(repo_path) {
+    let mut parents = if let Ok(id) = get_head(repo_path) {
         vec![repo.find_commit(id.into())?]
     } else {
         Vec::new()
     };
 
+    // concluding a merge: the merged commits are parents too
+    if state == RepositoryState::Merge {
+        let merge_heads =
+            fs::read_to_string(repo.path().join("MERGE_HEAD"))?;
+        for id in merge_heads.lines() {
+            parents
+                .push(repo.find_commit(Oid::from_str(id.trim())?)?);
+        }
+    }
+
     let parents = parents.iter().collect::<Vec<_>>();
 
-    Ok(repo.commit(
+    let id = repo.commit(
         Some("HEAD"),
-        &signature,
+        &author,
         &signature,
         msg,
         &tree,
         parents.as_slice(),
-    )?)
+    )?;
+
+    if matches!(
+        state,
+        RepositoryState::Merge
+            | RepositoryState::CherryPick
+            | RepositoryState::Revert
+    ) {
+        repo.cleanup_state()?;
+    }
+
+    Ok(id)
 }
 
 /// add a file diff from workingdir to stage (will not add removed files see `stage_addremoved`)
@@ -157,6 +218,22 @@ mod tests {
         path::Path,
     };
 
+    #[test]
+    fn test_get_config_string() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert_eq!(
+            get_config_string(repo_path, "user.name").unwrap(),
+            Some(String::from("name"))
+        );
+        assert_eq!(
+            get_config_string(repo_path, "gitui.not-set").unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn test_commit() {
         let file_path = Path::new("foo");
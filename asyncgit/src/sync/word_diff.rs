@@ -0,0 +1,215 @@
+//! intra-line diff of changed lines paired within a hunk
+
+use super::diff::{DiffLine, DiffLineType};
+use std::ops::Range;
+
+/// lines with more tokens are not compared
+const MAX_TOKENS: usize = 500;
+
+/// changed byte ranges of the old and the new line
+type LineChanges = (Vec<Range<usize>>, Vec<Range<usize>>);
+
+/// pairs each run of deleted lines with the run of added lines
+/// directly following it and fills in their `changes`
+pub(crate) fn highlight_changes(lines: &mut [DiffLine]) {
+    let mut idx = 0;
+    while idx < lines.len() {
+        let deletes = run_len(&lines[idx..], DiffLineType::Delete);
+        let adds =
+            run_len(&lines[idx + deletes..], DiffLineType::Add);
+
+        for pair in 0..deletes.min(adds) {
+            let (old, new) = lines.split_at_mut(idx + deletes);
+            let old = &mut old[idx + pair];
+            let new = &mut new[pair];
+
+            if let Some((old_changes, new_changes)) =
+                changed_ranges(&old.content, &new.content)
+            {
+                old.changes = old_changes;
+                new.changes = new_changes;
+            }
+        }
+
+        idx += (deletes + adds).max(1);
+    }
+}
+
+fn run_len(lines: &[DiffLine], line_type: DiffLineType) -> usize {
+    lines
+        .iter()
+        .take_while(|line| line.line_type == line_type)
+        .count()
+}
+
+/// byte ranges of `old` and `new` not part of the longest common
+/// token sequence of both, `None` if the lines share nothing but
+/// whitespace (or are too long to compare)
+pub(crate) fn changed_ranges(
+    old: &str,
+    new: &str,
+) -> Option<LineChanges> {
+    let old = old.trim_end_matches(&['\n', '\r'][..]);
+    let new = new.trim_end_matches(&['\n', '\r'][..]);
+
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+
+    if old_tokens.len() > MAX_TOKENS || new_tokens.len() > MAX_TOKENS
+    {
+        return None;
+    }
+
+    // lcs[i][j]: length of the common sequence of the tokens from
+    // `i` and `j` on
+    let mut lcs =
+        vec![vec![0_u16; new_tokens.len() + 1]; old_tokens.len() + 1];
+    for i in (0..old_tokens.len()).rev() {
+        for j in (0..new_tokens.len()).rev() {
+            lcs[i][j] = if old[old_tokens[i].clone()]
+                == new[new_tokens[j].clone()]
+            {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut old_changes = Vec::new();
+    let mut new_changes = Vec::new();
+    let mut common_words = false;
+
+    let (mut i, mut j) = (0, 0);
+    while i < old_tokens.len() || j < new_tokens.len() {
+        if i < old_tokens.len()
+            && j < new_tokens.len()
+            && old[old_tokens[i].clone()]
+                == new[new_tokens[j].clone()]
+        {
+            common_words |=
+                !old[old_tokens[i].clone()].trim().is_empty();
+            i += 1;
+            j += 1;
+        } else if j == new_tokens.len()
+            || (i < old_tokens.len()
+                && lcs[i + 1][j] >= lcs[i][j + 1])
+        {
+            push_range(&mut old_changes, old_tokens[i].clone());
+            i += 1;
+        } else {
+            push_range(&mut new_changes, new_tokens[j].clone());
+            j += 1;
+        }
+    }
+
+    if common_words {
+        Some((old_changes, new_changes))
+    } else {
+        None
+    }
+}
+
+/// adds `range`, merging it with the last one if they are adjacent
+fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    }
+}
+
+#[derive(PartialEq)]
+enum TokenKind {
+    Word,
+    Whitespace,
+    Other,
+}
+
+impl TokenKind {
+    fn of(c: char) -> Self {
+        if c.is_alphanumeric() || c == '_' {
+            Self::Word
+        } else if c.is_whitespace() {
+            Self::Whitespace
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// splits into words, whitespace runs and single other characters
+fn tokenize(s: &str) -> Vec<Range<usize>> {
+    let mut tokens: Vec<Range<usize>> = Vec::new();
+    let mut last_kind = None;
+
+    for (idx, c) in s.char_indices() {
+        let kind = TokenKind::of(c);
+        let end = idx + c.len_utf8();
+
+        match tokens.last_mut() {
+            Some(last)
+                if kind != TokenKind::Other
+                    && last_kind.as_ref() == Some(&kind) =>
+            {
+                last.end = end
+            }
+            _ => tokens.push(idx..end),
+        }
+
+        last_kind = Some(kind);
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(content: &str, line_type: DiffLineType) -> DiffLine {
+        DiffLine {
+            content: content.to_string(),
+            line_type,
+            ..DiffLine::default()
+        }
+    }
+
+    fn spans(ranges: &[Range<usize>]) -> Vec<(usize, usize)> {
+        ranges.iter().map(|r| (r.start, r.end)).collect()
+    }
+
+    #[test]
+    fn test_changed_ranges() {
+        let (old, new) = changed_ranges(
+            "let foo = bar(1);\n",
+            "let foo = baz(1, 2);\n",
+        )
+        .unwrap();
+
+        assert_eq!(spans(&old), vec![(10, 13)]);
+        assert_eq!(spans(&new), vec![(10, 13), (15, 18)]);
+    }
+
+    #[test]
+    fn test_nothing_in_common() {
+        assert!(changed_ranges("foo bar\n", "baz qux\n").is_none());
+    }
+
+    #[test]
+    fn test_highlight_changes() {
+        let mut lines = vec![
+            line("@@ -1,2 +1,2 @@\n", DiffLineType::Header),
+            line("a = 1\n", DiffLineType::Delete),
+            line("unrelated\n", DiffLineType::Delete),
+            line("a = 2\n", DiffLineType::Add),
+            line("b\n", DiffLineType::None),
+        ];
+
+        highlight_changes(&mut lines);
+
+        assert_eq!(spans(&lines[1].changes), vec![(4, 5)]);
+        assert_eq!(spans(&lines[3].changes), vec![(4, 5)]);
+        assert!(lines[2].changes.is_empty());
+        assert!(lines[4].changes.is_empty());
+    }
+}
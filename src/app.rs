@@ -3,31 +3,45 @@ use crate::{
     accessors,
     cmdbar::CommandBar,
     components::{
-        event_pump, CommandBlocking, CommandInfo, CommitComponent,
-        Component, DrawableComponent, HelpComponent,
-        InspectCommitComponent, MsgComponent, ResetComponent,
-        StashMsgComponent,
+        branch_to_string, event_pump, BlameFileComponent,
+        BranchCompareComponent, CommandBlocking, CommandInfo,
+        CommitComponent, CompareCommitsComponent, Component,
+        CreateBranchComponent, CredComponent, DiffOptionsComponent,
+        DrawableComponent, FetchComponent, FileHistoryComponent,
+        HelpComponent, InspectCommitComponent, LogSearchComponent,
+        MsgComponent, PushComponent, RebaseTodoComponent,
+        RenameBranchComponent, ResetComponent, SelectRemoteComponent,
+        StashMsgComponent, WalkSpecComponent,
     },
+    external,
     input::InputEvent,
     keys,
-    queue::{Action, InternalEvent, NeedsUpdate, Queue},
+    options::{Options, SharedOptions},
+    queue::{
+        Action, ExternalProgram, InternalEvent, NeedsUpdate, Queue,
+        RemoteOperation,
+    },
     strings,
-    tabs::{Revlog, StashList, Stashing, Status},
+    tabs::{BranchList, Revlog, StashList, Stashing, Status},
     ui::style::{SharedTheme, Theme},
 };
 use anyhow::{anyhow, Result};
-use asyncgit::{sync, AsyncNotification, CWD};
+use asyncgit::{
+    sync::{self, CredentialCache},
+    AsyncNotification, CWD,
+};
 use crossbeam_channel::Sender;
 use crossterm::event::{Event, KeyEvent};
+use std::borrow::Cow;
 use std::cell::Cell;
 use std::{cell::RefCell, rc::Rc};
 use strings::{commands, order};
 use tui::{
     backend::Backend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::Modifier,
     style::Style,
-    widgets::{Block, Borders, Tabs},
+    widgets::{Block, Borders, Paragraph, Tabs, Text},
     Frame,
 };
 
@@ -40,14 +54,34 @@ pub struct App {
     commit: CommitComponent,
     stashmsg_popup: StashMsgComponent,
     inspect_commit_popup: InspectCommitComponent,
+    file_history_popup: FileHistoryComponent,
+    compare_commits_popup: CompareCommitsComponent,
+    branch_compare_popup: BranchCompareComponent,
+    blame_file_popup: BlameFileComponent,
+    create_branch_popup: CreateBranchComponent,
+    rename_branch_popup: RenameBranchComponent,
+    walk_spec_popup: WalkSpecComponent,
+    log_search_popup: LogSearchComponent,
+    rebase_todo_popup: RebaseTodoComponent,
+    diff_options_popup: DiffOptionsComponent,
+    select_remote_popup: SelectRemoteComponent,
+    fetch_popup: FetchComponent,
+    push_popup: PushComponent,
+    cred_popup: CredComponent,
     cmdbar: RefCell<CommandBar>,
     tab: usize,
     revlog: Revlog,
     status_tab: Status,
     stashing_tab: Stashing,
     stashlist_tab: StashList,
+    branchlist_tab: BranchList,
     queue: Queue,
     theme: SharedTheme,
+    options: SharedOptions,
+    branch_status: Option<String>,
+
+    /// to run once input polling is suspended
+    external: Option<ExternalProgram>,
 
     // "Flags"
     requires_redraw: Cell<bool>,
@@ -57,10 +91,14 @@ pub struct App {
 // public interface
 impl App {
     ///
+    #[allow(clippy::too_many_lines)]
     pub fn new(sender: &Sender<AsyncNotification>) -> Self {
         let queue = Queue::default();
 
         let theme = Rc::new(Theme::init());
+        let options = Rc::new(RefCell::new(Options::init()));
+        let credentials =
+            Rc::new(RefCell::new(CredentialCache::new()));
 
         Self {
             reset: ResetComponent::new(queue.clone(), theme.clone()),
@@ -72,9 +110,79 @@ impl App {
                 queue.clone(),
                 theme.clone(),
             ),
+            blame_file_popup: BlameFileComponent::new(
+                &queue,
+                sender,
+                theme.clone(),
+            ),
+            file_history_popup: FileHistoryComponent::new(
+                &queue,
+                sender,
+                options.clone(),
+                theme.clone(),
+            ),
             inspect_commit_popup: InspectCommitComponent::new(
                 &queue,
                 sender,
+                options.clone(),
+                theme.clone(),
+            ),
+            compare_commits_popup: CompareCommitsComponent::new(
+                &queue,
+                sender,
+                options.clone(),
+                theme.clone(),
+            ),
+            branch_compare_popup: BranchCompareComponent::new(
+                &queue,
+                sender,
+                options.clone(),
+                theme.clone(),
+            ),
+            create_branch_popup: CreateBranchComponent::new(
+                queue.clone(),
+                theme.clone(),
+            ),
+            rename_branch_popup: RenameBranchComponent::new(
+                queue.clone(),
+                theme.clone(),
+            ),
+            walk_spec_popup: WalkSpecComponent::new(
+                queue.clone(),
+                theme.clone(),
+            ),
+            log_search_popup: LogSearchComponent::new(
+                queue.clone(),
+                theme.clone(),
+            ),
+            rebase_todo_popup: RebaseTodoComponent::new(
+                queue.clone(),
+                theme.clone(),
+            ),
+            diff_options_popup: DiffOptionsComponent::new(
+                queue.clone(),
+                options.clone(),
+                theme.clone(),
+            ),
+            select_remote_popup: SelectRemoteComponent::new(
+                queue.clone(),
+                theme.clone(),
+            ),
+            fetch_popup: FetchComponent::new(
+                &queue,
+                sender,
+                credentials.clone(),
+                theme.clone(),
+            ),
+            push_popup: PushComponent::new(
+                &queue,
+                sender,
+                credentials.clone(),
+                theme.clone(),
+            ),
+            cred_popup: CredComponent::new(
+                &queue,
+                credentials,
                 theme.clone(),
             ),
             do_quit: false,
@@ -82,16 +190,30 @@ impl App {
             help: HelpComponent::new(theme.clone()),
             msg: MsgComponent::new(theme.clone()),
             tab: 0,
-            revlog: Revlog::new(&queue, sender, theme.clone()),
-            status_tab: Status::new(&queue, sender, theme.clone()),
+            revlog: Revlog::new(
+                &queue,
+                sender,
+                options.clone(),
+                theme.clone(),
+            ),
+            status_tab: Status::new(
+                &queue,
+                sender,
+                options.clone(),
+                theme.clone(),
+            ),
             stashing_tab: Stashing::new(
                 sender,
                 &queue,
                 theme.clone(),
             ),
             stashlist_tab: StashList::new(&queue, theme.clone()),
+            branchlist_tab: BranchList::new(&queue, theme.clone()),
             queue,
             theme,
+            options,
+            branch_status: None,
+            external: None,
             requires_redraw: Cell::new(false),
             set_polling: true,
         }
@@ -125,6 +247,7 @@ impl App {
             1 => self.revlog.draw(f, chunks_main[1])?,
             2 => self.stashing_tab.draw(f, chunks_main[1])?,
             3 => self.stashlist_tab.draw(f, chunks_main[1])?,
+            4 => self.branchlist_tab.draw(f, chunks_main[1])?,
             _ => return Err(anyhow!("unknown tab")),
         };
 
@@ -160,11 +283,27 @@ impl App {
                     keys::TAB_1
                     | keys::TAB_2
                     | keys::TAB_3
-                    | keys::TAB_4 => {
+                    | keys::TAB_4
+                    | keys::TAB_5 => {
                         self.switch_tab(k)?;
                         NeedsUpdate::COMMANDS
                     }
 
+                    keys::FETCH => {
+                        self.select_remote()?;
+                        NeedsUpdate::COMMANDS
+                    }
+
+                    keys::PULL => {
+                        self.fetch_popup.fetch(None, true)?;
+                        NeedsUpdate::COMMANDS
+                    }
+
+                    keys::PUSH | keys::FORCE_PUSH => {
+                        self.push_head(k == keys::FORCE_PUSH);
+                        NeedsUpdate::COMMANDS
+                    }
+
                     keys::CMD_BAR_TOGGLE => {
                         self.cmdbar.borrow_mut().toggle_more();
                         NeedsUpdate::empty()
@@ -180,6 +319,7 @@ impl App {
             flags.insert(new_flags);
 
             if flags.contains(NeedsUpdate::ALL) {
+                self.revlog.invalidate_refs();
                 self.update()?;
             }
             //TODO: make this a queue event?
@@ -187,17 +327,19 @@ impl App {
             if flags.contains(NeedsUpdate::DIFF) {
                 self.status_tab.update_diff()?;
                 self.inspect_commit_popup.update_diff()?;
+                if self.file_history_popup.is_visible() {
+                    self.file_history_popup.update_diff()?;
+                }
+                self.compare_commits_popup.update_diff()?;
+                self.branch_compare_popup.update_diff()?;
             }
             if flags.contains(NeedsUpdate::COMMANDS) {
                 self.update_commands();
             }
         } else if let InputEvent::State(polling_state) = ev {
             if let InputState::Paused = polling_state {
-                if let Err(e) = self.commit.show_editor() {
-                    let msg =
-                        format!("failed to launch editor:\n{}", e);
-                    log::error!("{}", msg.as_str());
-                    self.msg.show_msg(msg.as_str())?;
+                if let Some(program) = self.external.take() {
+                    self.run_external(program)?;
                 }
                 self.requires_redraw.set(true);
                 self.set_polling = true;
@@ -211,10 +353,22 @@ impl App {
     /// forward ticking to components that require it
     pub fn update(&mut self) -> Result<()> {
         log::trace!("update");
+        self.update_branch_status();
         self.status_tab.update()?;
         self.revlog.update()?;
         self.stashing_tab.update()?;
         self.stashlist_tab.update()?;
+        self.branchlist_tab.update()?;
+
+        if self.inspect_commit_popup.is_visible() {
+            self.inspect_commit_popup.update()?;
+        }
+        if self.compare_commits_popup.is_visible() {
+            self.compare_commits_popup.update()?;
+        }
+        if self.branch_compare_popup.is_visible() {
+            self.branch_compare_popup.update()?;
+        }
 
         Ok(())
     }
@@ -230,6 +384,17 @@ impl App {
         self.stashing_tab.update_git(ev)?;
         self.revlog.update_git(ev)?;
         self.inspect_commit_popup.update_git(ev)?;
+        self.file_history_popup.update_git(ev)?;
+        self.compare_commits_popup.update_git(ev)?;
+        self.branch_compare_popup.update_git(ev)?;
+        self.blame_file_popup.update_git(ev)?;
+        self.fetch_popup.update_git(ev)?;
+        self.push_popup.update_git(ev)?;
+
+        if self.process_queue()?.contains(NeedsUpdate::ALL) {
+            self.revlog.invalidate_refs();
+            self.update()?;
+        }
 
         //TODO: better system for this
         // can we simply process the queue here and everyone just uses the queue to schedule a cmd update?
@@ -249,6 +414,12 @@ impl App {
             || self.revlog.any_work_pending()
             || self.stashing_tab.anything_pending()
             || self.inspect_commit_popup.any_work_pending()
+            || self.file_history_popup.any_work_pending()
+            || self.compare_commits_popup.any_work_pending()
+            || self.branch_compare_popup.any_work_pending()
+            || self.blame_file_popup.any_work_pending()
+            || self.fetch_popup.any_work_pending()
+            || self.push_popup.any_work_pending()
     }
 
     ///
@@ -276,12 +447,27 @@ impl App {
             reset,
             commit,
             stashmsg_popup,
+            diff_options_popup,
+            blame_file_popup,
+            file_history_popup,
+            compare_commits_popup,
             inspect_commit_popup,
+            branch_compare_popup,
+            create_branch_popup,
+            rename_branch_popup,
+            walk_spec_popup,
+            log_search_popup,
+            rebase_todo_popup,
+            select_remote_popup,
+            fetch_popup,
+            push_popup,
+            cred_popup,
             help,
             revlog,
             status_tab,
             stashing_tab,
-            stashlist_tab
+            stashlist_tab,
+            branchlist_tab
         ]
     );
 
@@ -301,6 +487,7 @@ impl App {
             &mut self.revlog,
             &mut self.stashing_tab,
             &mut self.stashlist_tab,
+            &mut self.branchlist_tab,
         ]
     }
 
@@ -321,6 +508,7 @@ impl App {
             keys::TAB_2 => self.set_tab(1)?,
             keys::TAB_3 => self.set_tab(2)?,
             keys::TAB_4 => self.set_tab(3)?,
+            keys::TAB_5 => self.set_tab(4)?,
             _ => (),
         }
 
@@ -342,6 +530,50 @@ impl App {
         Ok(())
     }
 
+    fn update_branch_status(&mut self) {
+        self.branch_status =
+            sync::get_branch_name(CWD).ok().map(|b| {
+                branch_to_string(
+                    b.as_str(),
+                    sync::get_head_upstream(CWD)
+                        .unwrap_or(None)
+                        .as_ref(),
+                )
+            });
+    }
+
+    /// fetches the only remote or lets the user pick one
+    fn select_remote(&mut self) -> Result<()> {
+        let remotes = sync::get_remotes(CWD)?;
+
+        if remotes.len() > 1 {
+            let default = sync::get_default_remote(CWD).ok();
+            self.select_remote_popup
+                .open(remotes, default.as_deref())?;
+        } else {
+            self.fetch_popup.fetch(None, false)?;
+        }
+
+        Ok(())
+    }
+
+    fn push_head(&mut self, force: bool) {
+        let ev = match sync::get_head_ref(CWD) {
+            Ok(branch_ref) if force => InternalEvent::ConfirmAction(
+                Action::ForcePush(branch_ref),
+            ),
+            Ok(branch_ref) => InternalEvent::Remote(
+                RemoteOperation::Push(branch_ref, false),
+            ),
+            Err(e) => InternalEvent::ShowErrorMsg(format!(
+                "push error:\n{}",
+                e
+            )),
+        };
+
+        self.queue.borrow_mut().push_back(ev);
+    }
+
     fn update_commands(&mut self) {
         self.help.set_cmds(self.commands(true));
         self.cmdbar.borrow_mut().set_cmds(self.commands(false));
@@ -363,28 +595,113 @@ impl App {
         Ok(flags)
     }
 
-    fn process_internal_event(
+    /// called once input polling is suspended
+    fn run_external(
         &mut self,
-        ev: InternalEvent,
+        program: ExternalProgram,
+    ) -> Result<()> {
+        let res = match program {
+            ExternalProgram::CommitEditor => {
+                self.commit.show_editor()
+            }
+            ExternalProgram::MergeTool(path) => {
+                external::run_merge_tool(&path)
+            }
+            ExternalProgram::DiffTool(path, staged) => {
+                external::run_diff_tool(&path, staged)
+            }
+        };
+
+        if let Err(e) = res {
+            let msg =
+                format!("failed to launch external program:\n{}", e);
+            log::error!("{}", msg.as_str());
+            self.msg.show_msg(msg.as_str())?;
+        }
+
+        // the program might have changed anything
+        self.update()
+    }
+
+    fn process_confirmed_action(
+        &mut self,
+        action: Action,
     ) -> Result<NeedsUpdate> {
         let mut flags = NeedsUpdate::empty();
-        match ev {
-            InternalEvent::ConfirmedAction(action) => match action {
-                Action::Reset(r) => {
-                    if self.status_tab.reset(&r) {
-                        flags.insert(NeedsUpdate::ALL);
-                    }
+        match action {
+            Action::Reset(r) => {
+                if self.status_tab.reset(&r) {
+                    flags.insert(NeedsUpdate::ALL);
                 }
-                Action::StashDrop(s) => {
-                    if StashList::drop(s) {
-                        flags.insert(NeedsUpdate::ALL);
-                    }
+            }
+            Action::StashDrop(s) => {
+                if StashList::drop(s) {
+                    flags.insert(NeedsUpdate::ALL);
                 }
-                Action::ResetHunk(path, hash) => {
-                    sync::reset_hunk(CWD, path, hash)?;
+            }
+            Action::ResetHunk(path, hash) => {
+                sync::reset_hunk(
+                    CWD,
+                    path,
+                    hash,
+                    Some(self.options.borrow().diff),
+                )?;
+                flags.insert(NeedsUpdate::ALL);
+            }
+            Action::ResetLines(path, lines) => {
+                sync::reset_lines(
+                    CWD,
+                    &path,
+                    &lines,
+                    Some(self.options.borrow().diff),
+                )?;
+                flags.insert(NeedsUpdate::ALL);
+            }
+            Action::DeleteBranch(branch_ref) => {
+                if self.branchlist_tab.delete(&branch_ref) {
                     flags.insert(NeedsUpdate::ALL);
                 }
-            },
+            }
+            Action::ForcePush(branch_ref) => {
+                self.push_popup.push(branch_ref, true)?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            Action::RebaseAbort => {
+                if let Err(e) = sync::rebase_abort(CWD) {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "rebase abort error:\n{}",
+                            e
+                        )),
+                    );
+                }
+                flags.insert(NeedsUpdate::ALL);
+            }
+            Action::MergeAbort => {
+                if let Err(e) = sync::abort_merge(CWD) {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "abort error:\n{}",
+                            e
+                        )),
+                    );
+                }
+                flags.insert(NeedsUpdate::ALL);
+            }
+        }
+
+        Ok(flags)
+    }
+
+    fn process_internal_event(
+        &mut self,
+        ev: InternalEvent,
+    ) -> Result<NeedsUpdate> {
+        let mut flags = NeedsUpdate::empty();
+        match ev {
+            InternalEvent::ConfirmedAction(action) => {
+                flags.insert(self.process_confirmed_action(action)?);
+            }
             InternalEvent::ConfirmAction(action) => {
                 self.reset.open(action)?;
                 flags.insert(NeedsUpdate::COMMANDS);
@@ -394,6 +711,11 @@ impl App {
                 flags
                     .insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
             }
+            InternalEvent::ShowInfoMsg(msg) => {
+                self.msg.show_info(msg.as_str())?;
+                flags
+                    .insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
+            }
             InternalEvent::Update(u) => flags.insert(u),
             InternalEvent::OpenCommit => self.commit.show()?,
             InternalEvent::PopupStashing(opts) => {
@@ -405,9 +727,73 @@ impl App {
                 self.inspect_commit_popup.open(id)?;
                 flags.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS)
             }
-            InternalEvent::SuspendPolling => {
+            InternalEvent::CompareCommits(from, to) => {
+                self.compare_commits_popup.open(from, to)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::CompareBranch(target, name) => {
+                self.branch_compare_popup.open(&target, &name)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::RunExternal(program) => {
+                self.external = Some(program);
                 self.set_polling = false;
             }
+            InternalEvent::CreateBranch(target) => {
+                self.create_branch_popup.open(target)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::RenameBranch(branch_ref, name) => {
+                self.rename_branch_popup.open(branch_ref, name)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::Remote(op) => {
+                match op {
+                    RemoteOperation::Fetch(remote, pull) => {
+                        self.fetch_popup.fetch(remote, pull)?
+                    }
+                    RemoteOperation::Push(branch_ref, force) => {
+                        self.push_popup.push(branch_ref, force)?
+                    }
+                }
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::AskCredentials(url, op) => {
+                self.cred_popup.open(url, op)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenWalkSpec(spec) => {
+                self.walk_spec_popup.open(&spec)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::SetWalkSpec(spec) => {
+                self.revlog.set_walk_spec(spec);
+                flags.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenFileHistory(path) => {
+                self.file_history_popup.open(path)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::BlameFile(params) => {
+                self.blame_file_popup.open(params)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenLogSearch(query) => {
+                self.log_search_popup.open(query)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::SetLogFilter(filter) => {
+                self.revlog.set_filter(filter);
+                flags.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenRebase(base) => {
+                self.rebase_todo_popup.open(base)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenDiffOptions => {
+                self.diff_options_popup.show()?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
         };
 
         Ok(flags)
@@ -442,6 +828,28 @@ impl App {
             .order(order::NAV),
         );
 
+        res.push(CommandInfo::new(
+            commands::FETCH,
+            true,
+            !self.any_popup_visible(),
+        ));
+
+        res.push(CommandInfo::new(
+            commands::PULL,
+            true,
+            !self.any_popup_visible(),
+        ));
+        res.push(CommandInfo::new(
+            commands::PUSH,
+            true,
+            !self.any_popup_visible(),
+        ));
+        res.push(CommandInfo::new(
+            commands::FORCE_PUSH,
+            true,
+            !self.any_popup_visible(),
+        ));
+
         res.push(
             CommandInfo::new(
                 commands::QUIT,
@@ -461,6 +869,20 @@ impl App {
             || self.msg.is_visible()
             || self.stashmsg_popup.is_visible()
             || self.inspect_commit_popup.is_visible()
+            || self.file_history_popup.is_visible()
+            || self.compare_commits_popup.is_visible()
+            || self.branch_compare_popup.is_visible()
+            || self.blame_file_popup.is_visible()
+            || self.create_branch_popup.is_visible()
+            || self.rename_branch_popup.is_visible()
+            || self.walk_spec_popup.is_visible()
+            || self.log_search_popup.is_visible()
+            || self.rebase_todo_popup.is_visible()
+            || self.diff_options_popup.is_visible()
+            || self.select_remote_popup.is_visible()
+            || self.fetch_popup.is_visible()
+            || self.push_popup.is_visible()
+            || self.cred_popup.is_visible()
     }
 
     fn draw_popups<B: Backend>(
@@ -480,16 +902,63 @@ impl App {
 
         self.commit.draw(f, size)?;
         self.stashmsg_popup.draw(f, size)?;
+        self.create_branch_popup.draw(f, size)?;
+        self.rename_branch_popup.draw(f, size)?;
+        self.walk_spec_popup.draw(f, size)?;
+        self.log_search_popup.draw(f, size)?;
+        self.rebase_todo_popup.draw(f, size)?;
         self.reset.draw(f, size)?;
+        self.select_remote_popup.draw(f, size)?;
+        self.fetch_popup.draw(f, size)?;
+        self.push_popup.draw(f, size)?;
+        self.cred_popup.draw(f, size)?;
         self.help.draw(f, size)?;
         self.msg.draw(f, size)?;
+        self.branch_compare_popup.draw(f, size)?;
         self.inspect_commit_popup.draw(f, size)?;
+        self.file_history_popup.draw(f, size)?;
+        self.compare_commits_popup.draw(f, size)?;
+        self.blame_file_popup.draw(f, size)?;
+        self.diff_options_popup.draw(f, size)?;
 
         Ok(())
     }
 
     //TODO: make this dynamic
     fn draw_tabs<B: Backend>(&self, f: &mut Frame<B>, r: Rect) {
+        let branch_status = self
+            .branch_status
+            .as_ref()
+            .map(|b| format!("{{{}}}", b))
+            .unwrap_or_default();
+
+        #[allow(clippy::cast_possible_truncation)]
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Min(1),
+                    Constraint::Length(
+                        branch_status.chars().count() as u16 + 1,
+                    ),
+                ]
+                .as_ref(),
+            )
+            .split(r);
+
+        f.render_widget(
+            Paragraph::new(
+                [Text::Styled(
+                    Cow::from(branch_status.as_str()),
+                    self.theme.branch(false, true),
+                )]
+                .iter(),
+            )
+            .block(Block::default().borders(Borders::BOTTOM))
+            .alignment(Alignment::Right),
+            chunks[1],
+        );
+
         f.render_widget(
             Tabs::default()
                 .block(Block::default().borders(Borders::BOTTOM))
@@ -498,6 +967,7 @@ impl App {
                     strings::TAB_LOG,
                     strings::TAB_STASHING,
                     strings::TAB_STASHES,
+                    strings::TAB_BRANCHES,
                 ])
                 .style(Style::default())
                 .highlight_style(
@@ -507,7 +977,7 @@ impl App {
                 )
                 .divider(strings::TAB_DIVIDER)
                 .select(self.tab),
-            r,
+            chunks[0],
         );
     }
 }
@@ -0,0 +1,417 @@
+use super::{
+    utils::age_to_string, visibility_blocking, CommandBlocking,
+    CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    keys,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::{
+        calc_scroll_top,
+        style::SharedTheme,
+        syntax::{self, HighlightedLine},
+    },
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{CommitId, FileBlame},
+    AsyncBlame, AsyncNotification, BlameParams, DiffLineType,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use std::{borrow::Cow, cell::Cell};
+use strings::commands;
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    widgets::{Block, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+const AUTHOR_WIDTH: usize = 12;
+
+/// every line of a file with the commit that last changed it
+pub struct BlameFileComponent {
+    params: Option<BlameParams>,
+    blame: Option<FileBlame>,
+    highlights: Vec<HighlightedLine>,
+    error: Option<String>,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    current_height: Cell<usize>,
+    git_blame: AsyncBlame,
+    queue: Queue,
+    theme: SharedTheme,
+    visible: bool,
+}
+
+impl DrawableComponent for BlameFileComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.is_visible() {
+            let height = rect.height.saturating_sub(2) as usize;
+            self.current_height.set(height);
+            self.scroll_top.set(calc_scroll_top(
+                self.scroll_top.get(),
+                height,
+                self.selection,
+            ));
+
+            let title = self.title();
+            let txt = self.get_text(height);
+
+            f.render_widget(Clear, rect);
+            f.render_widget(
+                Paragraph::new(txt.iter())
+                    .block(
+                        Block::default()
+                            .title(title.as_str())
+                            .borders(Borders::ALL)
+                            .border_style(self.theme.block(true))
+                            .title_style(self.theme.title(true)),
+                    )
+                    .alignment(Alignment::Left),
+                rect,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for BlameFileComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            out.push(
+                CommandInfo::new(commands::CLOSE_POPUP, true, true)
+                    .order(1),
+            );
+
+            out.push(CommandInfo::new(
+                commands::SCROLL,
+                self.line_count() > 0,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                commands::BLAME_INSPECT_COMMIT,
+                self.selected_commit().is_some(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if let Event::Key(e) = ev {
+                match e {
+                    keys::EXIT_POPUP => self.hide(),
+                    keys::MOVE_UP => self.move_selection(-1),
+                    keys::MOVE_DOWN => self.move_selection(1),
+                    keys::PAGE_UP => {
+                        self.move_selection(-self.page_size());
+                    }
+                    keys::PAGE_DOWN => {
+                        self.move_selection(self.page_size());
+                    }
+                    keys::HOME | keys::SHIFT_UP => self.selection = 0,
+                    keys::END | keys::SHIFT_DOWN => {
+                        self.selection =
+                            self.line_count().saturating_sub(1);
+                    }
+                    keys::BLAME_INSPECT_COMMIT => {
+                        if let Some(id) = self.selected_commit() {
+                            self.hide();
+                            self.queue.borrow_mut().push_back(
+                                InternalEvent::InspectCommit(id),
+                            );
+                        }
+                    }
+                    _ => (),
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl BlameFileComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        theme: SharedTheme,
+    ) -> Self {
+        Self {
+            params: None,
+            blame: None,
+            highlights: Vec::new(),
+            error: None,
+            selection: 0,
+            scroll_top: Cell::new(0),
+            current_height: Cell::new(0),
+            git_blame: AsyncBlame::new(sender),
+            queue: queue.clone(),
+            theme,
+            visible: false,
+        }
+    }
+
+    /// blame a file in a commit or the workdir
+    pub fn open(&mut self, params: BlameParams) -> Result<()> {
+        self.git_blame.request(params.clone())?;
+
+        self.params = Some(params);
+        self.blame = None;
+        self.highlights.clear();
+        self.error = None;
+        self.selection = 0;
+        self.scroll_top.set(0);
+
+        self.show()
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_blame.is_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.is_visible() {
+            if let AsyncNotification::Blame = ev {
+                self.update()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update(&mut self) -> Result<()> {
+        if let Some(params) = self.params.clone() {
+            match self.git_blame.current()? {
+                Some((blamed, res)) if blamed == params => {
+                    match res {
+                        Ok(blame) => {
+                            self.highlights = syntax::highlight(
+                                &blame.path,
+                                blame
+                                    .lines
+                                    .iter()
+                                    .map(|(_, line)| line.as_str()),
+                            );
+                            self.blame = Some(blame);
+                        }
+                        Err(e) => self.error = Some(e),
+                    }
+                }
+                // requested while another blame was running
+                _ => self.git_blame.request(params)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn title(&self) -> String {
+        let path = self
+            .params
+            .as_ref()
+            .map(|p| p.path.as_str())
+            .unwrap_or_default();
+
+        let revision = self
+            .params
+            .as_ref()
+            .and_then(|p| p.commit_id)
+            .map(|id| format!(" @ {}", &id.to_string()[..7]))
+            .unwrap_or_default();
+
+        let state = if self.error.is_some() {
+            strings::BLAME_TITLE_FAILED
+        } else if self.blame.is_none() {
+            strings::BLAME_TITLE_LOADING
+        } else {
+            ""
+        };
+
+        format!(
+            "{} {}{}{}",
+            strings::BLAME_TITLE,
+            path,
+            revision,
+            state
+        )
+    }
+
+    fn get_text(&self, height: usize) -> Vec<Text> {
+        if let Some(e) = &self.error {
+            return vec![Text::Styled(
+                Cow::from(e.as_str()),
+                self.theme.text_danger(),
+            )];
+        }
+
+        let blame = match &self.blame {
+            Some(blame) => blame,
+            None => return Vec::new(),
+        };
+
+        let (oldest, newest) = blame
+            .hunks
+            .iter()
+            .fold((i64::MAX, i64::MIN), |(min, max), h| {
+                (min.min(h.time), max.max(h.time))
+            });
+
+        let line_number_width = blame.lines.len().to_string().len();
+
+        let mut txt = Vec::with_capacity(height * 4);
+
+        for (idx, (hunk, line)) in blame
+            .lines
+            .iter()
+            .enumerate()
+            .skip(self.scroll_top.get())
+            .take(height)
+        {
+            let selected = idx == self.selection;
+            let hunk = hunk.and_then(|h| blame.hunks.get(h));
+
+            #[allow(clippy::cast_precision_loss)]
+            let age = hunk.map(|h| {
+                if newest > oldest {
+                    (newest - h.time) as f64
+                        / (newest - oldest) as f64
+                } else {
+                    0_f64
+                }
+            });
+
+            let info = hunk.map_or_else(
+                || {
+                    format!(
+                        "{:w$}",
+                        strings::BLAME_NOT_COMMITTED,
+                        w = AUTHOR_WIDTH + 14
+                    )
+                },
+                |h| {
+                    let author: String =
+                        h.author.chars().take(AUTHOR_WIDTH).collect();
+                    format!(
+                        "{} {:aw$} {:>4}",
+                        &h.commit_id.to_string()[..7],
+                        author,
+                        age_to_string(h.time),
+                        aw = AUTHOR_WIDTH
+                    )
+                },
+            );
+
+            txt.push(Text::Styled(
+                Cow::from(info),
+                self.theme.blame_age(age, selected),
+            ));
+            txt.push(Text::Styled(
+                Cow::from(format!(
+                    " {:>w$} ",
+                    idx + 1,
+                    w = line_number_width
+                )),
+                self.theme.commit_time(selected),
+            ));
+
+            match self.highlights.get(idx) {
+                Some(highlighted) => {
+                    for (range, _, color) in syntax::segments(
+                        line.len(),
+                        &[],
+                        Some(highlighted),
+                    ) {
+                        txt.push(Text::Styled(
+                            Cow::from(
+                                line[range].replace('\t', "  "),
+                            ),
+                            self.theme.diff_line_part(
+                                DiffLineType::None,
+                                selected,
+                                false,
+                                color,
+                            ),
+                        ));
+                    }
+                    txt.push(Text::Raw(Cow::from("\n")));
+                }
+                None => txt.push(Text::Styled(
+                    //TODO: allow customize tabsize
+                    Cow::from(format!(
+                        "{}\n",
+                        line.replace('\t', "  ")
+                    )),
+                    self.theme.text(true, selected),
+                )),
+            }
+        }
+
+        txt
+    }
+
+    fn line_count(&self) -> usize {
+        self.blame.as_ref().map_or(0, |b| b.lines.len())
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn page_size(&self) -> isize {
+        self.current_height.get().saturating_sub(1).max(1) as isize
+    }
+
+    #[allow(
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss,
+        clippy::cast_possible_truncation
+    )]
+    fn move_selection(&mut self, delta: isize) {
+        let max = self.line_count().saturating_sub(1) as isize;
+        self.selection = (self.selection as isize + delta)
+            .max(0)
+            .min(max) as usize;
+    }
+
+    fn selected_commit(&self) -> Option<CommitId> {
+        let blame = self.blame.as_ref()?;
+        let (hunk, _) = blame.lines.get(self.selection)?;
+
+        hunk.and_then(|h| blame.hunks.get(h)).map(|h| h.commit_id)
+    }
+}
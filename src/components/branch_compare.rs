@@ -0,0 +1,442 @@
+use super::{
+    utils::short_hash, visibility_blocking, CommandBlocking,
+    CommandInfo, CommitList, Component, DiffComponent,
+    DrawableComponent, FileTreeComponent,
+};
+use crate::{
+    keys,
+    options::SharedOptions,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, BranchCompare, CommitId, DiffOptions},
+    AsyncCompareFiles, AsyncDiff, AsyncNotification, DiffParams,
+    DiffType, CWD,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use strings::commands;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::Clear,
+    Frame,
+};
+
+const SLICE_SIZE: usize = 1200;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Focus {
+    Commits,
+    Files,
+    Diff,
+}
+
+/// commits on `HEAD` not on a target branch and the changes since
+/// their merge base, like a merge request would show them
+pub struct BranchCompareComponent {
+    compare: Option<BranchCompare>,
+    commits: CommitList,
+    files: FileTreeComponent,
+    diff: DiffComponent,
+    focus: Focus,
+    git_diff: AsyncDiff,
+    git_files: AsyncCompareFiles,
+    queue: Queue,
+    options: SharedOptions,
+    visible: bool,
+}
+
+impl DrawableComponent for BranchCompareComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.is_visible() {
+            let percentages = if self.focus == Focus::Diff {
+                (30, 70)
+            } else {
+                (50, 50)
+            };
+
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(
+                    [
+                        Constraint::Percentage(percentages.0),
+                        Constraint::Percentage(percentages.1),
+                    ]
+                    .as_ref(),
+                )
+                .split(rect);
+
+            let left = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [
+                        Constraint::Percentage(40),
+                        Constraint::Percentage(60),
+                    ]
+                    .as_ref(),
+                )
+                .split(chunks[0]);
+
+            f.render_widget(Clear, rect);
+
+            self.commits.draw(f, left[0])?;
+            self.files.draw(f, left[1])?;
+            self.diff.draw(f, chunks[1])?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for BranchCompareComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            if self.focus == Focus::Commits || force_all {
+                self.commits.commands(out, force_all);
+            }
+            if self.focus == Focus::Files || force_all {
+                self.files.commands(out, force_all);
+            }
+            if self.focus == Focus::Diff || force_all {
+                self.diff.commands(out, force_all);
+            }
+
+            out.push(
+                CommandInfo::new(commands::CLOSE_POPUP, true, true)
+                    .order(1),
+            );
+
+            out.push(CommandInfo::new(
+                commands::BRANCH_COMPARE_FOCUS,
+                true,
+                self.focus != Focus::Diff || force_all,
+            ));
+
+            out.push(CommandInfo::new(
+                commands::BRANCH_COMPARE_INSPECT,
+                self.commits.selected_entry().is_some(),
+                self.focus == Focus::Commits || force_all,
+            ));
+
+            out.push(CommandInfo::new(
+                commands::DIFF_FOCUS_RIGHT,
+                self.can_focus_diff(),
+                self.focus == Focus::Files || force_all,
+            ));
+
+            out.push(CommandInfo::new(
+                commands::DIFF_FOCUS_LEFT,
+                true,
+                self.focus == Focus::Diff || force_all,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            let event_used = match self.focus {
+                Focus::Commits => {
+                    let used = self.commits.event(ev)?;
+                    if used {
+                        self.update_commits();
+                    }
+                    used
+                }
+                Focus::Files => self.files.event(ev)?,
+                Focus::Diff => self.diff.event(ev)?,
+            };
+            if event_used {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                match e {
+                    keys::EXIT_POPUP => {
+                        self.hide();
+                    }
+                    keys::BRANCH_COMPARE_FOCUS
+                        if self.focus != Focus::Diff =>
+                    {
+                        self.set_focus(
+                            if self.focus == Focus::Files {
+                                Focus::Commits
+                            } else {
+                                Focus::Files
+                            },
+                        );
+                    }
+                    keys::BRANCH_COMPARE_INSPECT
+                        if self.focus == Focus::Commits =>
+                    {
+                        if let Some(e) = self.commits.selected_entry()
+                        {
+                            self.queue.borrow_mut().push_back(
+                                InternalEvent::InspectCommit(e.id),
+                            );
+                        }
+                    }
+                    keys::FOCUS_RIGHT
+                        if self.focus == Focus::Files
+                            && self.can_focus_diff() =>
+                    {
+                        self.set_focus(Focus::Diff);
+                    }
+                    keys::FOCUS_LEFT if self.focus == Focus::Diff => {
+                        self.set_focus(Focus::Files);
+                    }
+                    _ => (),
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        self.set_focus(Focus::Files);
+        self.update()?;
+        Ok(())
+    }
+}
+
+impl BranchCompareComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        options: SharedOptions,
+        theme: SharedTheme,
+    ) -> Self {
+        Self {
+            compare: None,
+            commits: CommitList::new(
+                strings::BRANCH_COMPARE_COMMITS_TITLE,
+                theme.clone(),
+            ),
+            files: FileTreeComponent::new(
+                strings::BRANCH_COMPARE_FILES_TITLE,
+                true,
+                Some(queue.clone()),
+                theme.clone(),
+            ),
+            diff: DiffComponent::new(
+                queue.clone(),
+                options.clone(),
+                theme,
+                true,
+            ),
+            focus: Focus::Files,
+            git_diff: AsyncDiff::new(sender.clone()),
+            git_files: AsyncCompareFiles::new(sender),
+            queue: queue.clone(),
+            options,
+            visible: false,
+        }
+    }
+
+    /// reviews `HEAD` against the branch `target` (a reference)
+    pub fn open(&mut self, target: &str, name: &str) -> Result<()> {
+        let compare = match sync::compare_branch(CWD, target) {
+            Ok(compare) => compare,
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "compare error:\n{}",
+                        e
+                    )),
+                );
+                return Ok(());
+            }
+        };
+
+        self.commits.clear();
+        self.commits.set_selection(0);
+        self.commits.set_count_total(compare.commits.len());
+        self.commits.set_title(format!(
+            "{} {}",
+            strings::BRANCH_COMPARE_COMMITS_TITLE,
+            name
+        ));
+        self.files.clear()?;
+        self.files.set_revision(Some(compare.head));
+        self.files.set_title(format!(
+            "{} {}",
+            strings::BRANCH_COMPARE_FILES_TITLE,
+            short_hash(compare.merge_base)
+        ));
+        self.compare = Some(compare);
+
+        self.fetch_commits();
+        self.show()?;
+
+        Ok(())
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_diff.is_pending() || self.git_files.is_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.is_visible() {
+            match ev {
+                AsyncNotification::Diff => self.update_diff()?,
+                AsyncNotification::CompareFiles => {
+                    self.update_files()?;
+                    self.update_diff()?;
+                }
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// called when any tree component changed selection
+    pub fn update_diff(&mut self) -> Result<()> {
+        if self.is_visible() {
+            if let Some(compare) = &self.compare {
+                if let Some(f) = self.files.selection_file() {
+                    let diff_params = DiffParams {
+                        path: f.path.clone(),
+                        diff_type: DiffType::CommitToCommit(
+                            compare.merge_base,
+                            compare.head,
+                        ),
+                        options: self.diff.diff_options_for(&f.path),
+                    };
+
+                    if let Some((params, last)) =
+                        self.git_diff.last()?
+                    {
+                        if params == diff_params {
+                            self.diff.update(f.path, false, last)?;
+                            return Ok(());
+                        }
+                    }
+
+                    self.git_diff.request(diff_params)?;
+                }
+            }
+
+            self.diff.clear()?;
+        }
+
+        Ok(())
+    }
+
+    /// refetches the changed files if the diff options changed
+    pub fn update(&mut self) -> Result<()> {
+        if let Some(params) = self.files_params() {
+            self.git_files.fetch(params)?;
+        } else {
+            self.files.clear()?;
+        }
+
+        self.update_diff()?;
+
+        Ok(())
+    }
+
+    /// shows the fetched files if they are of the compared branches
+    fn update_files(&mut self) -> Result<()> {
+        if let Some(params) = self.files_params() {
+            if let Some((fetched, files)) =
+                self.git_files.current()?
+            {
+                if fetched == params {
+                    self.files.update(files.as_slice())?;
+                    return Ok(());
+                }
+            }
+
+            // an outdated request was still pending
+            self.git_files.fetch(params)?;
+        }
+
+        Ok(())
+    }
+
+    fn files_params(
+        &self,
+    ) -> Option<(CommitId, Option<CommitId>, DiffOptions)> {
+        self.compare.as_ref().map(|compare| {
+            (
+                compare.merge_base,
+                Some(compare.head),
+                self.options.borrow().diff,
+            )
+        })
+    }
+
+    fn update_commits(&mut self) {
+        let selection = self.commits.selection();
+        let selection_max = self.commits.selection_max();
+        if self.commits.items().needs_data(selection, selection_max) {
+            self.fetch_commits();
+        }
+    }
+
+    fn fetch_commits(&mut self) {
+        let want_min =
+            self.commits.selection().saturating_sub(SLICE_SIZE / 2);
+
+        let ids = self
+            .compare
+            .iter()
+            .flat_map(|c| c.commits.iter())
+            .skip(want_min)
+            .take(SLICE_SIZE)
+            .map(|id| (*id).into())
+            .collect::<Vec<_>>();
+
+        let commits = sync::get_commits_info(
+            CWD,
+            &ids,
+            self.commits.current_size().0.into(),
+        );
+
+        if let Ok(commits) = commits {
+            self.commits.items().set_items(want_min, commits);
+        }
+    }
+
+    fn set_focus(&mut self, focus: Focus) {
+        self.focus = focus;
+        self.files.focus(focus == Focus::Files);
+        self.files.show_selection(focus != Focus::Commits);
+        self.diff.focus(focus == Focus::Diff);
+    }
+
+    fn can_focus_diff(&self) -> bool {
+        self.files.selection_file().is_some()
+    }
+}
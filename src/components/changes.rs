@@ -6,7 +6,10 @@ use super::{
 use crate::{
     components::{CommandInfo, Component},
     keys,
-    queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
+    queue::{
+        Action, ExternalProgram, InternalEvent, NeedsUpdate, Queue,
+        ResetItem,
+    },
     strings,
     ui::style::SharedTheme,
 };
@@ -204,6 +207,12 @@ impl Component for ChangesComponent {
 
         let some_selection = self.selection().is_some();
 
+        out.push(CommandInfo::new(
+            commands::DIFF_TOOL,
+            self.files.is_file_seleted(),
+            self.focused(),
+        ));
+
         if self.is_working_dir {
             out.push(CommandInfo::new(
                 commands::STAGE_ALL,
@@ -275,9 +284,26 @@ impl Component for ChangesComponent {
                         if !self.is_working_dir
                             && !self.is_empty() =>
                     {
-                        self.queue
-                            .borrow_mut()
-                            .push_back(InternalEvent::SuspendPolling);
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::RunExternal(
+                                ExternalProgram::CommitEditor,
+                            ),
+                        );
+                        Ok(true)
+                    }
+                    keys::DIFF_TOOL => {
+                        if let Some(file) =
+                            self.files.selection_file()
+                        {
+                            self.queue.borrow_mut().push_back(
+                                InternalEvent::RunExternal(
+                                    ExternalProgram::DiffTool(
+                                        file.path,
+                                        !self.is_working_dir,
+                                    ),
+                                ),
+                            );
+                        }
                         Ok(true)
                     }
                     keys::STATUS_STAGE_FILE => {
@@ -4,7 +4,7 @@ use super::{
 };
 use crate::strings::COMMIT_EDITOR_MSG;
 use crate::{
-    get_app_config_path, keys,
+    external, get_app_config_path, keys,
     queue::{InternalEvent, NeedsUpdate, Queue},
     strings,
     ui::style::SharedTheme,
@@ -19,7 +19,6 @@ use std::env;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::PathBuf;
-use std::process::Command;
 use strings::commands;
 use sync::HookResult;
 use tui::{backend::Backend, layout::Rect, Frame};
@@ -107,6 +106,12 @@ impl Component for CommitComponent {
 
         self.input.clear();
         self.input.set_title(strings::COMMIT_TITLE.into());
+
+        // message prepared by a merge, cherry-pick or revert
+        if let Ok(Some(msg)) = sync::get_prepared_commit_msg(CWD) {
+            self.input.set_text(msg.trim_end().to_string());
+        }
+
         self.input.show()?;
 
         Ok(())
@@ -150,10 +155,10 @@ impl CommitComponent {
             anyhow!("unable to read editor command")
         })?;
 
-        Command::new(command)
-            .args(editor)
-            .status()
-            .map_err(|e| anyhow!("\"{}\": {}", command, e))?;
+        external::run_with_terminal(
+            command,
+            &editor.collect::<Vec<_>>(),
+        )?;
 
         let mut message = String::new();
 
@@ -13,7 +13,7 @@ use asyncgit::{
 };
 use crossterm::event::Event;
 use std::borrow::Cow;
-use sync::{CommitId, Tags};
+use sync::{CommitId, RefKind, Refs};
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
@@ -41,7 +41,7 @@ impl DetailsComponent {
     pub fn set_commit(
         &mut self,
         id: Option<CommitId>,
-        tags: &Tags,
+        refs: &Refs,
     ) -> Result<()> {
         self.tags.clear();
 
@@ -52,8 +52,12 @@ impl DetailsComponent {
         };
 
         if let Some(id) = id {
-            if let Some(tags) = tags.get(&id) {
-                self.tags.extend(tags.clone());
+            if let Some(refs) = refs.get(&id) {
+                self.tags.extend(
+                    refs.iter()
+                        .filter(|r| r.kind == RefKind::Tag)
+                        .map(|r| r.name.clone()),
+                );
             }
         }
 
@@ -5,11 +5,12 @@ use super::{
     Component, DrawableComponent, FileTreeComponent,
 };
 use crate::{
-    accessors, queue::Queue, strings, ui::style::SharedTheme,
+    accessors, options::SharedOptions, queue::Queue, strings,
+    ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::{CommitId, Tags},
+    sync::{CommitId, Refs},
     AsyncCommitFiles, AsyncNotification,
 };
 use crossbeam_channel::Sender;
@@ -25,6 +26,7 @@ pub struct CommitDetailsComponent {
     details: DetailsComponent,
     file_tree: FileTreeComponent,
     git_commit_files: AsyncCommitFiles,
+    options: SharedOptions,
     visible: bool,
 }
 
@@ -35,11 +37,13 @@ impl CommitDetailsComponent {
     pub fn new(
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
+        options: SharedOptions,
         theme: SharedTheme,
     ) -> Self {
         Self {
             details: DetailsComponent::new(theme.clone()),
             git_commit_files: AsyncCommitFiles::new(sender),
+            options,
             file_tree: FileTreeComponent::new(
                 "",
                 false,
@@ -68,15 +72,17 @@ impl CommitDetailsComponent {
     pub fn set_commit(
         &mut self,
         id: Option<CommitId>,
-        tags: &Tags,
+        refs: &Refs,
     ) -> Result<()> {
-        self.details.set_commit(id, tags)?;
+        self.details.set_commit(id, refs)?;
+        self.file_tree.set_revision(id);
 
         if let Some(id) = id {
-            if let Some((fetched_id, res)) =
+            let options = self.options.borrow().diff;
+            if let Some((fetched, res)) =
                 self.git_commit_files.current()?
             {
-                if fetched_id == id {
+                if fetched == (id, options) {
                     self.file_tree.update(res.as_slice())?;
                     self.file_tree
                         .set_title(self.get_files_title(false));
@@ -85,7 +91,7 @@ impl CommitDetailsComponent {
                 }
             }
             self.file_tree.clear()?;
-            self.git_commit_files.fetch(id)?;
+            self.git_commit_files.fetch(id, options)?;
             self.file_tree.set_title(self.get_files_title(true));
         } else {
             self.file_tree.set_title(self.get_files_title(false));
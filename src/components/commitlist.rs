@@ -1,4 +1,7 @@
-use super::utils::logitems::{ItemBatch, LogEntry};
+use super::utils::{
+    branch_to_string,
+    logitems::{ItemBatch, LogEntry},
+};
 use crate::{
     components::{
         CommandBlocking, CommandInfo, Component, DrawableComponent,
@@ -15,7 +18,9 @@ use crossterm::event::Event;
 use std::{
     borrow::Cow, cell::Cell, cmp, convert::TryFrom, time::Instant,
 };
-use sync::Tags;
+use sync::{
+    CommitId, GraphCell, GraphRow, RefInfo, Refs, UpstreamInfo,
+};
 use tui::{
     backend::Backend,
     layout::{Alignment, Rect},
@@ -25,16 +30,20 @@ use tui::{
 use unicode_width::UnicodeWidthStr;
 
 const ELEMENTS_PER_LINE: usize = 10;
+const MAX_GRAPH_LANES: usize = 16;
 
 ///
 pub struct CommitList {
     title: String,
     selection: usize,
     branch: Option<String>,
+    upstream: Option<UpstreamInfo>,
     count_total: usize,
     items: ItemBatch,
     scroll_state: (Instant, f32),
-    tags: Option<Tags>,
+    refs: Option<Refs>,
+    /// commit marked to compare against
+    marked: Option<CommitId>,
     current_size: Cell<(u16, u16)>,
     scroll_top: Cell<usize>,
     theme: SharedTheme,
@@ -47,9 +56,11 @@ impl CommitList {
             items: ItemBatch::default(),
             selection: 0,
             branch: None,
+            upstream: None,
             count_total: 0,
             scroll_state: (Instant::now(), 0_f32),
-            tags: None,
+            refs: None,
+            marked: None,
             current_size: Cell::new((0, 0)),
             scroll_top: Cell::new(0),
             theme,
@@ -62,11 +73,26 @@ impl CommitList {
         &mut self.items
     }
 
+    ///
+    pub fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
+    ///
+    pub fn set_selection(&mut self, selection: usize) {
+        self.selection = selection;
+    }
+
     ///
     pub fn set_branch(&mut self, name: Option<String>) {
         self.branch = name;
     }
 
+    ///
+    pub fn set_upstream(&mut self, upstream: Option<UpstreamInfo>) {
+        self.upstream = upstream;
+    }
+
     ///
     pub const fn selection(&self) -> usize {
         self.selection
@@ -89,24 +115,39 @@ impl CommitList {
     }
 
     ///
-    pub fn tags(&self) -> Option<&Tags> {
-        self.tags.as_ref()
+    pub fn refs(&self) -> Option<&Refs> {
+        self.refs.as_ref()
     }
 
     ///
-    pub fn has_tags(&self) -> bool {
-        self.tags.is_some()
+    pub fn has_refs(&self) -> bool {
+        self.refs.is_some()
     }
 
     ///
     pub fn clear(&mut self) {
-        self.tags = None;
+        self.refs = None;
         self.items.clear();
     }
 
     ///
-    pub fn set_tags(&mut self, tags: Tags) {
-        self.tags = Some(tags);
+    pub fn set_refs(&mut self, refs: Refs) {
+        self.refs = Some(refs);
+    }
+
+    ///
+    pub const fn marked(&self) -> Option<CommitId> {
+        self.marked
+    }
+
+    /// marks the selected commit, or unmarks it if it is marked
+    pub fn toggle_mark(&mut self) {
+        let selected = self.selected_entry().map(|e| e.id);
+        self.marked = if self.marked == selected {
+            None
+        } else {
+            selected
+        };
     }
 
     ///
@@ -181,16 +222,29 @@ impl CommitList {
         e: &'a LogEntry,
         selected: bool,
         txt: &mut Vec<Text<'a>>,
-        tags: Option<String>,
+        refs: Option<&'a [RefInfo]>,
         theme: &Theme,
         width: usize,
+        graph_lanes: usize,
     ) {
-        txt.reserve(ELEMENTS_PER_LINE);
+        txt.reserve(ELEMENTS_PER_LINE + graph_lanes * 2);
 
         let splitter_txt = Cow::from(" ");
         let splitter =
             Text::Styled(splitter_txt, theme.text(true, selected));
 
+        // commit graph
+        if graph_lanes > 0 {
+            add_graph(
+                e.graph.as_ref(),
+                graph_lanes,
+                selected,
+                txt,
+                theme,
+            );
+        }
+        let width = width.saturating_sub(graph_lanes * 2);
+
         // commit hash
         txt.push(Text::Styled(
             Cow::from(e.hash_short.as_str()),
@@ -219,15 +273,14 @@ impl CommitList {
 
         txt.push(splitter.clone());
 
-        // commit tags
-        txt.push(Text::Styled(
-            Cow::from(if let Some(tags) = tags {
-                format!(" {}", tags)
-            } else {
-                String::from("")
-            }),
-            theme.tags(selected),
-        ));
+        // commit refs
+        for r in refs.unwrap_or_default() {
+            txt.push(splitter.clone());
+            txt.push(Text::Styled(
+                Cow::from(r.name.as_str()),
+                theme.commit_ref(r.kind, selected),
+            ));
+        }
 
         txt.push(splitter);
 
@@ -244,6 +297,16 @@ impl CommitList {
 
         let mut txt = Vec::with_capacity(height * ELEMENTS_PER_LINE);
 
+        let graph_lanes = self
+            .items
+            .iter()
+            .skip(self.scroll_top.get())
+            .take(height)
+            .filter_map(|e| e.graph.as_ref().map(|g| g.cells.len()))
+            .max()
+            .unwrap_or_default()
+            .min(MAX_GRAPH_LANES);
+
         for (idx, e) in self
             .items
             .iter()
@@ -251,21 +314,36 @@ impl CommitList {
             .take(height)
             .enumerate()
         {
-            let tags = if let Some(tags) =
-                self.tags.as_ref().and_then(|t| t.get(&e.id))
-            {
-                Some(tags.join(" "))
+            let refs = self
+                .refs
+                .as_ref()
+                .and_then(|r| r.get(&e.id))
+                .map(Vec::as_slice);
+            let selected = idx + self.scroll_top.get() == selection;
+
+            // column with the marked commit
+            let width = if self.marked.is_some() {
+                txt.push(Text::Styled(
+                    Cow::from(if self.marked == Some(e.id) {
+                        "* "
+                    } else {
+                        "  "
+                    }),
+                    self.theme.commit_hash(selected),
+                ));
+                width.saturating_sub(2)
             } else {
-                None
+                width
             };
 
             Self::add_entry(
                 e,
-                idx + self.scroll_top.get() == selection,
+                selected,
                 &mut txt,
-                tags,
+                refs,
                 &self.theme,
                 width,
+                graph_lanes,
             );
         }
 
@@ -299,8 +377,12 @@ impl DrawableComponent for CommitList {
             selection,
         ));
 
-        let branch_post_fix =
-            self.branch.as_ref().map(|b| format!("- {{{}}}", b));
+        let branch_post_fix = self.branch.as_ref().map(|b| {
+            format!(
+                "- {{{}}}",
+                branch_to_string(b, self.upstream.as_ref())
+            )
+        });
 
         let title = format!(
             "{} {}/{} {}",
@@ -378,6 +460,67 @@ impl Component for CommitList {
     }
 }
 
+/// graph column padded to `lanes` lanes, each followed by
+/// the connection to the next one
+fn add_graph(
+    row: Option<&GraphRow>,
+    lanes: usize,
+    selected: bool,
+    txt: &mut Vec<Text<'_>>,
+    theme: &Theme,
+) {
+    let cells = row.map_or(&[][..], |r| &r.cells[..]);
+    let commit_lane = row.map_or(0, |r| r.commit_lane);
+
+    for lane in 0..lanes {
+        let cell = cells.get(lane).copied().unwrap_or_default();
+
+        txt.push(Text::Styled(
+            Cow::from(graph_symbol(cell)),
+            theme.commit_graph(lane, selected),
+        ));
+
+        // color connections like the lane they lead to
+        let connection_lane =
+            if lane >= commit_lane { lane + 1 } else { lane };
+
+        txt.push(Text::Styled(
+            Cow::from(if cell.has(GraphCell::RIGHT) {
+                "\u{2500}"
+            } else {
+                " "
+            }),
+            theme.commit_graph(connection_lane, selected),
+        ));
+    }
+}
+
+const fn graph_symbol(cell: GraphCell) -> &'static str {
+    if cell.has(GraphCell::COMMIT) {
+        return "\u{25cf}";
+    }
+
+    match (
+        cell.has(GraphCell::UP),
+        cell.has(GraphCell::DOWN),
+        cell.has(GraphCell::LEFT),
+        cell.has(GraphCell::RIGHT),
+    ) {
+        (true, true, false, false) => "\u{2502}",
+        (false, false, true, true) => "\u{2500}",
+        (true, true, true, true) => "\u{253c}",
+        (true, false, true, false) => "\u{256f}",
+        (true, false, false, true) => "\u{2570}",
+        (false, true, true, false) => "\u{256e}",
+        (false, true, false, true) => "\u{256d}",
+        (true, true, true, false) => "\u{2524}",
+        (true, true, false, true) => "\u{251c}",
+        (true, false, true, true) => "\u{2534}",
+        (false, true, true, true) => "\u{252c}",
+        _ => " ",
+    }
+}
+
 #[inline]
 fn string_width_align(s: &str, width: usize) -> String {
     static POSTFIX: &str = "..";
@@ -0,0 +1,305 @@
+use super::{
+    command_pump, event_pump, utils::short_hash, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DiffComponent,
+    DrawableComponent, FileTreeComponent,
+};
+use crate::{
+    accessors, keys, options::SharedOptions, queue::Queue, strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::CommitId, AsyncCompareFiles, AsyncDiff, AsyncNotification,
+    DiffParams, DiffType,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use strings::commands;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::Clear,
+    Frame,
+};
+
+/// files changed between two commits (or a commit and the workdir)
+/// with the diff of the selected one
+pub struct CompareCommitsComponent {
+    /// commits compared, `to` is the workdir if `None`
+    commits: Option<(CommitId, Option<CommitId>)>,
+    files: FileTreeComponent,
+    diff: DiffComponent,
+    git_diff: AsyncDiff,
+    git_files: AsyncCompareFiles,
+    options: SharedOptions,
+    visible: bool,
+}
+
+impl DrawableComponent for CompareCommitsComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.is_visible() {
+            let percentages = if self.diff.focused() {
+                (30, 70)
+            } else {
+                (50, 50)
+            };
+
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(
+                    [
+                        Constraint::Percentage(percentages.0),
+                        Constraint::Percentage(percentages.1),
+                    ]
+                    .as_ref(),
+                )
+                .split(rect);
+
+            f.render_widget(Clear, rect);
+
+            self.files.draw(f, chunks[0])?;
+            self.diff.draw(f, chunks[1])?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for CompareCommitsComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            command_pump(
+                out,
+                force_all,
+                self.components().as_slice(),
+            );
+
+            out.push(
+                CommandInfo::new(commands::CLOSE_POPUP, true, true)
+                    .order(1),
+            );
+
+            out.push(CommandInfo::new(
+                commands::DIFF_FOCUS_RIGHT,
+                self.can_focus_diff(),
+                !self.diff.focused() || force_all,
+            ));
+
+            out.push(CommandInfo::new(
+                commands::DIFF_FOCUS_LEFT,
+                true,
+                self.diff.focused() || force_all,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if event_pump(ev, self.components_mut().as_mut_slice())? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                match e {
+                    keys::EXIT_POPUP => {
+                        self.hide();
+                    }
+                    keys::FOCUS_RIGHT if self.can_focus_diff() => {
+                        self.files.focus(false);
+                        self.diff.focus(true);
+                    }
+                    keys::FOCUS_LEFT if self.diff.focused() => {
+                        self.files.focus(true);
+                        self.diff.focus(false);
+                    }
+                    _ => (),
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        self.files.focus(true);
+        self.files.show_selection(true);
+        self.diff.focus(false);
+        self.update()?;
+        Ok(())
+    }
+}
+
+impl CompareCommitsComponent {
+    accessors!(self, [diff, files]);
+
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        options: SharedOptions,
+        theme: SharedTheme,
+    ) -> Self {
+        Self {
+            files: FileTreeComponent::new(
+                strings::COMPARE_TITLE,
+                true,
+                Some(queue.clone()),
+                theme.clone(),
+            ),
+            diff: DiffComponent::new(
+                queue.clone(),
+                options.clone(),
+                theme,
+                true,
+            ),
+            commits: None,
+            git_diff: AsyncDiff::new(sender.clone()),
+            git_files: AsyncCompareFiles::new(sender),
+            options,
+            visible: false,
+        }
+    }
+
+    /// compares `from` with `to`, or with the workdir if `to` is `None`
+    pub fn open(
+        &mut self,
+        from: CommitId,
+        to: Option<CommitId>,
+    ) -> Result<()> {
+        self.commits = Some((from, to));
+        self.files.clear()?;
+        self.files.set_revision(to);
+        self.files.set_title(format!(
+            "{} {}..{}",
+            strings::COMPARE_TITLE,
+            short_hash(from),
+            to.map_or_else(
+                || String::from(strings::COMPARE_WORKDIR),
+                short_hash
+            ),
+        ));
+        self.show()?;
+
+        Ok(())
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_diff.is_pending() || self.git_files.is_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.is_visible() {
+            match ev {
+                AsyncNotification::Diff => self.update_diff()?,
+                AsyncNotification::CompareFiles => {
+                    self.update_files()?;
+                    self.update_diff()?;
+                }
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// called when any tree component changed selection
+    pub fn update_diff(&mut self) -> Result<()> {
+        if self.is_visible() {
+            if let Some((from, to)) = self.commits {
+                if let Some(f) = self.files.selection_file() {
+                    let diff_params = DiffParams {
+                        path: f.path.clone(),
+                        diff_type: to.map_or(
+                            DiffType::CommitToWorkdir(from),
+                            |to| DiffType::CommitToCommit(from, to),
+                        ),
+                        options: self.diff.diff_options_for(&f.path),
+                    };
+
+                    if let Some((params, last)) =
+                        self.git_diff.last()?
+                    {
+                        if params == diff_params {
+                            self.diff.update(f.path, false, last)?;
+                            return Ok(());
+                        }
+                    }
+
+                    self.git_diff.request(diff_params)?;
+                }
+            }
+
+            self.diff.clear()?;
+        }
+
+        Ok(())
+    }
+
+    /// refetches the changed files if the options changed (or the
+    /// workdir is compared, which may have changed)
+    pub fn update(&mut self) -> Result<()> {
+        if let Some((from, to)) = self.commits {
+            self.git_files.fetch((
+                from,
+                to,
+                self.options.borrow().diff,
+            ))?;
+        } else {
+            self.files.clear()?;
+        }
+
+        self.update_diff()?;
+
+        Ok(())
+    }
+
+    /// shows the fetched files if they are of the compared commits
+    fn update_files(&mut self) -> Result<()> {
+        if let Some((from, to)) = self.commits {
+            let params = (from, to, self.options.borrow().diff);
+
+            if let Some((fetched, files)) =
+                self.git_files.current()?
+            {
+                if fetched == params {
+                    self.files.update(files.as_slice())?;
+                    return Ok(());
+                }
+            }
+
+            // an outdated request was still pending
+            self.git_files.fetch(params)?;
+        }
+
+        Ok(())
+    }
+
+    fn can_focus_diff(&self) -> bool {
+        self.files.selection_file().is_some()
+    }
+}
@@ -0,0 +1,381 @@
+use super::{CommandBlocking, DrawableComponent};
+use crate::{
+    components::{CommandInfo, Component},
+    keys,
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings,
+    ui::{calc_scroll_top, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, ConflictFile, ConflictSide},
+    DiffLineType, CWD,
+};
+use crossterm::event::Event;
+use std::{borrow::Cow, cell::Cell};
+use strings::commands;
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    widgets::{Block, Borders, Paragraph, Text},
+    Frame,
+};
+
+/// a conflicted file: the merged workdir version with its conflicts
+/// or one of the versions it was merged from
+pub struct ConflictViewComponent {
+    path: Option<String>,
+    /// `None` shows the merged workdir file
+    side: Option<ConflictSide>,
+    file: ConflictFile,
+    /// `None` if the file does not exist in `side`
+    version: Option<Vec<String>>,
+    selected_hunk: usize,
+    scroll: usize,
+    scroll_top: Cell<usize>,
+    current_height: Cell<usize>,
+    focused: bool,
+    queue: Queue,
+    theme: SharedTheme,
+}
+
+impl ConflictViewComponent {
+    ///
+    pub fn new(queue: Queue, theme: SharedTheme) -> Self {
+        Self {
+            path: None,
+            side: None,
+            file: ConflictFile::default(),
+            version: None,
+            selected_hunk: 0,
+            scroll: 0,
+            scroll_top: Cell::new(0),
+            current_height: Cell::new(0),
+            focused: false,
+            queue,
+            theme,
+        }
+    }
+
+    /// show (or reload) the conflicted file `path`
+    pub fn update(&mut self, path: String) -> Result<()> {
+        if self.path.as_ref() != Some(&path) {
+            self.selected_hunk = 0;
+            self.scroll = 0;
+            self.scroll_top.set(0);
+            self.path = Some(path);
+        }
+
+        self.load()
+    }
+
+    ///
+    pub fn clear(&mut self) {
+        self.path = None;
+        self.file = ConflictFile::default();
+        self.version = None;
+    }
+
+    /// switch between the merged file, ours, theirs and base
+    pub fn cycle_view(&mut self) -> Result<()> {
+        self.side = match self.side {
+            None => Some(ConflictSide::Ours),
+            Some(ConflictSide::Ours) => Some(ConflictSide::Theirs),
+            Some(ConflictSide::Theirs) => Some(ConflictSide::Base),
+            Some(ConflictSide::Base) => None,
+        };
+        self.scroll = 0;
+        self.scroll_top.set(0);
+
+        self.load()
+    }
+
+    fn load(&mut self) -> Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        match self.side {
+            None => {
+                self.file = sync::get_conflict_file(CWD, path)?;
+                self.selected_hunk = self
+                    .selected_hunk
+                    .min(self.file.hunks.len().saturating_sub(1));
+            }
+            Some(side) => {
+                self.version = sync::get_conflict_version(
+                    CWD, path, side,
+                )?
+                .map(|content| {
+                    content.lines().map(String::from).collect()
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn title(&self) -> String {
+        let view = match self.side {
+            None => strings::CONFLICT_VIEW_MERGED,
+            Some(ConflictSide::Ours) => strings::CONFLICT_VIEW_OURS,
+            Some(ConflictSide::Theirs) => {
+                strings::CONFLICT_VIEW_THEIRS
+            }
+            Some(ConflictSide::Base) => strings::CONFLICT_VIEW_BASE,
+        };
+
+        let conflicts = if self.side.is_none() {
+            format!(
+                " {}/{}",
+                (self.selected_hunk + 1).min(self.file.hunks.len()),
+                self.file.hunks.len()
+            )
+        } else {
+            String::new()
+        };
+
+        format!(
+            "{}{} [{}]{}",
+            strings::TITLE_CONFLICT,
+            self.path.as_deref().unwrap_or_default(),
+            view,
+            conflicts
+        )
+    }
+
+    fn line_count(&self) -> usize {
+        if self.side.is_none() {
+            self.file.lines.len()
+        } else {
+            self.version.as_ref().map_or(0, Vec::len)
+        }
+    }
+
+    fn get_text(&self, height: usize) -> Vec<Text> {
+        if self.side.is_some() {
+            return match &self.version {
+                Some(lines) => lines
+                    .iter()
+                    .skip(self.scroll_top.get())
+                    .take(height)
+                    .map(|line| {
+                        Text::Raw(Cow::from(format!(
+                            "{}\n",
+                            line.replace('\t', "  ")
+                        )))
+                    })
+                    .collect(),
+                None => vec![Text::Styled(
+                    Cow::from(strings::CONFLICT_VERSION_MISSING),
+                    self.theme.text(false, false),
+                )],
+            };
+        }
+
+        self.file
+            .lines
+            .iter()
+            .enumerate()
+            .skip(self.scroll_top.get())
+            .take(height)
+            .map(|(idx, line)| {
+                Text::Styled(
+                    Cow::from(format!(
+                        "{}\n",
+                        line.replace('\t', "  ")
+                    )),
+                    self.line_style(idx),
+                )
+            })
+            .collect()
+    }
+
+    fn line_style(&self, idx: usize) -> tui::style::Style {
+        let hunk = self
+            .file
+            .hunks
+            .iter()
+            .enumerate()
+            .find(|(_, h)| h.start <= idx && idx <= h.end);
+
+        match hunk {
+            Some((hunk_idx, hunk)) => {
+                let selected =
+                    self.focused && hunk_idx == self.selected_hunk;
+
+                let typ = if hunk
+                    .side_lines(ConflictSide::Ours)
+                    .contains(&idx)
+                {
+                    DiffLineType::Add
+                } else if hunk
+                    .side_lines(ConflictSide::Theirs)
+                    .contains(&idx)
+                {
+                    DiffLineType::Delete
+                } else {
+                    DiffLineType::Header
+                };
+
+                self.theme.diff_line(typ, selected)
+            }
+            None => self.theme.diff_line(DiffLineType::None, false),
+        }
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        if self.side.is_none() && !self.file.hunks.is_empty() {
+            self.selected_hunk = if down {
+                (self.selected_hunk + 1)
+                    .min(self.file.hunks.len().saturating_sub(1))
+            } else {
+                self.selected_hunk.saturating_sub(1)
+            };
+        } else {
+            let max = self
+                .line_count()
+                .saturating_sub(self.current_height.get());
+            self.scroll = if down {
+                (self.scroll + 1).min(max)
+            } else {
+                self.scroll.saturating_sub(1)
+            };
+        }
+    }
+
+    fn update_scroll(&self, height: usize) {
+        if self.side.is_some() {
+            self.scroll_top.set(self.scroll);
+        } else if let Some(hunk) =
+            self.file.hunks.get(self.selected_hunk)
+        {
+            // show the whole hunk if it fits, at least its start
+            let top = calc_scroll_top(
+                self.scroll_top.get(),
+                height,
+                hunk.end,
+            );
+            self.scroll_top
+                .set(calc_scroll_top(top, height, hunk.start));
+        }
+    }
+
+    fn take_side(&mut self, side: ConflictSide) -> Result<()> {
+        if let Some(path) = &self.path {
+            if self.side.is_none()
+                && self.selected_hunk < self.file.hunks.len()
+            {
+                sync::resolve_conflict_hunk(
+                    CWD,
+                    path,
+                    self.selected_hunk,
+                    side,
+                )?;
+                self.load()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl DrawableComponent for ConflictViewComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        r: Rect,
+    ) -> Result<()> {
+        let height = r.height.saturating_sub(2) as usize;
+        self.current_height.set(height);
+        self.update_scroll(height);
+
+        let title = self.title();
+
+        f.render_widget(
+            Paragraph::new(self.get_text(height).iter())
+                .block(
+                    Block::default()
+                        .title(title.as_str())
+                        .borders(Borders::ALL)
+                        .border_style(self.theme.block(self.focused))
+                        .title_style(self.theme.title(self.focused)),
+                )
+                .alignment(Alignment::Left),
+            r,
+        );
+
+        Ok(())
+    }
+}
+
+impl Component for ConflictViewComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        _force_all: bool,
+    ) -> CommandBlocking {
+        out.push(CommandInfo::new(
+            commands::SCROLL,
+            self.line_count() > 0,
+            self.focused,
+        ));
+
+        let hunk_selected = self.side.is_none()
+            && self.selected_hunk < self.file.hunks.len();
+
+        out.push(CommandInfo::new(
+            commands::CONFLICT_HUNK_OURS,
+            hunk_selected,
+            self.focused,
+        ));
+        out.push(CommandInfo::new(
+            commands::CONFLICT_HUNK_THEIRS,
+            hunk_selected,
+            self.focused,
+        ));
+
+        CommandBlocking::PassingOn
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.focused {
+            if let Event::Key(e) = ev {
+                let side = match e {
+                    keys::MOVE_DOWN | keys::MOVE_UP => {
+                        self.move_selection(e == keys::MOVE_DOWN);
+                        return Ok(true);
+                    }
+                    keys::CONFLICT_OURS => ConflictSide::Ours,
+                    keys::CONFLICT_THEIRS => ConflictSide::Theirs,
+                    _ => return Ok(false),
+                };
+
+                if let Err(e) = self.take_side(side) {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "resolve error:\n{}",
+                            e
+                        )),
+                    );
+                }
+
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Update(NeedsUpdate::ALL),
+                );
+
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn focused(&self) -> bool {
+        self.focused
+    }
+    fn focus(&mut self, focus: bool) {
+        self.focused = focus;
+    }
+}
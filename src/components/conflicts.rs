@@ -0,0 +1,185 @@
+use super::{
+    filetree::FileTreeComponent, utils::filetree::FileTreeItem,
+    CommandBlocking, DrawableComponent,
+};
+use crate::{
+    components::{CommandInfo, Component},
+    keys,
+    queue::{ExternalProgram, InternalEvent, NeedsUpdate, Queue},
+    strings, try_or_popup,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{sync, sync::ConflictSide, StatusItem, CWD};
+use crossterm::event::Event;
+use strings::commands;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// conflicted files of a merge, rebase, cherry-pick or revert
+pub struct ConflictsComponent {
+    files: FileTreeComponent,
+    queue: Queue,
+}
+
+impl ConflictsComponent {
+    ///
+    pub fn new(queue: Queue, theme: SharedTheme) -> Self {
+        Self {
+            files: FileTreeComponent::new(
+                strings::TITLE_CONFLICTS,
+                false,
+                Some(queue.clone()),
+                theme,
+            ),
+            queue,
+        }
+    }
+
+    ///
+    pub fn update(&mut self, list: &[StatusItem]) -> Result<()> {
+        self.files.set_title(format!(
+            "{} ({})",
+            strings::TITLE_CONFLICTS,
+            list.len()
+        ));
+        self.files.update(list)
+    }
+
+    ///
+    pub fn selection(&self) -> Option<FileTreeItem> {
+        self.files.selection()
+    }
+
+    ///
+    pub fn focus_select(&mut self, focus: bool) {
+        self.files.focus(focus);
+        self.files.show_selection(focus);
+    }
+
+    /// returns true if list is empty
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    ///
+    pub fn is_file_seleted(&self) -> bool {
+        self.files.is_file_seleted()
+    }
+
+    fn take_side(&self, side: ConflictSide) -> Result<bool> {
+        if let Some(file) = self.files.selection_file() {
+            sync::resolve_conflict_file(CWD, &file.path, side)?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn mark_resolved(&self) -> Result<bool> {
+        if let Some(file) = self.files.selection_file() {
+            sync::mark_conflict_resolved(CWD, &file.path)?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+}
+
+impl DrawableComponent for ConflictsComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        r: Rect,
+    ) -> Result<()> {
+        self.files.draw(f, r)
+    }
+}
+
+impl Component for ConflictsComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        self.files.commands(out, force_all);
+
+        let file_selected = self.is_file_seleted();
+
+        out.push(CommandInfo::new(
+            commands::CONFLICT_MARK_RESOLVED,
+            file_selected,
+            self.focused() || force_all,
+        ));
+        out.push(CommandInfo::new(
+            commands::CONFLICT_TAKE_OURS,
+            file_selected,
+            self.focused() || force_all,
+        ));
+        out.push(CommandInfo::new(
+            commands::CONFLICT_TAKE_THEIRS,
+            file_selected,
+            self.focused() || force_all,
+        ));
+        out.push(CommandInfo::new(
+            commands::MERGE_TOOL,
+            file_selected,
+            self.focused() || force_all,
+        ));
+
+        CommandBlocking::PassingOn
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.files.event(ev)? {
+            return Ok(true);
+        }
+
+        if self.focused() {
+            if let Event::Key(e) = ev {
+                let res = match e {
+                    keys::CONFLICT_MARK_RESOLVED => {
+                        self.mark_resolved()
+                    }
+                    keys::CONFLICT_OURS => {
+                        self.take_side(ConflictSide::Ours)
+                    }
+                    keys::CONFLICT_THEIRS => {
+                        self.take_side(ConflictSide::Theirs)
+                    }
+                    keys::MERGE_TOOL => {
+                        if let Some(file) =
+                            self.files.selection_file()
+                        {
+                            self.queue.borrow_mut().push_back(
+                                InternalEvent::RunExternal(
+                                    ExternalProgram::MergeTool(
+                                        file.path,
+                                    ),
+                                ),
+                            );
+                        }
+                        return Ok(true);
+                    }
+                    _ => return Ok(false),
+                };
+
+                try_or_popup!(self, "resolve error:", res);
+
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Update(NeedsUpdate::ALL),
+                );
+
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn focused(&self) -> bool {
+        self.files.focused()
+    }
+    fn focus(&mut self, focus: bool) {
+        self.files.focus(focus)
+    }
+}
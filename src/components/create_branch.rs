@@ -0,0 +1,139 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, CommitId},
+    CWD,
+};
+use crossterm::event::{Event, KeyCode};
+use strings::commands;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+pub struct CreateBranchComponent {
+    input: TextInputComponent,
+    target: Option<CommitId>,
+    queue: Queue,
+}
+
+impl DrawableComponent for CreateBranchComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for CreateBranchComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                commands::CREATE_BRANCH_CONFIRM_MSG,
+                !self.input.get_text().is_empty(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if let KeyCode::Enter = e.code {
+                    if !self.input.get_text().is_empty() {
+                        self.create_branch();
+                    }
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl CreateBranchComponent {
+    ///
+    pub fn new(queue: Queue, theme: SharedTheme) -> Self {
+        Self {
+            queue,
+            target: None,
+            input: TextInputComponent::new(
+                theme,
+                strings::CREATE_BRANCH_POPUP_TITLE,
+                strings::CREATE_BRANCH_POPUP_MSG,
+            ),
+        }
+    }
+
+    /// open the popup to create a branch on `target` (HEAD if `None`)
+    pub fn open(&mut self, target: Option<CommitId>) -> Result<()> {
+        self.target = target;
+        self.input.clear();
+        self.show()
+    }
+
+    fn create_branch(&mut self) {
+        let res = sync::create_branch(
+            CWD,
+            self.input.get_text().as_str(),
+            self.target,
+        );
+
+        self.input.clear();
+        self.hide();
+
+        match res {
+            Ok(_) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Update(NeedsUpdate::ALL),
+                );
+            }
+            Err(e) => {
+                log::error!("create branch: {}", e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "create branch error:\n{}",
+                        e,
+                    )),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use super::{
+    textinput::{InputType, TextInputComponent},
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent,
+};
+use crate::{
+    queue::{InternalEvent, Queue, RemoteOperation},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, BasicAuthCredential, CredentialCache},
+    CWD,
+};
+use crossterm::event::{Event, KeyCode};
+use std::{cell::RefCell, rc::Rc};
+use strings::commands;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// credentials entered in this session, shared by all remote operations
+pub type SharedCredentials = Rc<RefCell<CredentialCache>>;
+
+/// asks for username and password (or key passphrase) one after another
+pub struct CredComponent {
+    input_username: TextInputComponent,
+    input_password: TextInputComponent,
+    url: String,
+    operation: Option<RemoteOperation>,
+    credentials: SharedCredentials,
+    queue: Queue,
+}
+
+impl DrawableComponent for CredComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input_username.draw(f, rect)?;
+        self.input_password.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for CredComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input_username.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                commands::CRED_CONFIRM,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.input_username.is_visible() {
+            if self.input_username.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if let KeyCode::Enter = e.code {
+                    self.input_username.hide();
+                    self.input_password.show()?;
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        } else if self.input_password.is_visible() {
+            if self.input_password.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if let KeyCode::Enter = e.code {
+                    self.confirm();
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input_username.is_visible()
+            || self.input_password.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input_username.hide();
+        self.input_password.hide();
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input_password.hide();
+        self.input_username.show()?;
+
+        Ok(())
+    }
+}
+
+impl CredComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        credentials: SharedCredentials,
+        theme: SharedTheme,
+    ) -> Self {
+        let mut input_password = TextInputComponent::new(
+            theme.clone(),
+            strings::CRED_PASSWORD_POPUP_TITLE,
+            strings::CRED_PASSWORD_POPUP_MSG,
+        );
+        input_password.set_input_type(InputType::Password);
+
+        Self {
+            input_username: TextInputComponent::new(
+                theme,
+                strings::CRED_USERNAME_POPUP_TITLE,
+                strings::CRED_USERNAME_POPUP_MSG,
+            ),
+            input_password,
+            url: String::new(),
+            operation: None,
+            credentials,
+            queue: queue.clone(),
+        }
+    }
+
+    /// ask for the credentials of `url` and retry `operation` with them
+    pub fn open(
+        &mut self,
+        url: String,
+        operation: RemoteOperation,
+    ) -> Result<()> {
+        let known =
+            sync::extract_username_password(CWD, url.as_str())
+                .unwrap_or_default();
+
+        self.input_username.set_title(format!(
+            "{} {}",
+            strings::CRED_USERNAME_POPUP_TITLE,
+            url
+        ));
+        self.input_username
+            .set_text(known.username.unwrap_or_default());
+        self.input_password.clear();
+
+        self.url = url;
+        self.operation = Some(operation);
+
+        self.show()
+    }
+
+    fn confirm(&mut self) {
+        let username = self.input_username.get_text().clone();
+        let password = self.input_password.get_text().clone();
+
+        self.credentials.borrow_mut().insert(
+            self.url.clone(),
+            BasicAuthCredential::new(Some(username), Some(password)),
+        );
+
+        if let Some(op) = self.operation.take() {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::Remote(op));
+        }
+
+        self.input_password.clear();
+        self.hide();
+    }
+}
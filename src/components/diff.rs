@@ -1,19 +1,30 @@
-use super::{CommandBlocking, DrawableComponent, ScrollType};
+use super::{
+    utils::split_diff::{split_rows, SplitRow},
+    CommandBlocking, DrawableComponent, ScrollType,
+};
 use crate::{
     components::{CommandInfo, Component},
     keys,
+    options::SharedOptions,
     queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
     strings,
-    ui::{calc_scroll_top, style::SharedTheme},
+    ui::{
+        calc_scroll_top,
+        style::SharedTheme,
+        syntax::{self, HighlightedLine},
+    },
+};
+use asyncgit::{
+    hash, sync, DiffLine, DiffLinePosition, DiffLineType, FileDiff,
+    CWD,
 };
-use asyncgit::{hash, sync, DiffLine, DiffLineType, FileDiff, CWD};
 use bytesize::ByteSize;
 use crossterm::event::Event;
 use std::{borrow::Cow, cell::Cell, cmp, path::Path};
 use strings::commands;
 use tui::{
     backend::Backend,
-    layout::{Alignment, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     symbols,
     widgets::{Block, Borders, Paragraph, Text},
     Frame,
@@ -21,6 +32,9 @@ use tui::{
 
 use anyhow::Result;
 
+/// larger diffs are not syntax highlighted
+const MAX_HIGHLIGHT_LINES: usize = 5000;
+
 #[derive(Default)]
 struct Current {
     path: String,
@@ -31,27 +45,52 @@ struct Current {
 ///
 pub struct DiffComponent {
     diff: Option<FileDiff>,
+    /// syntax highlighting of each line of each hunk
+    highlights: Vec<Vec<HighlightedLine>>,
+    /// old and new lines side by side instead of one after another
+    split: bool,
+    /// rows of the side-by-side view
+    rows: Vec<SplitRow>,
     selection: usize,
+    /// other end of a line range selected with shift
+    selection_anchor: Option<usize>,
     selected_hunk: Option<usize>,
     current_size: Cell<(u16, u16)>,
     focused: bool,
     current: Current,
     scroll_top: Cell<usize>,
-    queue: Option<Queue>,
+    queue: Queue,
+    options: SharedOptions,
+    /// file whose diff is loaded in full regardless of the limits
+    full_diff: Option<String>,
     theme: SharedTheme,
+    /// no staging or reverting (diffs of commits)
+    is_immutable: bool,
 }
 
 impl DiffComponent {
     ///
-    pub fn new(queue: Option<Queue>, theme: SharedTheme) -> Self {
+    pub fn new(
+        queue: Queue,
+        options: SharedOptions,
+        theme: SharedTheme,
+        is_immutable: bool,
+    ) -> Self {
         Self {
             focused: false,
             queue,
+            options,
+            full_diff: None,
+            is_immutable,
             current: Current::default(),
             selected_hunk: None,
             diff: None,
+            highlights: Vec::new(),
+            split: false,
+            rows: Vec::new(),
             current_size: Cell::new((0, 0)),
             selection: 0,
+            selection_anchor: None,
             scroll_top: Cell::new(0),
             theme,
         }
@@ -67,12 +106,25 @@ impl DiffComponent {
     pub fn current(&self) -> (String, bool) {
         (self.current.path.clone(), self.current.is_stage)
     }
+    /// options to diff the file at `path` with, without limits if its
+    /// diff was requested in full
+    pub fn diff_options_for(&self, path: &str) -> sync::DiffOptions {
+        let options = self.diff_options();
+        if self.full_diff.as_deref() == Some(path) {
+            options.unlimited()
+        } else {
+            options
+        }
+    }
     ///
     pub fn clear(&mut self) -> Result<()> {
         self.current = Current::default();
         self.diff = None;
+        self.highlights.clear();
+        self.rows.clear();
         self.scroll_top.set(0);
         self.selection = 0;
+        self.selection_anchor = None;
         self.selected_hunk = None;
 
         Ok(())
@@ -86,6 +138,11 @@ impl DiffComponent {
     ) -> Result<()> {
         let hash = hash(&diff);
 
+        if self.full_diff.as_ref().map_or(false, |full| *full != path)
+        {
+            self.full_diff = None;
+        }
+
         if self.current.hash != hash {
             self.current = Current {
                 path,
@@ -96,9 +153,13 @@ impl DiffComponent {
             self.selected_hunk =
                 Self::find_selected_hunk(&diff, self.selection)?;
 
+            self.highlights =
+                Self::highlight(&self.current.path, &diff);
+            self.rows = split_rows(&diff);
             self.diff = Some(diff);
             self.scroll_top.set(0);
             self.selection = 0;
+            self.selection_anchor = None;
         }
 
         Ok(())
@@ -107,30 +168,52 @@ impl DiffComponent {
     fn move_selection(
         &mut self,
         move_type: ScrollType,
+        extend: bool,
     ) -> Result<()> {
+        self.selection_anchor = if extend {
+            self.selection_anchor.or(Some(self.selection))
+        } else {
+            None
+        };
+
         if let Some(diff) = &self.diff {
             let old = self.selection;
 
-            let max = diff.lines.saturating_sub(1) as usize;
+            // the split view moves by rows
+            let (pos, max) = if self.split {
+                (
+                    self.row_of(self.selection),
+                    self.rows.len().saturating_sub(1),
+                )
+            } else {
+                (
+                    self.selection,
+                    diff.lines.saturating_sub(1) as usize,
+                )
+            };
 
-            self.selection = match move_type {
-                ScrollType::Down => old.saturating_add(1),
-                ScrollType::Up => old.saturating_sub(1),
+            let pos = match move_type {
+                ScrollType::Down => pos.saturating_add(1),
+                ScrollType::Up => pos.saturating_sub(1),
                 ScrollType::Home => 0,
                 ScrollType::End => max,
-                ScrollType::PageDown => {
-                    self.selection.saturating_add(
-                        self.current_size.get().1.saturating_sub(1)
-                            as usize,
-                    )
-                }
-                ScrollType::PageUp => self.selection.saturating_sub(
+                ScrollType::PageDown => pos.saturating_add(
+                    self.current_size.get().1.saturating_sub(1)
+                        as usize,
+                ),
+                ScrollType::PageUp => pos.saturating_sub(
                     self.current_size.get().1.saturating_sub(1)
                         as usize,
                 ),
             };
 
-            self.selection = cmp::min(max, self.selection);
+            let pos = cmp::min(max, pos);
+
+            self.selection = if self.split {
+                self.rows.get(pos).map_or(0, SplitRow::first_line)
+            } else {
+                pos
+            };
 
             if old != self.selection {
                 self.selected_hunk =
@@ -140,6 +223,122 @@ impl DiffComponent {
         Ok(())
     }
 
+    /// lines from the anchor to the cursor (inclusive)
+    fn selected_lines(&self) -> (usize, usize) {
+        let anchor = self.selection_anchor.unwrap_or(self.selection);
+        (
+            cmp::min(anchor, self.selection),
+            cmp::max(anchor, self.selection),
+        )
+    }
+
+    fn is_line_selected(&self, line: usize) -> bool {
+        let (start, end) = self.selected_lines();
+        start <= line && line <= end
+    }
+
+    /// row of the side-by-side view showing `line`
+    fn row_of(&self, line: usize) -> usize {
+        self.rows
+            .iter()
+            .position(|row| row.contains(line))
+            .unwrap_or(0)
+    }
+
+    /// rows from the one of the anchor to the one of the cursor
+    fn selected_rows(&self) -> (usize, usize) {
+        let (start, end) = self.selected_lines();
+        let (start, end) = (self.row_of(start), self.row_of(end));
+        (cmp::min(start, end), cmp::max(start, end))
+    }
+
+    /// positions of the added or deleted lines in the selection
+    fn selected_changes(&self) -> Vec<DiffLinePosition> {
+        let mut selected: Vec<usize> = if self.split
+            && self.can_split()
+        {
+            // paired lines of a row are not adjacent
+            let (start, end) = self.selected_rows();
+            self.rows[start..=end]
+                .iter()
+                .flat_map(|row| row.old.into_iter().chain(row.new))
+                .collect()
+        } else {
+            let (start, end) = self.selected_lines();
+            (start..=end).collect()
+        };
+        selected.sort_unstable();
+        selected.dedup();
+
+        self.diff.as_ref().map_or_else(Vec::new, |diff| {
+            let lines: Vec<&DiffLine> = diff
+                .hunks
+                .iter()
+                .flat_map(|hunk| hunk.lines.iter())
+                .collect();
+
+            selected
+                .into_iter()
+                .filter_map(|idx| lines.get(idx))
+                .filter(|line| {
+                    matches!(
+                        line.line_type,
+                        DiffLineType::Add | DiffLineType::Delete
+                    )
+                })
+                .map(|line| line.position)
+                .collect()
+        })
+    }
+
+    /// old (deleted and context) and new (added and context) lines
+    /// of a hunk are highlighted separately
+    fn highlight(
+        path: &str,
+        diff: &FileDiff,
+    ) -> Vec<Vec<HighlightedLine>> {
+        if diff.lines > MAX_HIGHLIGHT_LINES {
+            return Vec::new();
+        }
+
+        diff.hunks
+            .iter()
+            .map(|hunk| {
+                let side = |typ: DiffLineType| {
+                    syntax::highlight(
+                        path,
+                        hunk.lines
+                            .iter()
+                            .filter(|line| {
+                                line.line_type == typ
+                                    || line.line_type
+                                        == DiffLineType::None
+                            })
+                            .map(|line| line.content.as_str()),
+                    )
+                    .into_iter()
+                };
+
+                let mut old = side(DiffLineType::Delete);
+                let mut new = side(DiffLineType::Add);
+
+                hunk.lines
+                    .iter()
+                    .map(|line| match line.line_type {
+                        DiffLineType::Delete => old.next(),
+                        DiffLineType::Add => new.next(),
+                        DiffLineType::None => {
+                            old.next();
+                            new.next()
+                        }
+                        DiffLineType::Header => None,
+                    })
+                    .map(Option::unwrap_or_default)
+                    .collect()
+            })
+            .collect()
+    }
+
     fn find_selected_hunk(
         diff: &FileDiff,
         line_selected: usize,
@@ -163,51 +362,62 @@ impl DiffComponent {
         Ok(None)
     }
 
+    /// what is shown instead of the hunks: whether the file is binary
+    /// and its size change
+    fn add_summary(&self, diff: &FileDiff, res: &mut Vec<Text>) {
+        if diff.binary {
+            res.push(Text::Styled(
+                Cow::from(diff.mime.as_ref().map_or_else(
+                    || format!("{}\n", strings::DIFF_BINARY),
+                    |mime| {
+                        format!(
+                            "{} ({})\n",
+                            strings::DIFF_BINARY,
+                            mime
+                        )
+                    },
+                )),
+                self.theme.text(true, false),
+            ));
+        }
+
+        let is_positive = diff.size_delta >= 0;
+        let delta_byte_size =
+            ByteSize::b(diff.size_delta.abs() as u64);
+        let sign = if is_positive { "+" } else { "-" };
+        res.extend(vec![
+            Text::Raw(Cow::from("size: ")),
+            Text::Styled(
+                Cow::from(format!("{}", ByteSize::b(diff.sizes.0))),
+                self.theme.text(false, false),
+            ),
+            Text::Raw(Cow::from(" -> ")),
+            Text::Styled(
+                Cow::from(format!("{}", ByteSize::b(diff.sizes.1))),
+                self.theme.text(false, false),
+            ),
+            Text::Raw(Cow::from(" (")),
+            Text::Styled(
+                Cow::from(format!("{}{:}", sign, delta_byte_size)),
+                self.theme.diff_line(
+                    if is_positive {
+                        DiffLineType::Add
+                    } else {
+                        DiffLineType::Delete
+                    },
+                    false,
+                ),
+            ),
+            Text::Raw(Cow::from(")")),
+        ]);
+    }
+
     fn get_text(&self, width: u16, height: u16) -> Result<Vec<Text>> {
         let mut res = Vec::new();
         if let Some(diff) = &self.diff {
             if diff.hunks.is_empty() {
-                let is_positive = diff.size_delta >= 0;
-                let delta_byte_size =
-                    ByteSize::b(diff.size_delta.abs() as u64);
-                let sign = if is_positive { "+" } else { "-" };
-                res.extend(vec![
-                    Text::Raw(Cow::from("size: ")),
-                    Text::Styled(
-                        Cow::from(format!(
-                            "{}",
-                            ByteSize::b(diff.sizes.0)
-                        )),
-                        self.theme.text(false, false),
-                    ),
-                    Text::Raw(Cow::from(" -> ")),
-                    Text::Styled(
-                        Cow::from(format!(
-                            "{}",
-                            ByteSize::b(diff.sizes.1)
-                        )),
-                        self.theme.text(false, false),
-                    ),
-                    Text::Raw(Cow::from(" (")),
-                    Text::Styled(
-                        Cow::from(format!(
-                            "{}{:}",
-                            sign, delta_byte_size
-                        )),
-                        self.theme.diff_line(
-                            if is_positive {
-                                DiffLineType::Add
-                            } else {
-                                DiffLineType::Delete
-                            },
-                            false,
-                        ),
-                    ),
-                    Text::Raw(Cow::from(")")),
-                ]);
+                self.add_summary(diff, &mut res);
             } else {
-                let selection = self.selection;
-
                 let min = self.scroll_top.get();
                 let max = min + height as usize;
 
@@ -229,6 +439,8 @@ impl DiffComponent {
                     if Self::hunk_visible(
                         hunk_min, hunk_max, min, max,
                     ) {
+                        let highlights = self.highlights.get(i);
+
                         for (i, line) in hunk.lines.iter().enumerate()
                         {
                             if line_cursor >= min
@@ -238,7 +450,10 @@ impl DiffComponent {
                                     &mut res,
                                     width,
                                     line,
-                                    selection == line_cursor,
+                                    highlights.and_then(|h| h.get(i)),
+                                    self.is_line_selected(
+                                        line_cursor,
+                                    ),
                                     hunk_selected,
                                     i == hunk_len as usize - 1,
                                     &self.theme,
@@ -257,10 +472,86 @@ impl DiffComponent {
         Ok(res)
     }
 
+    /// one column of the side-by-side view
+    fn get_split_text(
+        &self,
+        old: bool,
+        width: u16,
+        height: u16,
+        (sel_start, sel_end): (usize, usize),
+    ) -> Vec<Text> {
+        let mut res = Vec::new();
+        let empty = DiffLine::default();
+
+        if let Some(diff) = &self.diff {
+            for (row_idx, row) in self
+                .rows
+                .iter()
+                .enumerate()
+                .skip(self.scroll_top.get())
+                .take(height as usize)
+            {
+                let line_idx = if old { row.old } else { row.new }
+                    .map(|idx| idx - row.hunk_start);
+
+                let line = line_idx.and_then(|idx| {
+                    diff.hunks[row.hunk].lines.get(idx)
+                });
+                let highlighted = line_idx.and_then(|idx| {
+                    self.highlights
+                        .get(row.hunk)
+                        .and_then(|hunk| hunk.get(idx))
+                });
+
+                Self::add_line(
+                    &mut res,
+                    width,
+                    line.unwrap_or(&empty),
+                    highlighted,
+                    sel_start <= row_idx && row_idx <= sel_end,
+                    self.selected_hunk == Some(row.hunk),
+                    row.end_of_hunk,
+                    &self.theme,
+                );
+            }
+        }
+
+        res
+    }
+
+    fn toggle_split(&mut self) {
+        self.split = !self.split;
+        self.scroll_top.set(0);
+    }
+
+    fn is_truncated(&self) -> bool {
+        self.diff.as_ref().map_or(false, |diff| diff.truncated)
+    }
+
+    /// requests the current file's diff again without the limits
+    fn load_full(&mut self) {
+        self.full_diff = Some(self.current.path.clone());
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::DIFF));
+    }
+
+    /// a hunk of a truncated diff may lack lines that would be applied
+    /// unseen
+    fn can_apply_hunk(&self) -> bool {
+        self.selected_hunk.is_some() && !self.is_truncated()
+    }
+
+    fn can_split(&self) -> bool {
+        !self.rows.is_empty()
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn add_line(
         text: &mut Vec<Text>,
         width: u16,
         line: &DiffLine,
+        highlighted: Option<&HighlightedLine>,
         selected: bool,
         selected_hunk: bool,
         end_of_hunk: bool,
@@ -291,9 +582,51 @@ impl DiffComponent {
         let trimmed =
             line.content.trim_matches(|c| c == '\n' || c == '\r');
 
+        // changed words and highlighted tokens are styled separately
+        let mut segments = syntax::segments(
+            trimmed.len(),
+            &line.changes,
+            highlighted,
+        );
+        let (last, emphasis, color) =
+            segments.pop().unwrap_or((0..0, false, None));
+
+        let mut width = width as usize;
+        for (range, emphasis, color) in segments {
+            let part = &trimmed[range];
+            width = width.saturating_sub(part.chars().count());
+            text.push(Text::Styled(
+                Cow::from(part.replace('\t', "  ")),
+                theme.diff_line_part(
+                    line.line_type,
+                    selected,
+                    emphasis,
+                    color,
+                ),
+            ));
+        }
+
+        let mut trimmed = &trimmed[last];
+        let mut style = theme.diff_line_part(
+            line.line_type,
+            selected,
+            emphasis,
+            color,
+        );
+        if emphasis {
+            // do not emphasize the padding
+            width = width.saturating_sub(trimmed.chars().count());
+            text.push(Text::Styled(
+                Cow::from(trimmed.replace('\t', "  ")),
+                style,
+            ));
+            trimmed = "";
+            style = theme.diff_line(line.line_type, selected);
+        }
+
         let filled = if selected {
             // selected line
-            format!("{:w$}\n", trimmed, w = width as usize)
+            format!("{:w$}\n", trimmed, w = width)
         } else {
             // weird eof missing eol line
             format!("{}\n", trimmed)
@@ -301,10 +634,7 @@ impl DiffComponent {
         //TODO: allow customize tabsize
         let content = Cow::from(filled.replace("\t", "  "));
 
-        text.push(Text::Styled(
-            content,
-            theme.diff_line(line.line_type, selected),
-        ));
+        text.push(Text::Styled(content, style));
     }
 
     fn hunk_visible(
@@ -336,6 +666,7 @@ impl DiffComponent {
                     CWD,
                     self.current.path.clone(),
                     hash,
+                    Some(self.diff_options()),
                 )?;
                 self.queue_update();
             }
@@ -352,7 +683,12 @@ impl DiffComponent {
                     sync::stage_add_file(CWD, Path::new(&path))?;
                 } else {
                     let hash = diff.hunks[hunk].header_hash;
-                    sync::stage_hunk(CWD, path, hash)?;
+                    sync::stage_hunk(
+                        CWD,
+                        path,
+                        hash,
+                        Some(self.diff_options()),
+                    )?;
                 }
 
                 self.queue_update();
@@ -362,10 +698,46 @@ impl DiffComponent {
         Ok(())
     }
 
+    fn stage_lines(&mut self) -> Result<()> {
+        let lines = self.selected_changes();
+        if !lines.is_empty() {
+            let path = &self.current.path;
+            if self.current.is_stage {
+                sync::unstage_lines(
+                    CWD,
+                    path,
+                    &lines,
+                    Some(self.diff_options()),
+                )?;
+            } else {
+                sync::stage_lines(
+                    CWD,
+                    path,
+                    &lines,
+                    Some(self.diff_options()),
+                )?;
+            }
+
+            self.queue_update();
+        }
+
+        Ok(())
+    }
+
+    fn reset_lines(&self) {
+        let lines = self.selected_changes();
+        if !lines.is_empty() {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ConfirmAction(Action::ResetLines(
+                    self.current.path.clone(),
+                    lines,
+                )),
+            );
+        }
+    }
+
     fn queue_update(&mut self) {
         self.queue
-            .as_ref()
-            .expect("try using queue in immutable diff")
             .borrow_mut()
             .push_back(InternalEvent::Update(NeedsUpdate::ALL));
     }
@@ -375,38 +747,34 @@ impl DiffComponent {
             if let Some(hunk) = self.selected_hunk {
                 let hash = diff.hunks[hunk].header_hash;
 
-                self.queue
-                    .as_ref()
-                    .expect("try using queue in immutable diff")
-                    .borrow_mut()
-                    .push_back(InternalEvent::ConfirmAction(
-                        Action::ResetHunk(
-                            self.current.path.clone(),
-                            hash,
-                        ),
-                    ));
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ConfirmAction(Action::ResetHunk(
+                        self.current.path.clone(),
+                        hash,
+                    )),
+                );
             }
         }
         Ok(())
     }
 
     fn reset_untracked(&self) -> Result<()> {
-        self.queue
-            .as_ref()
-            .expect("try using queue in immutable diff")
-            .borrow_mut()
-            .push_back(InternalEvent::ConfirmAction(Action::Reset(
-                ResetItem {
-                    path: self.current.path.clone(),
-                    is_folder: false,
-                },
-            )));
+        self.queue.borrow_mut().push_back(
+            InternalEvent::ConfirmAction(Action::Reset(ResetItem {
+                path: self.current.path.clone(),
+                is_folder: false,
+            })),
+        );
 
         Ok(())
     }
 
-    fn is_immutable(&self) -> bool {
-        self.queue.is_none()
+    const fn is_immutable(&self) -> bool {
+        self.is_immutable
+    }
+
+    fn diff_options(&self) -> sync::DiffOptions {
+        self.options.borrow().diff
     }
 
     const fn is_stage(&self) -> bool {
@@ -425,29 +793,80 @@ impl DrawableComponent for DiffComponent {
             r.height.saturating_sub(2),
         ));
 
+        let split = self.split && self.can_split();
+
         self.scroll_top.set(calc_scroll_top(
             self.scroll_top.get(),
             self.current_size.get().1 as usize,
-            self.selection,
+            if split {
+                self.row_of(self.selection)
+            } else {
+                self.selection
+            },
         ));
 
-        let title =
-            format!("{}{}", strings::TITLE_DIFF, self.current.path);
-        f.render_widget(
-            Paragraph::new(
-                self.get_text(r.width, self.current_size.get().1)?
-                    .iter(),
-            )
-            .block(
-                Block::default()
-                    .title(title.as_str())
-                    .borders(Borders::ALL)
-                    .border_style(self.theme.block(self.focused))
-                    .title_style(self.theme.title(self.focused)),
-            )
-            .alignment(Alignment::Left),
-            r,
+        let title = format!(
+            "{}{}{}",
+            strings::TITLE_DIFF,
+            self.current.path,
+            if self.is_truncated() {
+                strings::DIFF_TRUNCATED
+            } else {
+                ""
+            }
         );
+        let block = Block::default()
+            .title(title.as_str())
+            .borders(Borders::ALL)
+            .border_style(self.theme.block(self.focused))
+            .title_style(self.theme.title(self.focused));
+
+        if split {
+            let height = self.current_size.get().1;
+            let selected = self.selected_rows();
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(
+                    [
+                        Constraint::Percentage(50),
+                        Constraint::Percentage(50),
+                    ]
+                    .as_ref(),
+                )
+                .split(block.inner(r));
+
+            f.render_widget(block, r);
+            for (column, old) in
+                columns.into_iter().zip(&[true, false])
+            {
+                f.render_widget(
+                    Paragraph::new(
+                        self.get_split_text(
+                            *old,
+                            column.width,
+                            height,
+                            selected,
+                        )
+                        .iter(),
+                    )
+                    .alignment(Alignment::Left),
+                    column,
+                );
+            }
+        } else {
+            f.render_widget(
+                Paragraph::new(
+                    self.get_text(
+                        r.width,
+                        self.current_size.get().1,
+                    )?
+                    .iter(),
+                )
+                .block(block)
+                .alignment(Alignment::Left),
+                r,
+            );
+        }
 
         Ok(())
     }
@@ -474,20 +893,60 @@ impl Component for DiffComponent {
             .hidden(),
         );
 
+        out.push(CommandInfo::new(
+            commands::DIFF_TOGGLE_SPLIT,
+            self.can_split(),
+            self.focused,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::DIFF_OPTIONS,
+            true,
+            self.focused,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::DIFF_LOAD_FULL,
+            true,
+            self.focused && self.is_truncated(),
+        ));
+
         if !self.is_immutable() {
             out.push(CommandInfo::new(
                 commands::DIFF_HUNK_REMOVE,
-                self.selected_hunk.is_some(),
+                self.can_apply_hunk(),
                 self.focused && self.is_stage(),
             ));
             out.push(CommandInfo::new(
                 commands::DIFF_HUNK_ADD,
-                self.selected_hunk.is_some(),
+                self.can_apply_hunk(),
                 self.focused && !self.is_stage(),
             ));
             out.push(CommandInfo::new(
                 commands::DIFF_HUNK_REVERT,
-                self.selected_hunk.is_some(),
+                self.can_apply_hunk(),
+                self.focused && !self.is_stage(),
+            ));
+
+            let lines_selected = !self.selected_changes().is_empty();
+            out.push(CommandInfo::new(
+                commands::DIFF_SELECT_LINES,
+                self.can_scroll(),
+                self.focused,
+            ));
+            out.push(CommandInfo::new(
+                commands::DIFF_LINES_REMOVE,
+                lines_selected,
+                self.focused && self.is_stage(),
+            ));
+            out.push(CommandInfo::new(
+                commands::DIFF_LINES_ADD,
+                lines_selected,
+                self.focused && !self.is_stage(),
+            ));
+            out.push(CommandInfo::new(
+                commands::DIFF_LINES_REVERT,
+                lines_selected,
                 self.focused && !self.is_stage(),
             ));
         }
@@ -500,30 +959,61 @@ impl Component for DiffComponent {
             if let Event::Key(e) = ev {
                 return match e {
                     keys::MOVE_DOWN => {
-                        self.move_selection(ScrollType::Down)?;
+                        self.move_selection(ScrollType::Down, false)?;
+                        Ok(true)
+                    }
+                    keys::DIFF_SELECT_DOWN => {
+                        self.move_selection(ScrollType::Down, true)?;
                         Ok(true)
                     }
-                    keys::SHIFT_DOWN | keys::END => {
-                        self.move_selection(ScrollType::End)?;
+                    keys::END => {
+                        self.move_selection(ScrollType::End, false)?;
                         Ok(true)
                     }
-                    keys::HOME | keys::SHIFT_UP => {
-                        self.move_selection(ScrollType::Home)?;
+                    keys::HOME => {
+                        self.move_selection(ScrollType::Home, false)?;
                         Ok(true)
                     }
                     keys::MOVE_UP => {
-                        self.move_selection(ScrollType::Up)?;
+                        self.move_selection(ScrollType::Up, false)?;
+                        Ok(true)
+                    }
+                    keys::DIFF_SELECT_UP => {
+                        self.move_selection(ScrollType::Up, true)?;
+                        Ok(true)
+                    }
+                    keys::DIFF_TOGGLE_SPLIT => {
+                        self.toggle_split();
+                        Ok(true)
+                    }
+                    keys::DIFF_OPTIONS => {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::OpenDiffOptions,
+                        );
+                        Ok(true)
+                    }
+                    keys::DIFF_LOAD_FULL if self.is_truncated() => {
+                        self.load_full();
                         Ok(true)
                     }
                     keys::PAGE_UP => {
-                        self.move_selection(ScrollType::PageUp)?;
+                        self.move_selection(
+                            ScrollType::PageUp,
+                            false,
+                        )?;
                         Ok(true)
                     }
                     keys::PAGE_DOWN => {
-                        self.move_selection(ScrollType::PageDown)?;
+                        self.move_selection(
+                            ScrollType::PageDown,
+                            false,
+                        )?;
                         Ok(true)
                     }
-                    keys::ENTER if !self.is_immutable() => {
+                    keys::ENTER
+                        if !self.is_immutable()
+                            && !self.is_truncated() =>
+                    {
                         if self.current.is_stage {
                             self.unstage_hunk()?;
                         } else {
@@ -531,9 +1021,23 @@ impl Component for DiffComponent {
                         }
                         Ok(true)
                     }
-                    keys::DIFF_RESET_HUNK
+                    keys::DIFF_STAGE_LINES
+                        if !self.is_immutable() =>
+                    {
+                        self.stage_lines()?;
+                        Ok(true)
+                    }
+                    keys::DIFF_RESET_LINES
                         if !self.is_immutable()
                             && !self.is_stage() =>
+                    {
+                        self.reset_lines();
+                        Ok(true)
+                    }
+                    keys::DIFF_RESET_HUNK
+                        if !self.is_immutable()
+                            && !self.is_stage()
+                            && !self.is_truncated() =>
                     {
                         if let Some(diff) = &self.diff {
                             if diff.untracked {
@@ -573,7 +1077,10 @@ mod tests {
             &DiffLine {
                 content: String::from("line 1\r\n"),
                 line_type: DiffLineType::None,
+                position: DiffLinePosition::default(),
+                changes: Vec::new(),
             },
+            None,
             false,
             false,
             false,
@@ -588,4 +1095,34 @@ mod tests {
             panic!("err")
         }
     }
+
+    #[test]
+    fn test_emphasized_changes() {
+        let mut text = Vec::new();
+        DiffComponent::add_line(
+            &mut text,
+            10,
+            &DiffLine {
+                content: String::from("a = 1;\n"),
+                line_type: DiffLineType::Add,
+                position: DiffLinePosition::default(),
+                changes: std::iter::once(4..5).collect(),
+            },
+            None,
+            false,
+            false,
+            false,
+            &SharedTheme::default(),
+        );
+
+        let contents: Vec<_> = text
+            .iter()
+            .skip(1)
+            .map(|t| match t {
+                Text::Styled(c, _) | Text::Raw(c) => c.to_string(),
+            })
+            .collect();
+
+        assert_eq!(contents, vec!["a = ", "1", ";\n"]);
+    }
 }
@@ -0,0 +1,356 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent,
+};
+use crate::{
+    keys,
+    options::SharedOptions,
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings,
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{DiffAlgorithm, DiffOptions, IgnoreWhitespace};
+use bytesize::ByteSize;
+use crossterm::event::Event;
+use std::borrow::Cow;
+use strings::commands;
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    widgets::{Block, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// most context or interhunk lines to choose
+const MAX_LINES: u32 = 20;
+
+/// diff size limits to choose from, 0 for none
+const LINE_LIMITS: [usize; 6] =
+    [0, 1000, 5000, 10_000, 50_000, 100_000];
+const BYTE_LIMITS: [usize; 5] = [
+    0,
+    256 * 1024,
+    1024 * 1024,
+    4 * 1024 * 1024,
+    16 * 1024 * 1024,
+];
+
+#[derive(Copy, Clone, PartialEq)]
+enum Entry {
+    Whitespace,
+    Context,
+    InterhunkLines,
+    Algorithm,
+    Renames,
+    Copies,
+    MaxLines,
+    MaxBytes,
+}
+
+const ENTRIES: [Entry; 8] = [
+    Entry::Whitespace,
+    Entry::Context,
+    Entry::InterhunkLines,
+    Entry::Algorithm,
+    Entry::Renames,
+    Entry::Copies,
+    Entry::MaxLines,
+    Entry::MaxBytes,
+];
+
+/// changes the options diffs are generated with
+pub struct DiffOptionsComponent {
+    options: SharedOptions,
+    selection: usize,
+    queue: Queue,
+    theme: SharedTheme,
+    visible: bool,
+}
+
+impl DrawableComponent for DiffOptionsComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.is_visible() {
+            #[allow(clippy::cast_possible_truncation)]
+            let area = ui::centered_rect_absolute(
+                40,
+                ENTRIES.len() as u16 + 2,
+                f.size(),
+            );
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(self.get_text().iter())
+                    .block(
+                        Block::default()
+                            .title(strings::DIFF_OPTIONS_TITLE)
+                            .borders(Borders::ALL)
+                            .border_style(self.theme.block(true))
+                            .title_style(self.theme.title(true)),
+                    )
+                    .alignment(Alignment::Left),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for DiffOptionsComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            out.push(
+                CommandInfo::new(commands::CLOSE_POPUP, true, true)
+                    .order(1),
+            );
+            out.push(CommandInfo::new(
+                commands::DIFF_OPTIONS_SELECT,
+                true,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::DIFF_OPTIONS_CHANGE,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if let Event::Key(e) = ev {
+                match e {
+                    keys::EXIT_POPUP => self.hide(),
+                    keys::MOVE_UP => {
+                        self.selection =
+                            self.selection.saturating_sub(1);
+                    }
+                    keys::MOVE_DOWN => {
+                        self.selection = (self.selection + 1)
+                            .min(ENTRIES.len() - 1);
+                    }
+                    keys::MOVE_LEFT => self.change(false),
+                    keys::MOVE_RIGHT => self.change(true),
+                    _ => (),
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl DiffOptionsComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        options: SharedOptions,
+        theme: SharedTheme,
+    ) -> Self {
+        Self {
+            options,
+            selection: 0,
+            queue,
+            theme,
+            visible: false,
+        }
+    }
+
+    fn get_text(&self) -> Vec<Text> {
+        let options = self.options.borrow().diff;
+
+        ENTRIES
+            .iter()
+            .enumerate()
+            .map(|(idx, entry)| {
+                let (name, value) = match entry {
+                    Entry::Whitespace => (
+                        "whitespace",
+                        match options.ignore_whitespace {
+                            IgnoreWhitespace::None => "show all",
+                            IgnoreWhitespace::All => "ignore all",
+                            IgnoreWhitespace::Change => {
+                                "ignore amount"
+                            }
+                            IgnoreWhitespace::Eol => "ignore at eol",
+                        }
+                        .to_string(),
+                    ),
+                    Entry::Context => {
+                        ("context lines", options.context.to_string())
+                    }
+                    Entry::InterhunkLines => (
+                        "interhunk lines",
+                        options.interhunk_lines.to_string(),
+                    ),
+                    Entry::Algorithm => (
+                        "algorithm",
+                        match options.algorithm {
+                            DiffAlgorithm::Myers => "myers",
+                            DiffAlgorithm::Minimal => "minimal",
+                            DiffAlgorithm::Patience => "patience",
+                        }
+                        .to_string(),
+                    ),
+                    Entry::Renames => (
+                        "detect renames",
+                        yes_no(options.find_renames),
+                    ),
+                    Entry::Copies => {
+                        ("detect copies", yes_no(options.find_copies))
+                    }
+                    Entry::MaxLines => (
+                        "max lines",
+                        limit(options.max_lines, |lines| {
+                            lines.to_string()
+                        }),
+                    ),
+                    Entry::MaxBytes => (
+                        "max size",
+                        limit(options.max_bytes, |bytes| {
+                            ByteSize::b(bytes as u64).to_string()
+                        }),
+                    ),
+                };
+
+                Text::Styled(
+                    Cow::from(format!("{:16} < {} >\n", name, value)),
+                    self.theme.text(true, idx == self.selection),
+                )
+            })
+            .collect()
+    }
+
+    /// sets the selected option to its next (or previous) value
+    fn change(&mut self, forward: bool) {
+        let mut options = self.options.borrow().diff;
+        let step = |value: u32| {
+            if forward {
+                (value + 1).min(MAX_LINES)
+            } else {
+                value.saturating_sub(1)
+            }
+        };
+
+        match ENTRIES[self.selection] {
+            Entry::Whitespace => {
+                options.ignore_whitespace = cycle(
+                    &[
+                        IgnoreWhitespace::None,
+                        IgnoreWhitespace::All,
+                        IgnoreWhitespace::Change,
+                        IgnoreWhitespace::Eol,
+                    ],
+                    options.ignore_whitespace,
+                    forward,
+                );
+            }
+            Entry::Context => options.context = step(options.context),
+            Entry::InterhunkLines => {
+                options.interhunk_lines =
+                    step(options.interhunk_lines);
+            }
+            Entry::Algorithm => {
+                options.algorithm = cycle(
+                    &[
+                        DiffAlgorithm::Myers,
+                        DiffAlgorithm::Minimal,
+                        DiffAlgorithm::Patience,
+                    ],
+                    options.algorithm,
+                    forward,
+                );
+            }
+            Entry::Renames => {
+                options.find_renames = !options.find_renames;
+            }
+            Entry::Copies => {
+                options.find_copies = !options.find_copies
+            }
+            Entry::MaxLines => {
+                options.max_lines =
+                    cycle(&LINE_LIMITS, options.max_lines, forward);
+            }
+            Entry::MaxBytes => {
+                options.max_bytes =
+                    cycle(&BYTE_LIMITS, options.max_bytes, forward);
+            }
+        }
+
+        self.set(options);
+    }
+
+    fn set(&mut self, diff: DiffOptions) {
+        if self.options.borrow().diff == diff {
+            return;
+        }
+
+        self.options.borrow_mut().diff = diff;
+
+        let mut queue = self.queue.borrow_mut();
+        if let Err(e) = self.options.borrow().save() {
+            queue.push_back(InternalEvent::ShowErrorMsg(format!(
+                "failed to save options:\n{}",
+                e
+            )));
+        }
+        queue.push_back(InternalEvent::Update(
+            NeedsUpdate::ALL | NeedsUpdate::DIFF,
+        ));
+    }
+}
+
+fn yes_no(value: bool) -> String {
+    String::from(if value { "yes" } else { "no" })
+}
+
+fn limit(value: usize, format: impl Fn(usize) -> String) -> String {
+    if value == 0 {
+        String::from("none")
+    } else {
+        format(value)
+    }
+}
+
+/// the value after (or before) `current` in `values`, wrapping around
+fn cycle<T: Copy + PartialEq>(
+    values: &[T],
+    current: T,
+    forward: bool,
+) -> T {
+    let idx = values.iter().position(|v| *v == current).unwrap_or(0);
+    let idx = if forward {
+        (idx + 1) % values.len()
+    } else {
+        (idx + values.len() - 1) % values.len()
+    };
+    values[idx]
+}
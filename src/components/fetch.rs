@@ -0,0 +1,281 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent, SharedCredentials,
+};
+use crate::{
+    queue::{InternalEvent, NeedsUpdate, Queue, RemoteOperation},
+    strings, ui,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, FetchProgress, PullOutcome},
+    AsyncFetch, AsyncNotification, FetchRequest, CWD,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    widgets::{Block, BorderType, Borders, Clear, Gauge},
+    Frame,
+};
+
+///
+pub struct FetchComponent {
+    visible: bool,
+    remote: String,
+    url: String,
+    pull: bool,
+    progress: Option<FetchProgress>,
+    git_fetch: AsyncFetch,
+    credentials: SharedCredentials,
+    queue: Queue,
+    theme: SharedTheme,
+}
+
+impl FetchComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        credentials: SharedCredentials,
+        theme: SharedTheme,
+    ) -> Self {
+        Self {
+            visible: false,
+            remote: String::new(),
+            url: String::new(),
+            pull: false,
+            progress: None,
+            git_fetch: AsyncFetch::new(sender),
+            credentials,
+            queue: queue.clone(),
+            theme,
+        }
+    }
+
+    /// fetch `remote` or the default one (see
+    /// `sync::get_default_remote`) if `None`,
+    /// if `pull` is set integrate the upstream of `HEAD` afterwards
+    pub fn fetch(
+        &mut self,
+        remote: Option<String>,
+        pull: bool,
+    ) -> Result<()> {
+        if self.git_fetch.is_pending()? {
+            return Ok(());
+        }
+
+        if pull && sync::get_head_upstream(CWD)?.is_none() {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(String::from(
+                    strings::PULL_NO_UPSTREAM_MSG,
+                )),
+            );
+            return Ok(());
+        }
+
+        self.pull = pull;
+
+        let remote =
+            remote.map_or_else(|| sync::get_default_remote(CWD), Ok);
+
+        match remote {
+            Ok(remote) => {
+                self.url = sync::get_remote_url(CWD, remote.as_str())
+                    .unwrap_or_default();
+                self.remote = remote;
+                self.progress = None;
+                self.git_fetch.request(FetchRequest {
+                    remote: self.remote.clone(),
+                    basic_credential: self
+                        .credentials
+                        .borrow()
+                        .get(&self.url)
+                        .cloned(),
+                })?;
+                self.show()?;
+            }
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "fetch error:\n{}",
+                        e
+                    )),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_fetch.is_pending().unwrap_or(false)
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.is_visible() {
+            if let AsyncNotification::Fetch = ev {
+                self.update()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update(&mut self) -> Result<()> {
+        self.progress = self.git_fetch.progress()?;
+
+        if !self.git_fetch.is_pending()? {
+            self.hide();
+
+            if self.git_fetch.last_auth_failed()? {
+                self.credentials.borrow_mut().remove(&self.url);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::AskCredentials(
+                        self.url.clone(),
+                        RemoteOperation::Fetch(
+                            Some(self.remote.clone()),
+                            self.pull,
+                        ),
+                    ),
+                );
+            } else if let Some(err) = self.git_fetch.last_result()? {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "fetch error:\n{}",
+                        err
+                    )),
+                );
+            } else if self.pull {
+                self.integrate_upstream();
+            }
+
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+        }
+
+        Ok(())
+    }
+
+    fn integrate_upstream(&self) {
+        let res = sync::get_pull_strategy(CWD)
+            .and_then(|strategy| sync::pull_upstream(CWD, strategy));
+
+        let ev = match res {
+            Ok(PullOutcome::UpToDate) => {
+                Some(InternalEvent::ShowInfoMsg(String::from(
+                    strings::MERGE_UP_TO_DATE_MSG,
+                )))
+            }
+            Ok(PullOutcome::Conflicts) => {
+                Some(InternalEvent::ShowErrorMsg(String::from(
+                    strings::PULL_CONFLICTS_MSG,
+                )))
+            }
+            Ok(_) => None,
+            Err(e) => Some(InternalEvent::ShowErrorMsg(format!(
+                "pull error:\n{}",
+                e
+            ))),
+        };
+
+        if let Some(ev) = ev {
+            self.queue.borrow_mut().push_back(ev);
+        }
+    }
+
+    fn get_progress(&self) -> (String, u8) {
+        self.progress.as_ref().map_or_else(
+            || (String::from(strings::FETCH_POPUP_CONNECTING), 0),
+            |p| {
+                (
+                    format!(
+                        "{}/{} objects, {} KiB",
+                        p.received_objects,
+                        p.total_objects,
+                        p.received_bytes / 1024
+                    ),
+                    p.progress_percent(),
+                )
+            },
+        )
+    }
+}
+
+impl DrawableComponent for FetchComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let (label, percent) = self.get_progress();
+            let title = format!(
+                "{} {}",
+                if self.pull {
+                    strings::PULL_POPUP_TITLE
+                } else {
+                    strings::FETCH_POPUP_TITLE
+                },
+                self.remote
+            );
+
+            let area = ui::centered_rect_absolute(40, 3, f.size());
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Gauge::default()
+                    .label(label.as_str())
+                    .block(
+                        Block::default()
+                            .title(title.as_str())
+                            .borders(Borders::ALL)
+                            .border_type(BorderType::Thick)
+                            .title_style(self.theme.title(true))
+                            .border_style(self.theme.block(true)),
+                    )
+                    .style(self.theme.text(true, false))
+                    .percent(u16::from(percent)),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for FetchComponent {
+    fn commands(
+        &self,
+        _out: &mut Vec<CommandInfo>,
+        _force_all: bool,
+    ) -> CommandBlocking {
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, _ev: Event) -> Result<bool> {
+        // block all input while fetching
+        Ok(self.visible)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
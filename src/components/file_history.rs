@@ -0,0 +1,308 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, CommitList,
+    Component, DiffComponent, DrawableComponent,
+};
+use crate::{
+    keys, options::SharedOptions, queue::Queue, strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, FileHistoryEntry},
+    AsyncDiff, AsyncFileHistory, AsyncNotification, DiffParams,
+    DiffType, CWD,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use strings::commands;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::Clear,
+    Frame,
+};
+
+const SLICE_SIZE: usize = 1200;
+
+/// commits changing a single file, with its diff in the selected one
+pub struct FileHistoryComponent {
+    path: Option<String>,
+    entries: Vec<FileHistoryEntry>,
+    list: CommitList,
+    diff: DiffComponent,
+    git_history: AsyncFileHistory,
+    git_diff: AsyncDiff,
+    visible: bool,
+}
+
+impl DrawableComponent for FileHistoryComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.is_visible() {
+            let percentages = if self.diff.focused() {
+                (30, 70)
+            } else {
+                (50, 50)
+            };
+
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(
+                    [
+                        Constraint::Percentage(percentages.0),
+                        Constraint::Percentage(percentages.1),
+                    ]
+                    .as_ref(),
+                )
+                .split(rect);
+
+            f.render_widget(Clear, rect);
+
+            self.list.draw(f, chunks[0])?;
+            self.diff.draw(f, chunks[1])?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for FileHistoryComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            if self.diff.focused() || force_all {
+                self.diff.commands(out, force_all);
+            }
+            if !self.diff.focused() || force_all {
+                self.list.commands(out, force_all);
+            }
+
+            out.push(
+                CommandInfo::new(commands::CLOSE_POPUP, true, true)
+                    .order(1),
+            );
+
+            out.push(CommandInfo::new(
+                commands::DIFF_FOCUS_RIGHT,
+                self.selected_entry().is_some(),
+                !self.diff.focused() || force_all,
+            ));
+
+            out.push(CommandInfo::new(
+                commands::DIFF_FOCUS_LEFT,
+                true,
+                self.diff.focused() || force_all,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if self.diff.focused() {
+                if self.diff.event(ev)? {
+                    return Ok(true);
+                }
+            } else if self.list.event(ev)? {
+                self.update()?;
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                match e {
+                    keys::EXIT_POPUP => {
+                        self.hide();
+                    }
+                    keys::FOCUS_RIGHT
+                        if self.selected_entry().is_some() =>
+                    {
+                        self.diff.focus(true);
+                    }
+                    keys::FOCUS_LEFT if self.diff.focused() => {
+                        self.diff.focus(false);
+                    }
+                    _ => (),
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        self.diff.focus(false);
+        self.update()?;
+
+        Ok(())
+    }
+}
+
+impl FileHistoryComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        options: SharedOptions,
+        theme: SharedTheme,
+    ) -> Self {
+        Self {
+            path: None,
+            entries: Vec::new(),
+            list: CommitList::new(
+                strings::FILE_HISTORY_TITLE,
+                theme.clone(),
+            ),
+            diff: DiffComponent::new(
+                queue.clone(),
+                options,
+                theme,
+                true,
+            ),
+            git_history: AsyncFileHistory::new(sender),
+            git_diff: AsyncDiff::new(sender.clone()),
+            visible: false,
+        }
+    }
+
+    /// show the history of `path`
+    pub fn open(&mut self, path: String) -> Result<()> {
+        self.git_history.fetch(&path, true)?;
+
+        self.entries.clear();
+        self.list.clear();
+        self.list.set_selection(0);
+        self.list.set_count_total(0);
+        self.list.set_title(format!(
+            "{} {}",
+            strings::FILE_HISTORY_TITLE,
+            path
+        ));
+        self.path = Some(path);
+
+        self.show()
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_history.is_pending() || self.git_diff.is_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.is_visible() {
+            match ev {
+                AsyncNotification::FileHistory => {
+                    self.update_history()?;
+                    self.update()?;
+                }
+                AsyncNotification::Diff => self.update_diff()?,
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_history(&mut self) -> Result<()> {
+        if let Some(path) = self.path.clone() {
+            match self.git_history.current()? {
+                Some((walked, entries)) if walked == path => {
+                    self.entries = entries;
+                    self.list.set_count_total(self.entries.len());
+                    self.fetch_commits();
+                }
+                // requested while another walk was running
+                _ => self.git_history.fetch(&path, false)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update(&mut self) -> Result<()> {
+        let selection = self.list.selection();
+        let selection_max = self.list.selection_max();
+        if self.list.items().needs_data(selection, selection_max) {
+            self.fetch_commits();
+        }
+
+        self.update_diff()
+    }
+
+    fn fetch_commits(&mut self) {
+        let want_min =
+            self.list.selection().saturating_sub(SLICE_SIZE / 2);
+
+        let ids = self
+            .entries
+            .iter()
+            .skip(want_min)
+            .take(SLICE_SIZE)
+            .map(|e| e.id.into())
+            .collect::<Vec<_>>();
+
+        let commits = sync::get_commits_info(
+            CWD,
+            &ids,
+            self.list.current_size().0.into(),
+        );
+
+        if let Ok(commits) = commits {
+            self.list.items().set_items(want_min, commits);
+        }
+    }
+
+    fn selected_entry(&self) -> Option<&FileHistoryEntry> {
+        self.entries.get(self.list.selection())
+    }
+
+    ///
+    pub fn update_diff(&mut self) -> Result<()> {
+        if let Some(entry) = self.selected_entry() {
+            let diff_params = DiffParams {
+                path: entry.path.clone(),
+                diff_type: DiffType::Commit(entry.id),
+                options: self.diff.diff_options_for(&entry.path),
+            };
+
+            if let Some((params, last)) = self.git_diff.last()? {
+                if params == diff_params {
+                    self.diff.update(
+                        diff_params.path,
+                        false,
+                        last,
+                    )?;
+                    return Ok(());
+                }
+            }
+
+            self.git_diff.request(diff_params)?;
+        }
+
+        self.diff.clear()?;
+
+        Ok(())
+    }
+}
@@ -13,7 +13,9 @@ use crate::{
     ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::{hash, StatusItem, StatusItemType};
+use asyncgit::{
+    hash, sync::CommitId, BlameParams, StatusItem, StatusItemType,
+};
 use crossterm::event::Event;
 use std::{borrow::Cow, convert::From, path::Path};
 use strings::{commands, order};
@@ -27,6 +29,8 @@ pub struct FileTreeComponent {
     focused: bool,
     show_selection: bool,
     queue: Option<Queue>,
+    /// commit the files are from, `None` for the workdir
+    revision: Option<CommitId>,
     theme: SharedTheme,
 }
 
@@ -45,6 +49,7 @@ impl FileTreeComponent {
             focused: focus,
             show_selection: focus,
             queue,
+            revision: None,
             theme,
         }
     }
@@ -96,6 +101,11 @@ impl FileTreeComponent {
         self.title = title;
     }
 
+    /// set the commit the files are from (`None` for the workdir)
+    pub fn set_revision(&mut self, revision: Option<CommitId>) {
+        self.revision = revision;
+    }
+
     ///
     pub fn clear(&mut self) -> Result<()> {
         self.current_hash = 0;
@@ -128,6 +138,33 @@ impl FileTreeComponent {
         changed
     }
 
+    fn open_history(&self) -> bool {
+        match (&self.queue, self.selection_file()) {
+            (Some(queue), Some(file)) => {
+                queue.borrow_mut().push_back(
+                    InternalEvent::OpenFileHistory(file.path),
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn open_blame(&self) -> bool {
+        match (&self.queue, self.selection_file()) {
+            (Some(queue), Some(file)) => {
+                queue.borrow_mut().push_back(
+                    InternalEvent::BlameFile(BlameParams {
+                        path: file.path,
+                        commit_id: self.revision,
+                    }),
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+
     fn item_to_text<'a>(
         item: &FileTreeItem,
         width: u16,
@@ -204,6 +241,7 @@ impl FileTreeComponent {
             StatusItemType::New => '+',
             StatusItemType::Deleted => '-',
             StatusItemType::Renamed => 'R',
+            StatusItemType::Conflicted => '!',
             _ => ' ',
         }
     }
@@ -276,6 +314,18 @@ impl Component for FileTreeComponent {
             .order(order::NAV),
         );
 
+        out.push(CommandInfo::new(
+            commands::FILE_HISTORY,
+            self.selection_file().is_some(),
+            (self.focused && self.queue.is_some()) || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::BLAME_FILE,
+            self.selection_file().is_some(),
+            (self.focused && self.queue.is_some()) || force_all,
+        ));
+
         CommandBlocking::PassingOn
     }
 
@@ -301,6 +351,8 @@ impl Component for FileTreeComponent {
                     keys::MOVE_RIGHT => {
                         Ok(self.move_selection(MoveSelection::Right))
                     }
+                    keys::FILE_HISTORY => Ok(self.open_history()),
+                    keys::BLAME => Ok(self.open_blame()),
                     _ => Ok(false),
                 };
             }
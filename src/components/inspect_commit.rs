@@ -4,7 +4,8 @@ use super::{
     DrawableComponent,
 };
 use crate::{
-    accessors, keys, queue::Queue, strings, ui::style::SharedTheme,
+    accessors, keys, options::SharedOptions, queue::Queue, strings,
+    ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
@@ -13,7 +14,7 @@ use asyncgit::{
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
 use strings::commands;
-use sync::{CommitId, Tags};
+use sync::{CommitId, Refs};
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
@@ -150,15 +151,22 @@ impl InspectCommitComponent {
     pub fn new(
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
+        options: SharedOptions,
         theme: SharedTheme,
     ) -> Self {
         Self {
             details: CommitDetailsComponent::new(
                 queue,
                 sender,
+                options.clone(),
                 theme.clone(),
             ),
-            diff: DiffComponent::new(None, theme),
+            diff: DiffComponent::new(
+                queue.clone(),
+                options,
+                theme,
+                true,
+            ),
             commit_id: None,
             git_diff: AsyncDiff::new(sender.clone()),
             visible: false,
@@ -203,6 +211,7 @@ impl InspectCommitComponent {
                     let diff_params = DiffParams {
                         path: f.path.clone(),
                         diff_type: DiffType::Commit(id),
+                        options: self.diff.diff_options_for(&f.path),
                     };
 
                     if let Some((params, last)) =
@@ -224,8 +233,9 @@ impl InspectCommitComponent {
         Ok(())
     }
 
-    fn update(&mut self) -> Result<()> {
-        self.details.set_commit(self.commit_id, &Tags::new())?;
+    ///
+    pub fn update(&mut self) -> Result<()> {
+        self.details.set_commit(self.commit_id, &Refs::new())?;
         self.update_diff()?;
 
         Ok(())
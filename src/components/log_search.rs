@@ -0,0 +1,133 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::sync::LogFilter;
+use crossterm::event::{Event, KeyCode};
+use strings::commands;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks for the query to search the log for
+pub struct LogSearchComponent {
+    input: TextInputComponent,
+    queue: Queue,
+}
+
+impl DrawableComponent for LogSearchComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for LogSearchComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                commands::LOG_SEARCH_CONFIRM_MSG,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if let KeyCode::Enter = e.code {
+                    self.confirm();
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl LogSearchComponent {
+    ///
+    pub fn new(queue: Queue, theme: SharedTheme) -> Self {
+        Self {
+            queue,
+            input: TextInputComponent::new(
+                theme,
+                strings::LOG_SEARCH_POPUP_TITLE,
+                strings::LOG_SEARCH_POPUP_MSG,
+            ),
+        }
+    }
+
+    /// open the popup prefilled with the `current` query
+    pub fn open(&mut self, current: String) -> Result<()> {
+        self.input.set_text(current);
+        self.show()
+    }
+
+    /// an empty query ends the search, invalid ones keep the popup
+    /// open to correct them
+    fn confirm(&mut self) {
+        let query = self.input.get_text();
+
+        let filter = if query.trim().is_empty() {
+            Ok(None)
+        } else {
+            LogFilter::parse(query).map(Some)
+        };
+
+        match filter {
+            Ok(filter) => {
+                self.hide();
+                self.queue
+                    .borrow_mut()
+                    .push_back(InternalEvent::SetLogFilter(filter));
+            }
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "invalid search:\n{}",
+                        e,
+                    )),
+                );
+            }
+        }
+    }
+}
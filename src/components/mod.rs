@@ -1,32 +1,64 @@
+mod blame_file;
+mod branch_compare;
 mod changes;
 mod command;
 mod commit;
 mod commit_details;
 mod commitlist;
+mod compare_commits;
+mod conflict_view;
+mod conflicts;
+mod create_branch;
+mod cred;
 mod diff;
+mod diff_options;
+mod fetch;
+mod file_history;
 mod filetree;
 mod help;
 mod inspect_commit;
+mod log_search;
 mod msg;
+mod push;
+mod rebase_todo;
+mod rename_branch;
 mod reset;
+mod select_remote;
 mod stashmsg;
 mod textinput;
 mod utils;
+mod walk_spec;
 use anyhow::Result;
+pub use blame_file::BlameFileComponent;
+pub use branch_compare::BranchCompareComponent;
 pub use changes::ChangesComponent;
 pub use command::{CommandInfo, CommandText};
 pub use commit::CommitComponent;
 pub use commit_details::CommitDetailsComponent;
 pub use commitlist::CommitList;
+pub use compare_commits::CompareCommitsComponent;
+pub use conflict_view::ConflictViewComponent;
+pub use conflicts::ConflictsComponent;
+pub use create_branch::CreateBranchComponent;
+pub use cred::{CredComponent, SharedCredentials};
 use crossterm::event::Event;
 pub use diff::DiffComponent;
+pub use diff_options::DiffOptionsComponent;
+pub use fetch::FetchComponent;
+pub use file_history::FileHistoryComponent;
 pub use filetree::FileTreeComponent;
 pub use help::HelpComponent;
 pub use inspect_commit::InspectCommitComponent;
+pub use log_search::LogSearchComponent;
 pub use msg::MsgComponent;
+pub use push::PushComponent;
+pub use rebase_todo::RebaseTodoComponent;
+pub use rename_branch::RenameBranchComponent;
 pub use reset::ResetComponent;
+pub use select_remote::SelectRemoteComponent;
 pub use stashmsg::StashMsgComponent;
-pub use utils::filetree::FileTreeItemKind;
+pub use utils::{branch_to_string, filetree::FileTreeItemKind};
+pub use walk_spec::WalkSpecComponent;
 
 use crate::ui::style::Theme;
 use tui::{
@@ -16,6 +16,8 @@ use ui::style::SharedTheme;
 
 pub struct MsgComponent {
     msg: String,
+    /// error or just information
+    error: bool,
     visible: bool,
     theme: SharedTheme,
 }
@@ -33,14 +35,20 @@ impl DrawableComponent for MsgComponent {
         }
         let txt = vec![Text::Raw(Cow::from(self.msg.as_str()))];
 
+        let (title, title_style) = if self.error {
+            (strings::MSG_TITLE_ERROR, self.theme.text_danger())
+        } else {
+            (strings::MSG_TITLE_INFO, self.theme.title(true))
+        };
+
         let area = ui::centered_rect_absolute(65, 25, f.size());
         f.render_widget(Clear, area);
         f.render_widget(
             Paragraph::new(txt.iter())
                 .block(
                     Block::default()
-                        .title(strings::MSG_TITLE_ERROR)
-                        .title_style(self.theme.text_danger())
+                        .title(title)
+                        .title_style(title_style)
                         .borders(Borders::ALL)
                         .border_type(BorderType::Thick),
                 )
@@ -100,6 +108,7 @@ impl MsgComponent {
     pub const fn new(theme: SharedTheme) -> Self {
         Self {
             msg: String::new(),
+            error: true,
             visible: false,
             theme,
         }
@@ -107,6 +116,16 @@ impl MsgComponent {
     ///
     pub fn show_msg(&mut self, msg: &str) -> Result<()> {
         self.msg = msg.to_string();
+        self.error = true;
+        self.show()?;
+
+        Ok(())
+    }
+
+    /// like `show_msg` for messages that are no error
+    pub fn show_info(&mut self, msg: &str) -> Result<()> {
+        self.msg = msg.to_string();
+        self.error = false;
         self.show()?;
 
         Ok(())
@@ -0,0 +1,247 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent, SharedCredentials,
+};
+use crate::{
+    queue::{InternalEvent, NeedsUpdate, Queue, RemoteOperation},
+    strings, ui,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, PushProgress, PushStage},
+    AsyncNotification, AsyncPush, PushRequest, CWD,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use std::borrow::Cow;
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+///
+pub struct PushComponent {
+    visible: bool,
+    title: String,
+    url: String,
+    branch_ref: String,
+    force: bool,
+    progress: Option<PushProgress>,
+    git_push: AsyncPush,
+    credentials: SharedCredentials,
+    queue: Queue,
+    theme: SharedTheme,
+}
+
+impl PushComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        credentials: SharedCredentials,
+        theme: SharedTheme,
+    ) -> Self {
+        Self {
+            visible: false,
+            title: String::new(),
+            url: String::new(),
+            branch_ref: String::new(),
+            force: false,
+            progress: None,
+            git_push: AsyncPush::new(sender),
+            credentials,
+            queue: queue.clone(),
+            theme,
+        }
+    }
+
+    /// push local branch `branch_ref` to the remote of its upstream
+    /// (see `sync::get_branch_remote`)
+    pub fn push(
+        &mut self,
+        branch_ref: String,
+        force: bool,
+    ) -> Result<()> {
+        if self.git_push.is_pending()? {
+            return Ok(());
+        }
+
+        match sync::get_branch_remote(CWD, branch_ref.as_str()) {
+            Ok(remote) => {
+                self.title = format!(
+                    "{} {} \u{2192} {}",
+                    if force {
+                        strings::FORCE_PUSH_POPUP_TITLE
+                    } else {
+                        strings::PUSH_POPUP_TITLE
+                    },
+                    branch_ref.trim_start_matches("refs/heads/"),
+                    remote
+                );
+                self.url = sync::get_remote_url(CWD, remote.as_str())
+                    .unwrap_or_default();
+                self.branch_ref.clone_from(&branch_ref);
+                self.force = force;
+                self.progress = None;
+                self.git_push.request(PushRequest {
+                    remote,
+                    branch: branch_ref,
+                    force,
+                    basic_credential: self
+                        .credentials
+                        .borrow()
+                        .get(&self.url)
+                        .cloned(),
+                })?;
+                self.show()?;
+            }
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "push error:\n{}",
+                        e
+                    )),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_push.is_pending().unwrap_or(false)
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.is_visible() {
+            if let AsyncNotification::Push = ev {
+                self.update()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update(&mut self) -> Result<()> {
+        self.progress = self.git_push.progress()?;
+
+        if !self.git_push.is_pending()? {
+            self.hide();
+
+            if self.git_push.last_auth_failed()? {
+                self.credentials.borrow_mut().remove(&self.url);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::AskCredentials(
+                        self.url.clone(),
+                        RemoteOperation::Push(
+                            self.branch_ref.clone(),
+                            self.force,
+                        ),
+                    ),
+                );
+            } else if let Some(err) = self.git_push.last_result()? {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "push error:\n{}",
+                        err
+                    )),
+                );
+            }
+
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+        }
+
+        Ok(())
+    }
+
+    fn get_text(&self) -> Vec<Text> {
+        let (stage, remote_msg) = match &self.progress {
+            Some(PushProgress {
+                stage: PushStage::Pushing,
+                remote_msg,
+            }) => (strings::PUSH_POPUP_PUSHING, remote_msg.clone()),
+            _ => (strings::PUSH_POPUP_CONNECTING, None),
+        };
+
+        vec![
+            Text::Styled(
+                Cow::from(stage),
+                self.theme.text(true, false),
+            ),
+            Text::Raw(Cow::from("\n")),
+            Text::Styled(
+                Cow::from(remote_msg.unwrap_or_default()),
+                self.theme.text(false, false),
+            ),
+        ]
+    }
+}
+
+impl DrawableComponent for PushComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let txt = self.get_text();
+
+            let area = ui::centered_rect_absolute(50, 4, f.size());
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(txt.iter())
+                    .block(
+                        Block::default()
+                            .title(self.title.as_str())
+                            .borders(Borders::ALL)
+                            .border_type(BorderType::Thick)
+                            .title_style(self.theme.title(true))
+                            .border_style(self.theme.block(true)),
+                    )
+                    .alignment(Alignment::Left),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for PushComponent {
+    fn commands(
+        &self,
+        _out: &mut Vec<CommandInfo>,
+        _force_all: bool,
+    ) -> CommandBlocking {
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, _ev: Event) -> Result<bool> {
+        // block all input while pushing
+        Ok(self.visible)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,373 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    keys,
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings,
+    ui::{self, calc_scroll_top, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{
+        self, CommitId, CommitMessage, RebaseAction, RebaseOutcome,
+        RebaseTodoItem,
+    },
+    CWD,
+};
+use crossterm::event::Event;
+use std::{borrow::Cow, cell::Cell};
+use strings::commands;
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    widgets::{Block, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// editable todo list of an interactive rebase
+pub struct RebaseTodoComponent {
+    base: Option<CommitId>,
+    items: Vec<RebaseTodoItem>,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    reword: TextInputComponent,
+    queue: Queue,
+    theme: SharedTheme,
+    visible: bool,
+}
+
+impl DrawableComponent for RebaseTodoComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.is_visible() {
+            let area = ui::centered_rect(70, 60, f.size());
+            let height = area.height.saturating_sub(2) as usize;
+            self.scroll_top.set(calc_scroll_top(
+                self.scroll_top.get(),
+                height,
+                self.selection,
+            ));
+
+            let title = format!(
+                "{} {}",
+                strings::REBASE_TODO_TITLE,
+                self.base
+                    .map(|id| id.to_string()[..7].to_string())
+                    .unwrap_or_default()
+            );
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(self.get_text(height).iter())
+                    .block(
+                        Block::default()
+                            .title(title.as_str())
+                            .borders(Borders::ALL)
+                            .border_style(self.theme.block(true))
+                            .title_style(self.theme.title(true)),
+                    )
+                    .alignment(Alignment::Left),
+                area,
+            );
+
+            self.reword.draw(f, area)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for RebaseTodoComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            if self.reword.is_visible() {
+                self.reword.commands(out, force_all);
+
+                out.push(CommandInfo::new(
+                    commands::REBASE_REWORD_CONFIRM,
+                    true,
+                    true,
+                ));
+            } else {
+                out.push(
+                    CommandInfo::new(
+                        commands::CLOSE_POPUP,
+                        true,
+                        true,
+                    )
+                    .order(1),
+                );
+
+                out.push(CommandInfo::new(
+                    commands::REBASE_TODO_ACTION,
+                    true,
+                    true,
+                ));
+
+                out.push(CommandInfo::new(
+                    commands::REBASE_TODO_MOVE,
+                    self.items.len() > 1,
+                    true,
+                ));
+
+                out.push(CommandInfo::new(
+                    commands::REBASE_TODO_CONFIRM,
+                    true,
+                    true,
+                ));
+            }
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if self.reword.is_visible() {
+                if self.reword.event(ev)? {
+                    return Ok(true);
+                }
+
+                if let Event::Key(keys::ENTER) = ev {
+                    self.confirm_reword();
+                }
+
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                match e {
+                    keys::EXIT_POPUP => self.hide(),
+                    keys::MOVE_UP => {
+                        self.selection =
+                            self.selection.saturating_sub(1);
+                    }
+                    keys::MOVE_DOWN => {
+                        self.selection = (self.selection + 1)
+                            .min(self.items.len().saturating_sub(1));
+                    }
+                    keys::REBASE_TODO_MOVE_UP => {
+                        self.move_item(false);
+                    }
+                    keys::REBASE_TODO_MOVE_DOWN => {
+                        self.move_item(true);
+                    }
+                    keys::REBASE_TODO_PICK => {
+                        self.set_action(RebaseAction::Pick);
+                    }
+                    keys::REBASE_TODO_REWORD => self.open_reword()?,
+                    keys::REBASE_TODO_EDIT => {
+                        self.set_action(RebaseAction::Edit);
+                    }
+                    keys::REBASE_TODO_SQUASH => {
+                        self.set_action(RebaseAction::Squash);
+                    }
+                    keys::REBASE_TODO_FIXUP => {
+                        self.set_action(RebaseAction::Fixup);
+                    }
+                    keys::REBASE_TODO_DROP => {
+                        self.set_action(RebaseAction::Drop);
+                    }
+                    keys::REBASE_TODO_CONFIRM => self.execute(),
+                    _ => (),
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+        self.reword.hide();
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl RebaseTodoComponent {
+    ///
+    pub fn new(queue: Queue, theme: SharedTheme) -> Self {
+        Self {
+            base: None,
+            items: Vec::new(),
+            selection: 0,
+            scroll_top: Cell::new(0),
+            reword: TextInputComponent::new(
+                theme.clone(),
+                strings::REBASE_REWORD_POPUP_TITLE,
+                strings::REBASE_REWORD_POPUP_MSG,
+            ),
+            queue,
+            theme,
+            visible: false,
+        }
+    }
+
+    /// list the commits after `base` to rebase them
+    pub fn open(&mut self, base: CommitId) -> Result<()> {
+        let msg = match sync::get_rebase_todo(CWD, base) {
+            Ok(items) if items.is_empty() => {
+                Some(String::from(strings::REBASE_NOTHING_MSG))
+            }
+            Ok(items) => {
+                self.items = items;
+                None
+            }
+            Err(e) => Some(format!("rebase error:\n{}", e)),
+        };
+
+        if let Some(msg) = msg {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::ShowErrorMsg(msg));
+            return Ok(());
+        }
+
+        self.base = Some(base);
+        self.selection = 0;
+        self.scroll_top.set(0);
+
+        self.show()
+    }
+
+    fn get_text(&self, height: usize) -> Vec<Text> {
+        let mut txt = Vec::with_capacity(height * 3);
+
+        for (idx, item) in self
+            .items
+            .iter()
+            .enumerate()
+            .skip(self.scroll_top.get())
+            .take(height)
+        {
+            let selected = idx == self.selection;
+            let summary = item.message.as_ref().map_or_else(
+                || item.summary.clone(),
+                |msg| CommitMessage::from(msg).subject,
+            );
+
+            txt.push(Text::Styled(
+                Cow::from(format!("{:6} ", item.action.as_str())),
+                if item.action == RebaseAction::Drop {
+                    self.theme.text_danger()
+                } else {
+                    self.theme.text(true, selected)
+                },
+            ));
+            txt.push(Text::Styled(
+                Cow::from(format!("{} ", &item.id.to_string()[..7])),
+                self.theme.commit_hash(selected),
+            ));
+            txt.push(Text::Styled(
+                Cow::from(format!("{}\n", summary)),
+                self.theme.text(
+                    item.action != RebaseAction::Drop,
+                    selected,
+                ),
+            ));
+        }
+
+        txt
+    }
+
+    fn set_action(&mut self, action: RebaseAction) {
+        if let Some(item) = self.items.get_mut(self.selection) {
+            item.action = action;
+            if action != RebaseAction::Reword {
+                item.message = None;
+            }
+        }
+    }
+
+    fn move_item(&mut self, down: bool) {
+        let target = if down {
+            self.selection + 1
+        } else {
+            self.selection.wrapping_sub(1)
+        };
+
+        if target < self.items.len() {
+            self.items.swap(self.selection, target);
+            self.selection = target;
+        }
+    }
+
+    fn open_reword(&mut self) -> Result<()> {
+        if let Some(item) = self.items.get(self.selection) {
+            let msg = match &item.message {
+                Some(msg) => msg.clone(),
+                None => sync::get_commit_details(CWD, item.id)?
+                    .message
+                    .map(CommitMessage::combine)
+                    .unwrap_or_default(),
+            };
+
+            self.reword.set_text(msg);
+            self.reword.show()?;
+        }
+
+        Ok(())
+    }
+
+    fn confirm_reword(&mut self) {
+        let msg = self.reword.get_text().clone();
+        self.reword.hide();
+
+        if msg.trim().is_empty() {
+            return;
+        }
+
+        if let Some(item) = self.items.get_mut(self.selection) {
+            item.action = RebaseAction::Reword;
+            item.message = Some(msg);
+        }
+    }
+
+    fn execute(&mut self) {
+        let base = match self.base {
+            Some(base) => base,
+            None => return,
+        };
+
+        let msg =
+            match sync::rebase_interactive(CWD, base, &self.items) {
+                Ok(RebaseOutcome::Finished) => None,
+                Ok(RebaseOutcome::Edit(_)) => {
+                    Some(String::from(strings::REBASE_EDIT_MSG))
+                }
+                Ok(RebaseOutcome::Conflicts) => {
+                    Some(String::from(strings::REBASE_CONFLICTS_MSG))
+                }
+                Err(e) => Some(format!("rebase error:\n{}", e)),
+            };
+
+        self.hide();
+
+        let mut queue = self.queue.borrow_mut();
+        if let Some(msg) = msg {
+            queue.push_back(InternalEvent::ShowErrorMsg(msg));
+        }
+        queue.push_back(InternalEvent::Update(NeedsUpdate::ALL));
+    }
+}
@@ -0,0 +1,142 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{sync, CWD};
+use crossterm::event::{Event, KeyCode};
+use strings::commands;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+pub struct RenameBranchComponent {
+    input: TextInputComponent,
+    branch_ref: Option<String>,
+    queue: Queue,
+}
+
+impl DrawableComponent for RenameBranchComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for RenameBranchComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                commands::RENAME_BRANCH_CONFIRM_MSG,
+                !self.input.get_text().is_empty(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if let KeyCode::Enter = e.code {
+                    if !self.input.get_text().is_empty() {
+                        self.rename_branch();
+                    }
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl RenameBranchComponent {
+    ///
+    pub fn new(queue: Queue, theme: SharedTheme) -> Self {
+        Self {
+            queue,
+            branch_ref: None,
+            input: TextInputComponent::new(
+                theme,
+                strings::RENAME_BRANCH_POPUP_TITLE,
+                strings::RENAME_BRANCH_POPUP_MSG,
+            ),
+        }
+    }
+
+    /// open the popup prefilled with the current name of the branch
+    pub fn open(
+        &mut self,
+        branch_ref: String,
+        cur_name: String,
+    ) -> Result<()> {
+        self.branch_ref = Some(branch_ref);
+        self.input.set_text(cur_name);
+        self.show()
+    }
+
+    fn rename_branch(&mut self) {
+        if let Some(branch_ref) = self.branch_ref.take() {
+            let res = sync::rename_branch(
+                CWD,
+                branch_ref.as_str(),
+                self.input.get_text().as_str(),
+            );
+
+            match res {
+                Ok(_) => {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Update(NeedsUpdate::ALL),
+                    );
+                }
+                Err(e) => {
+                    log::error!("rename branch: {}", e);
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "rename branch error:\n{}",
+                            e,
+                        )),
+                    );
+                }
+            }
+        }
+
+        self.input.clear();
+        self.hide();
+    }
+}
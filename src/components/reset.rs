@@ -152,6 +152,26 @@ impl ResetComponent {
                     strings::CONFIRM_TITLE_RESET,
                     strings::CONFIRM_MSG_RESETHUNK,
                 ),
+                Action::ResetLines(_, _) => (
+                    strings::CONFIRM_TITLE_RESET,
+                    strings::CONFIRM_MSG_RESETLINES,
+                ),
+                Action::DeleteBranch(_) => (
+                    strings::CONFIRM_TITLE_DELETEBRANCH,
+                    strings::CONFIRM_MSG_DELETEBRANCH,
+                ),
+                Action::ForcePush(_) => (
+                    strings::CONFIRM_TITLE_FORCEPUSH,
+                    strings::CONFIRM_MSG_FORCEPUSH,
+                ),
+                Action::RebaseAbort => (
+                    strings::CONFIRM_TITLE_REBASE_ABORT,
+                    strings::CONFIRM_MSG_REBASE_ABORT,
+                ),
+                Action::MergeAbort => (
+                    strings::CONFIRM_TITLE_MERGE_ABORT,
+                    strings::CONFIRM_MSG_MERGE_ABORT,
+                ),
             };
         }
 
@@ -0,0 +1,176 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent,
+};
+use crate::{
+    keys,
+    queue::{InternalEvent, Queue, RemoteOperation},
+    strings,
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use std::borrow::Cow;
+use strings::commands;
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    widgets::{Block, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// picks the remote to fetch
+pub struct SelectRemoteComponent {
+    remotes: Vec<String>,
+    selection: usize,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+}
+
+impl DrawableComponent for SelectRemoteComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.is_visible() {
+            #[allow(clippy::cast_possible_truncation)]
+            let area = ui::centered_rect_absolute(
+                40,
+                (self.remotes.len() as u16).saturating_add(2),
+                f.size(),
+            );
+
+            let text: Vec<Text> = self
+                .remotes
+                .iter()
+                .enumerate()
+                .map(|(idx, remote)| {
+                    Text::Styled(
+                        Cow::from(format!("{}\n", remote)),
+                        self.theme.text(true, idx == self.selection),
+                    )
+                })
+                .collect();
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(text.iter())
+                    .block(
+                        Block::default()
+                            .title(strings::SELECT_REMOTE_TITLE)
+                            .borders(Borders::ALL)
+                            .border_style(self.theme.block(true))
+                            .title_style(self.theme.title(true)),
+                    )
+                    .alignment(Alignment::Left),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for SelectRemoteComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            out.push(
+                CommandInfo::new(commands::CLOSE_POPUP, true, true)
+                    .order(1),
+            );
+            out.push(CommandInfo::new(
+                commands::SELECT_REMOTE_CONFIRM,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if let Event::Key(e) = ev {
+                match e {
+                    keys::EXIT_POPUP => self.hide(),
+                    keys::MOVE_UP => {
+                        self.selection =
+                            self.selection.saturating_sub(1);
+                    }
+                    keys::MOVE_DOWN => {
+                        self.selection = (self.selection + 1).min(
+                            self.remotes.len().saturating_sub(1),
+                        );
+                    }
+                    keys::ENTER => {
+                        if let Some(remote) =
+                            self.remotes.get(self.selection)
+                        {
+                            self.queue.borrow_mut().push_back(
+                                InternalEvent::Remote(
+                                    RemoteOperation::Fetch(
+                                        Some(remote.clone()),
+                                        false,
+                                    ),
+                                ),
+                            );
+                        }
+                        self.hide();
+                    }
+                    _ => (),
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl SelectRemoteComponent {
+    ///
+    pub fn new(queue: Queue, theme: SharedTheme) -> Self {
+        Self {
+            remotes: Vec::new(),
+            selection: 0,
+            visible: false,
+            queue,
+            theme,
+        }
+    }
+
+    /// lets the user choose one of `remotes`, `default` preselected
+    pub fn open(
+        &mut self,
+        remotes: Vec<String>,
+        default: Option<&str>,
+    ) -> Result<()> {
+        self.selection = default
+            .and_then(|d| remotes.iter().position(|r| r == d))
+            .unwrap_or_default();
+        self.remotes = remotes;
+        self.show()
+    }
+}
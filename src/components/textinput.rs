@@ -7,6 +7,7 @@ use crate::{
 };
 use anyhow::Result;
 use crossterm::event::{Event, KeyCode, KeyModifiers};
+use std::borrow::Cow;
 use strings::commands;
 use tui::{
     backend::Backend,
@@ -17,6 +18,15 @@ use tui::{
 };
 use ui::style::SharedTheme;
 
+///
+#[derive(Copy, Clone, PartialEq)]
+pub enum InputType {
+    ///
+    Text,
+    /// every char is drawn as `*`
+    Password,
+}
+
 /// primarily a subcomponet for user input of text (used in `CommitComponent`)
 pub struct TextInputComponent {
     title: String,
@@ -25,6 +35,7 @@ pub struct TextInputComponent {
     visible: bool,
     theme: SharedTheme,
     cursor_position: usize,
+    input_type: InputType,
 }
 
 impl TextInputComponent {
@@ -41,6 +52,23 @@ impl TextInputComponent {
             title: title.to_string(),
             default_msg: default_msg.to_string(),
             cursor_position: 0,
+            input_type: InputType::Text,
+        }
+    }
+
+    /// Set how the `msg` is drawn.
+    pub fn set_input_type(&mut self, input_type: InputType) {
+        self.input_type = input_type;
+    }
+
+    /// Get the portion `msg[from..to]` the way it is drawn.
+    fn get_draw_text(&self, from: usize, to: usize) -> Cow<str> {
+        let text = &self.msg[from..to];
+        match self.input_type {
+            InputType::Text => Cow::from(text),
+            InputType::Password => {
+                Cow::from("*".repeat(text.chars().count()))
+            }
         }
     }
 
@@ -90,6 +118,7 @@ impl TextInputComponent {
     /// Set the `msg`.
     pub fn set_text(&mut self, msg: String) {
         self.msg = msg;
+        self.cursor_position = self.msg.len();
     }
 
     /// Set the `title`.
@@ -119,18 +148,18 @@ impl DrawableComponent for TextInputComponent {
                 // if the cursor is not at the first character
                 if self.cursor_position > 0 {
                     txt.push(Text::styled(
-                        &self.msg[..self.cursor_position],
+                        self.get_draw_text(0, self.cursor_position),
                         style,
                     ));
                 }
 
                 txt.push(Text::styled(
                     if let Some(pos) = self.next_char_position() {
-                        &self.msg[self.cursor_position..pos]
+                        self.get_draw_text(self.cursor_position, pos)
                     } else {
                         // if the cursor is at the end of the msg
                         // a whitespace is used to underline
-                        " "
+                        Cow::from(" ")
                     },
                     style.modifier(Modifier::UNDERLINED),
                 ));
@@ -140,7 +169,7 @@ impl DrawableComponent for TextInputComponent {
                 if let Some(pos) = self.next_char_position() {
                     if pos < self.msg.len() {
                         txt.push(Text::styled(
-                            &self.msg[pos..],
+                            self.get_draw_text(pos, self.msg.len()),
                             style,
                         ));
                     }
@@ -1,5 +1,5 @@
-use super::time_to_string;
-use asyncgit::sync::{CommitId, CommitInfo};
+use super::{short_hash, time_to_string};
+use asyncgit::sync::{CommitId, CommitInfo, GraphRow};
 use std::slice::Iter;
 
 static SLICE_OFFSET_RELOAD_THRESHOLD: usize = 100;
@@ -10,18 +10,18 @@ pub struct LogEntry {
     pub msg: String,
     pub hash_short: String,
     pub id: CommitId,
+    pub graph: Option<GraphRow>,
 }
 
 impl From<CommitInfo> for LogEntry {
     fn from(c: CommitInfo) -> Self {
-        let hash = c.id.to_string().chars().take(7).collect();
-
         Self {
             author: c.author,
             msg: c.message,
             time: time_to_string(c.time, true),
-            hash_short: hash,
+            hash_short: short_hash(c.id),
             id: c.id,
+            graph: None,
         }
     }
 }
@@ -64,6 +64,13 @@ impl ItemBatch {
         self.index_offset = start_index;
     }
 
+    /// attach graph rows to the current items (same order)
+    pub fn set_graph(&mut self, rows: Vec<GraphRow>) {
+        for (item, row) in self.items.iter_mut().zip(rows) {
+            item.graph = Some(row);
+        }
+    }
+
     /// returns `true` if we should fetch updated list of items
     pub fn needs_data(&self, idx: usize, idx_max: usize) -> bool {
         let want_min =
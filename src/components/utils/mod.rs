@@ -1,9 +1,16 @@
+use asyncgit::sync::{CommitId, UpstreamInfo};
 use chrono::{DateTime, Local, NaiveDateTime, Utc};
 
 pub mod filetree;
 pub mod logitems;
+pub mod split_diff;
 pub mod statustree;
 
+/// abbreviated hash of a commit as shown in lists and titles
+pub fn short_hash(id: CommitId) -> String {
+    id.to_string().chars().take(7).collect()
+}
+
 /// helper func to convert unix time since epoch to formated time string in local timezone
 pub fn time_to_string(secs: i64, short: bool) -> String {
     let time = DateTime::<Local>::from(DateTime::<Utc>::from_utc(
@@ -17,3 +24,39 @@ pub fn time_to_string(secs: i64, short: bool) -> String {
     })
     .to_string()
 }
+
+/// helper func to format the time since `secs` (unix time) in a
+/// short, coarse way (e.g. `5m`, `3d`, `2y`)
+pub fn age_to_string(secs: i64) -> String {
+    const UNITS: [(i64, &str); 6] = [
+        (60 * 60 * 24 * 365, "y"),
+        (60 * 60 * 24 * 30, "mo"),
+        (60 * 60 * 24 * 7, "w"),
+        (60 * 60 * 24, "d"),
+        (60 * 60, "h"),
+        (60, "m"),
+    ];
+
+    let age = Utc::now().timestamp().saturating_sub(secs).max(0);
+
+    UNITS.iter().find(|(unit, _)| age >= *unit).map_or_else(
+        || String::from("now"),
+        |(unit, name)| format!("{}{}", age / unit, name),
+    )
+}
+
+/// helper func to format a branch name with its upstream and the
+/// ahead/behind counts (e.g. `master → origin/master ↑1 ↓0`)
+pub fn branch_to_string(
+    branch: &str,
+    upstream: Option<&UpstreamInfo>,
+) -> String {
+    if let Some(upstream) = upstream {
+        format!(
+            "{} \u{2192} {} \u{2191}{} \u{2193}{}",
+            branch, upstream.name, upstream.ahead, upstream.behind
+        )
+    } else {
+        branch.to_string()
+    }
+}
@@ -0,0 +1,152 @@
+//! pairing of diff lines into the rows of a side-by-side view
+
+use asyncgit::{DiffLineType, FileDiff};
+
+/// one row of the side-by-side view, lines are indices into the lines
+/// of all hunks
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SplitRow {
+    /// deleted, context or header line
+    pub old: Option<usize>,
+    /// added, context or header line
+    pub new: Option<usize>,
+    ///
+    pub hunk: usize,
+    /// index of the first line of `hunk`
+    pub hunk_start: usize,
+    /// last row of `hunk`
+    pub end_of_hunk: bool,
+}
+
+impl SplitRow {
+    /// first line shown in this row
+    pub fn first_line(&self) -> usize {
+        self.old.or(self.new).unwrap_or(self.hunk_start)
+    }
+
+    ///
+    pub fn contains(&self, line: usize) -> bool {
+        self.old == Some(line) || self.new == Some(line)
+    }
+}
+
+/// context lines (and headers) go on both sides, each run of deleted
+/// lines is paired line by line with the run of added lines directly
+/// following it
+pub fn split_rows(diff: &FileDiff) -> Vec<SplitRow> {
+    let mut rows = Vec::with_capacity(diff.lines);
+    let mut hunk_start = 0;
+
+    for (hunk_idx, hunk) in diff.hunks.iter().enumerate() {
+        let row = |old, new| SplitRow {
+            old,
+            new,
+            hunk: hunk_idx,
+            hunk_start,
+            end_of_hunk: false,
+        };
+
+        let mut idx = 0;
+        while idx < hunk.lines.len() {
+            let deletes =
+                run_len(&hunk.lines[idx..], DiffLineType::Delete);
+            let adds = run_len(
+                &hunk.lines[idx + deletes..],
+                DiffLineType::Add,
+            );
+
+            if deletes + adds == 0 {
+                let line = Some(hunk_start + idx);
+                rows.push(row(line, line));
+                idx += 1;
+                continue;
+            }
+
+            for pair in 0..deletes.max(adds) {
+                let old = Some(hunk_start + idx + pair)
+                    .filter(|_| pair < deletes);
+                let new = Some(hunk_start + idx + deletes + pair)
+                    .filter(|_| pair < adds);
+                rows.push(row(old, new));
+            }
+
+            idx += deletes + adds;
+        }
+
+        if let Some(last) = rows.last_mut() {
+            last.end_of_hunk = true;
+        }
+
+        hunk_start += hunk.lines.len();
+    }
+
+    rows
+}
+
+fn run_len(
+    lines: &[asyncgit::DiffLine],
+    line_type: DiffLineType,
+) -> usize {
+    lines
+        .iter()
+        .take_while(|line| line.line_type == line_type)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asyncgit::{sync::diff::Hunk, DiffLine};
+
+    fn hunk(types: &[DiffLineType]) -> Hunk {
+        Hunk {
+            header_hash: 0,
+            lines: types
+                .iter()
+                .map(|line_type| DiffLine {
+                    line_type: *line_type,
+                    ..DiffLine::default()
+                })
+                .collect(),
+        }
+    }
+
+    fn pairs(
+        rows: &[SplitRow],
+    ) -> Vec<(Option<usize>, Option<usize>)> {
+        rows.iter().map(|r| (r.old, r.new)).collect()
+    }
+
+    #[test]
+    fn test_pairing() {
+        use DiffLineType::{Add, Delete, Header, None};
+
+        let diff = FileDiff {
+            hunks: vec![
+                hunk(&[Header, None, Delete, Delete, Add, None]),
+                hunk(&[Header, Add, Delete, Add]),
+            ],
+            lines: 10,
+            ..FileDiff::default()
+        };
+
+        let rows = split_rows(&diff);
+
+        assert_eq!(
+            pairs(&rows),
+            vec![
+                (Some(0), Some(0)),
+                (Some(1), Some(1)),
+                (Some(2), Some(4)),
+                (Some(3), Option::None),
+                (Some(5), Some(5)),
+                (Some(6), Some(6)),
+                (Option::None, Some(7)),
+                (Some(8), Some(9)),
+            ]
+        );
+        assert!(rows[4].end_of_hunk);
+        assert_eq!(rows[5].hunk, 1);
+        assert_eq!(rows[5].hunk_start, 6);
+    }
+}
@@ -0,0 +1,127 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, LogWalkSpec},
+    CWD,
+};
+use crossterm::event::{Event, KeyCode};
+use strings::commands;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks for the revisions the log shows (`--all`, `a..b`, a branch..)
+pub struct WalkSpecComponent {
+    input: TextInputComponent,
+    queue: Queue,
+}
+
+impl DrawableComponent for WalkSpecComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for WalkSpecComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                commands::WALK_SPEC_CONFIRM_MSG,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if let KeyCode::Enter = e.code {
+                    self.confirm();
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl WalkSpecComponent {
+    ///
+    pub fn new(queue: Queue, theme: SharedTheme) -> Self {
+        Self {
+            queue,
+            input: TextInputComponent::new(
+                theme,
+                strings::WALK_SPEC_POPUP_TITLE,
+                strings::WALK_SPEC_POPUP_MSG,
+            ),
+        }
+    }
+
+    /// open the popup prefilled with the `current` spec
+    pub fn open(&mut self, current: &LogWalkSpec) -> Result<()> {
+        self.input.set_text(current.to_string());
+        self.show()
+    }
+
+    /// invalid specs keep the popup open to correct them
+    fn confirm(&mut self) {
+        match sync::parse_walk_spec(CWD, self.input.get_text()) {
+            Ok(spec) => {
+                self.hide();
+                self.queue
+                    .borrow_mut()
+                    .push_back(InternalEvent::SetWalkSpec(spec));
+            }
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "invalid revision:\n{}",
+                        e,
+                    )),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! running external programs (editor, merge and diff tools) with the
+//! terminal handed over to them
+
+use crate::{setup_terminal, shutdown_terminal};
+use anyhow::{anyhow, Result};
+use asyncgit::{sync, CWD};
+use std::process::{Command, ExitStatus};
+
+/// leaves the alternate screen and raw mode while `command` runs,
+/// restoring both once it exits
+pub fn run_with_terminal<S: AsRef<str>>(
+    command: &str,
+    args: &[S],
+) -> Result<ExitStatus> {
+    shutdown_terminal()?;
+
+    let status = Command::new(command)
+        .args(args.iter().map(AsRef::as_ref))
+        .status();
+
+    setup_terminal()?;
+
+    status.map_err(|e| anyhow!("\"{}\": {}", command, e))
+}
+
+/// resolves the conflicted `path` with the `merge.tool` configured in
+/// git config (via `git mergetool`)
+pub fn run_merge_tool(path: &str) -> Result<()> {
+    if sync::get_config_string(CWD, "merge.tool")?.is_none() {
+        return Err(anyhow!("no merge.tool configured"));
+    }
+
+    run_git(&["mergetool", "--no-prompt", "--", path])
+}
+
+/// shows the changes of `path` in the `diff.tool` (or `merge.tool`)
+/// configured in git config (via `git difftool`)
+pub fn run_diff_tool(path: &str, staged: bool) -> Result<()> {
+    if sync::get_config_string(CWD, "diff.tool")?.is_none()
+        && sync::get_config_string(CWD, "merge.tool")?.is_none()
+    {
+        return Err(anyhow!("no diff.tool configured"));
+    }
+
+    let mut args = vec!["difftool", "--no-prompt"];
+    if staged {
+        args.push("--cached");
+    }
+    args.extend_from_slice(&["--", path]);
+
+    run_git(&args)
+}
+
+fn run_git(args: &[&str]) -> Result<()> {
+    let status = run_with_terminal("git", args)?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("git {} failed: {}", args[0], status))
+    }
+}
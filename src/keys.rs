@@ -18,6 +18,7 @@ pub const TAB_1: KeyEvent = no_mod(KeyCode::Char('1'));
 pub const TAB_2: KeyEvent = no_mod(KeyCode::Char('2'));
 pub const TAB_3: KeyEvent = no_mod(KeyCode::Char('3'));
 pub const TAB_4: KeyEvent = no_mod(KeyCode::Char('4'));
+pub const TAB_5: KeyEvent = no_mod(KeyCode::Char('5'));
 pub const TAB_TOGGLE: KeyEvent = no_mod(KeyCode::Tab);
 pub const TAB_TOGGLE_REVERSE: KeyEvent = no_mod(KeyCode::BackTab);
 //TODO: https://github.com/extrawurst/gitui/issues/112
@@ -53,6 +54,14 @@ pub const STATUS_STAGE_ALL: KeyEvent = no_mod(KeyCode::Char('a'));
 pub const STATUS_RESET_FILE: KeyEvent =
     with_mod(KeyCode::Char('D'), KeyModifiers::SHIFT);
 pub const DIFF_RESET_HUNK: KeyEvent = STATUS_RESET_FILE;
+pub const DIFF_SELECT_UP: KeyEvent = SHIFT_UP;
+pub const DIFF_SELECT_DOWN: KeyEvent = SHIFT_DOWN;
+pub const DIFF_STAGE_LINES: KeyEvent = no_mod(KeyCode::Char('l'));
+pub const DIFF_RESET_LINES: KeyEvent =
+    with_mod(KeyCode::Char('L'), KeyModifiers::SHIFT);
+pub const DIFF_TOGGLE_SPLIT: KeyEvent = no_mod(KeyCode::Char('v'));
+pub const DIFF_OPTIONS: KeyEvent = no_mod(KeyCode::Char('o'));
+pub const DIFF_LOAD_FULL: KeyEvent = no_mod(KeyCode::Char('e'));
 pub const STATUS_IGNORE_FILE: KeyEvent = no_mod(KeyCode::Char('i'));
 pub const STASHING_SAVE: KeyEvent = no_mod(KeyCode::Char('s'));
 pub const STASHING_TOGGLE_UNTRACKED: KeyEvent =
@@ -63,7 +72,65 @@ pub const STASH_APPLY: KeyEvent = no_mod(KeyCode::Enter);
 pub const STASH_OPEN: KeyEvent = no_mod(KeyCode::Right);
 pub const STASH_DROP: KeyEvent =
     with_mod(KeyCode::Char('D'), KeyModifiers::SHIFT);
+pub const FETCH: KeyEvent = no_mod(KeyCode::Char('f'));
+pub const PULL: KeyEvent =
+    with_mod(KeyCode::Char('F'), KeyModifiers::SHIFT);
+pub const PUSH: KeyEvent = no_mod(KeyCode::Char('p'));
+pub const FORCE_PUSH: KeyEvent =
+    with_mod(KeyCode::Char('P'), KeyModifiers::SHIFT);
 pub const CMD_BAR_TOGGLE: KeyEvent = no_mod(KeyCode::Char('.'));
 pub const LOG_COMMIT_DETAILS: KeyEvent = no_mod(KeyCode::Enter);
+pub const LOG_CREATE_BRANCH: KeyEvent = no_mod(KeyCode::Char('b'));
+pub const LOG_WALK_SPEC: KeyEvent = no_mod(KeyCode::Char('w'));
+pub const LOG_SEARCH: KeyEvent = no_mod(KeyCode::Char('/'));
+pub const LOG_SEARCH_CLEAR: KeyEvent = no_mod(KeyCode::Esc);
+pub const FILE_HISTORY: KeyEvent = no_mod(KeyCode::Char('l'));
+pub const BLAME: KeyEvent =
+    with_mod(KeyCode::Char('B'), KeyModifiers::SHIFT);
+pub const BLAME_INSPECT_COMMIT: KeyEvent = no_mod(KeyCode::Enter);
+pub const LOG_CHERRY_PICK: KeyEvent =
+    with_mod(KeyCode::Char('C'), KeyModifiers::SHIFT);
+pub const LOG_REVERT: KeyEvent =
+    with_mod(KeyCode::Char('V'), KeyModifiers::SHIFT);
+pub const LOG_CHERRY_PICK_COMMIT: KeyEvent =
+    with_mod(KeyCode::Char('p'), KeyModifiers::CONTROL);
+pub const LOG_REVERT_COMMIT: KeyEvent =
+    with_mod(KeyCode::Char('r'), KeyModifiers::CONTROL);
+pub const LOG_REBASE: KeyEvent =
+    with_mod(KeyCode::Char('R'), KeyModifiers::SHIFT);
+pub const LOG_MARK: KeyEvent = no_mod(KeyCode::Char(' '));
+pub const LOG_COMPARE: KeyEvent = no_mod(KeyCode::Char('c'));
+pub const REBASE_TODO_PICK: KeyEvent = no_mod(KeyCode::Char('p'));
+pub const REBASE_TODO_REWORD: KeyEvent = no_mod(KeyCode::Char('r'));
+pub const REBASE_TODO_EDIT: KeyEvent = no_mod(KeyCode::Char('e'));
+pub const REBASE_TODO_SQUASH: KeyEvent = no_mod(KeyCode::Char('s'));
+pub const REBASE_TODO_FIXUP: KeyEvent = no_mod(KeyCode::Char('f'));
+pub const REBASE_TODO_DROP: KeyEvent = no_mod(KeyCode::Char('d'));
+pub const REBASE_TODO_MOVE_UP: KeyEvent = SHIFT_UP;
+pub const REBASE_TODO_MOVE_DOWN: KeyEvent = SHIFT_DOWN;
+pub const REBASE_TODO_CONFIRM: KeyEvent = no_mod(KeyCode::Enter);
+pub const REBASE_CONTINUE: KeyEvent =
+    with_mod(KeyCode::Char('R'), KeyModifiers::SHIFT);
+pub const REBASE_ABORT: KeyEvent =
+    with_mod(KeyCode::Char('A'), KeyModifiers::SHIFT);
+pub const MERGE_CONTINUE: KeyEvent = REBASE_CONTINUE;
+pub const MERGE_ABORT: KeyEvent = REBASE_ABORT;
+pub const CONFLICT_MARK_RESOLVED: KeyEvent = STATUS_STAGE_FILE;
+pub const CONFLICT_OURS: KeyEvent = no_mod(KeyCode::Char('o'));
+pub const CONFLICT_THEIRS: KeyEvent = no_mod(KeyCode::Char('t'));
+pub const CONFLICT_VIEW: KeyEvent = no_mod(KeyCode::Char('v'));
+pub const MERGE_TOOL: KeyEvent = no_mod(KeyCode::Char('m'));
+pub const DIFF_TOOL: KeyEvent = no_mod(KeyCode::Char('d'));
+pub const BRANCH_CHECKOUT: KeyEvent = no_mod(KeyCode::Enter);
+pub const BRANCH_CREATE: KeyEvent = no_mod(KeyCode::Char('c'));
+pub const BRANCH_RENAME: KeyEvent = no_mod(KeyCode::Char('r'));
+pub const BRANCH_DELETE: KeyEvent =
+    with_mod(KeyCode::Char('D'), KeyModifiers::SHIFT);
+pub const BRANCH_MERGE: KeyEvent = no_mod(KeyCode::Char('m'));
+pub const BRANCH_MERGE_NO_FF: KeyEvent =
+    with_mod(KeyCode::Char('M'), KeyModifiers::SHIFT);
+pub const BRANCH_COMPARE: KeyEvent = no_mod(KeyCode::Char('v'));
+pub const BRANCH_COMPARE_FOCUS: KeyEvent = TAB_TOGGLE;
+pub const BRANCH_COMPARE_INSPECT: KeyEvent = ENTER;
 pub const COMMIT_AMEND: KeyEvent =
     with_mod(KeyCode::Char('a'), KeyModifiers::CONTROL);
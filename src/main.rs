@@ -12,8 +12,10 @@
 mod app;
 mod cmdbar;
 mod components;
+mod external;
 mod input;
 mod keys;
+mod options;
 mod queue;
 mod spinner;
 mod strings;
@@ -0,0 +1,60 @@
+//! options changeable at runtime, persisted in the config directory
+
+use crate::get_app_config_path;
+use anyhow::Result;
+use asyncgit::sync::DiffOptions;
+use ron::{
+    de::from_bytes,
+    ser::{to_string_pretty, PrettyConfig},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{Read, Write},
+    path::PathBuf,
+    rc::Rc,
+};
+
+pub type SharedOptions = Rc<RefCell<Options>>;
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Options {
+    pub diff: DiffOptions,
+}
+
+impl Options {
+    pub fn save(&self) -> Result<()> {
+        let options_file = Self::get_options_file()?;
+        let mut file = File::create(options_file)?;
+        let data = to_string_pretty(self, PrettyConfig::default())?;
+        file.write_all(data.as_bytes())?;
+        Ok(())
+    }
+
+    fn get_options_file() -> Result<PathBuf> {
+        let app_home = get_app_config_path()?;
+        Ok(app_home.join("options.ron"))
+    }
+
+    fn read_file(options_file: PathBuf) -> Result<Self> {
+        let mut f = File::open(options_file)?;
+        let mut buffer = Vec::new();
+        f.read_to_end(&mut buffer)?;
+        Ok(from_bytes(&buffer)?)
+    }
+
+    fn init_internal() -> Result<Self> {
+        let file = Self::get_options_file()?;
+        if file.exists() {
+            Self::read_file(file)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn init() -> Self {
+        Self::init_internal().unwrap_or_default()
+    }
+}
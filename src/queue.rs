@@ -1,5 +1,8 @@
 use crate::tabs::StashingOptions;
-use asyncgit::sync::CommitId;
+use asyncgit::{
+    sync::{CommitId, LogFilter, LogWalkSpec},
+    BlameParams, DiffLinePosition,
+};
 use bitflags::bitflags;
 use std::{cell::RefCell, collections::VecDeque, rc::Rc};
 
@@ -27,7 +30,32 @@ pub struct ResetItem {
 pub enum Action {
     Reset(ResetItem),
     ResetHunk(String, u64),
+    ResetLines(String, Vec<DiffLinePosition>),
     StashDrop(CommitId),
+    DeleteBranch(String),
+    ForcePush(String),
+    RebaseAbort,
+    MergeAbort,
+}
+
+/// program to hand the terminal over to, see `external`
+pub enum ExternalProgram {
+    /// edit the commit message in `$EDITOR`
+    CommitEditor,
+    /// `merge.tool` on a conflicted file
+    MergeTool(String),
+    /// `diff.tool` on a file of the workdir (or stage if `true`)
+    DiffTool(String, bool),
+}
+
+/// operation on a remote that might need credentials
+#[derive(Clone)]
+pub enum RemoteOperation {
+    /// fetch a remote (the default one if `None`)
+    /// and integrate the upstream if `true`
+    Fetch(Option<String>, bool),
+    /// push local branch (reference), with lease if forced
+    Push(String, bool),
 }
 
 ///
@@ -39,6 +67,8 @@ pub enum InternalEvent {
     ///
     ShowErrorMsg(String),
     ///
+    ShowInfoMsg(String),
+    ///
     Update(NeedsUpdate),
     /// open commit msg input
     OpenCommit,
@@ -48,8 +78,37 @@ pub enum InternalEvent {
     TabSwitch,
     ///
     InspectCommit(CommitId),
-    ///
-    SuspendPolling,
+    /// diff between two commits (or a commit and the workdir if `None`)
+    CompareCommits(CommitId, Option<CommitId>),
+    /// review `HEAD` against a branch (reference, name)
+    CompareBranch(String, String),
+    /// suspend input polling and run an external program
+    RunExternal(ExternalProgram),
+    /// open create branch popup (on HEAD if `None`)
+    CreateBranch(Option<CommitId>),
+    /// open rename popup for branch (reference, current name)
+    RenameBranch(String, String),
+    /// start (or retry) an operation on a remote
+    Remote(RemoteOperation),
+    /// ask for credentials of remote url, then retry the operation
+    AskCredentials(String, RemoteOperation),
+    /// open the popup to choose what the log shows
+    OpenWalkSpec(LogWalkSpec),
+    /// show `LogWalkSpec` in the log
+    SetWalkSpec(LogWalkSpec),
+    /// open the log search popup (with the current query)
+    OpenLogSearch(String),
+    /// filter the log (`None` shows all commits again)
+    SetLogFilter(Option<LogFilter>),
+    /// open the history of a file
+    OpenFileHistory(String),
+    /// open the blame of a file
+    BlameFile(BlameParams),
+    /// open the interactive rebase todo list for the commits after
+    /// the base
+    OpenRebase(CommitId),
+    /// open the popup to change how diffs are generated
+    OpenDiffOptions,
 }
 
 ///
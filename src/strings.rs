@@ -1,16 +1,22 @@
 pub static TITLE_STATUS: &str = "Unstaged Changes [w]";
 pub static TITLE_DIFF: &str = "Diff: ";
+pub static DIFF_TRUNCATED: &str = " (truncated)";
+pub static DIFF_BINARY: &str = "binary file";
 pub static TITLE_INDEX: &str = "Staged Changes [s]";
+pub static TITLE_CONFLICTS: &str = "Conflicts";
+pub static TITLE_CONFLICT: &str = "Conflict: ";
 
 pub static TAB_STATUS: &str = "Status [1]";
 pub static TAB_LOG: &str = "Log [2]";
 pub static TAB_STASHING: &str = "Stashing [3]";
 pub static TAB_STASHES: &str = "Stashes [4]";
+pub static TAB_BRANCHES: &str = "Branches [5]";
 pub static TAB_DIVIDER: &str = "  |  ";
 
 pub static CMD_SPLITTER: &str = " ";
 
 pub static MSG_TITLE_ERROR: &str = "Error";
+pub static MSG_TITLE_INFO: &str = "Info";
 pub static COMMIT_TITLE: &str = "Commit";
 pub static COMMIT_TITLE_AMEND: &str = "Commit (Amend)";
 pub static COMMIT_MSG: &str = "type commit message..";
@@ -20,19 +26,102 @@ pub static COMMIT_EDITOR_MSG: &str = r##"
 # Empty commit message will abort the commit"##;
 pub static STASH_POPUP_TITLE: &str = "Stash";
 pub static STASH_POPUP_MSG: &str = "type name (optional)";
+pub static CREATE_BRANCH_POPUP_TITLE: &str = "Branch";
+pub static CREATE_BRANCH_POPUP_MSG: &str = "type branch name";
+pub static RENAME_BRANCH_POPUP_TITLE: &str = "Rename Branch";
+pub static RENAME_BRANCH_POPUP_MSG: &str = "type new branch name";
 pub static CONFIRM_TITLE_RESET: &str = "Reset";
 pub static CONFIRM_TITLE_STASHDROP: &str = "Drop";
+pub static CONFIRM_TITLE_DELETEBRANCH: &str = "Delete Branch";
 pub static CONFIRM_MSG_RESET: &str = "confirm file reset?";
 pub static CONFIRM_MSG_STASHDROP: &str = "confirm stash drop?";
 pub static CONFIRM_MSG_RESETHUNK: &str = "confirm reset hunk?";
+pub static CONFIRM_MSG_RESETLINES: &str = "confirm reset lines?";
+pub static CONFIRM_MSG_DELETEBRANCH: &str = "confirm branch delete?";
+pub static CONFIRM_TITLE_FORCEPUSH: &str = "Force Push";
+pub static CONFIRM_MSG_FORCEPUSH: &str =
+    "overwrite remote branch (with lease)?";
+pub static CONFIRM_TITLE_REBASE_ABORT: &str = "Abort Rebase";
+pub static CONFIRM_MSG_REBASE_ABORT: &str =
+    "discard the rebase and restore the branch?";
+pub static CONFIRM_TITLE_MERGE_ABORT: &str = "Abort";
+pub static CONFIRM_MSG_MERGE_ABORT: &str =
+    "discard the merge, cherry-pick or revert and restore HEAD?";
 
 pub static LOG_TITLE: &str = "Commit";
 pub static STASHLIST_TITLE: &str = "Stashes";
+pub static BRANCHLIST_TITLE: &str = "Branches";
+
+pub static FETCH_POPUP_TITLE: &str = "Fetch";
+pub static SELECT_REMOTE_TITLE: &str = "Fetch Remote";
+pub static FETCH_POPUP_CONNECTING: &str = "connecting..";
+pub static PULL_POPUP_TITLE: &str = "Pull";
+pub static PULL_NO_UPSTREAM_MSG: &str =
+    "pull: the current branch has no upstream configured";
+pub static PULL_CONFLICTS_MSG: &str =
+    "pull stopped on conflicts.\nresolve them in the status tab and commit (merge) or continue the rebase.";
+pub static PUSH_POPUP_TITLE: &str = "Push";
+pub static FORCE_PUSH_POPUP_TITLE: &str = "Force Push";
+pub static PUSH_POPUP_CONNECTING: &str = "connecting..";
+pub static PUSH_POPUP_PUSHING: &str = "pushing..";
+pub static WALK_SPEC_POPUP_TITLE: &str = "Log Revisions";
+pub static WALK_SPEC_POPUP_MSG: &str =
+    "HEAD, --all, branch, rev or from..to";
+pub static LOG_SEARCH_POPUP_TITLE: &str = "Search Log";
+pub static LOG_SEARCH_POPUP_MSG: &str =
+    "text, /regex/, msg:, author:, hash:, date:from..to";
+pub static LOG_SEARCH_MATCHES: &str = "matches";
+pub static COMPARE_TITLE: &str = "Compare";
+pub static COMPARE_WORKDIR: &str = "workdir";
+pub static BRANCH_COMPARE_COMMITS_TITLE: &str = "Commits not on";
+pub static BRANCH_COMPARE_FILES_TITLE: &str = "Changes since";
+pub static FILE_HISTORY_TITLE: &str = "History:";
+pub static BLAME_TITLE: &str = "Blame:";
+pub static BLAME_TITLE_LOADING: &str = " (loading..)";
+pub static BLAME_TITLE_FAILED: &str = " (failed)";
+pub static BLAME_NOT_COMMITTED: &str = "not committed yet";
+pub static REBASE_TODO_TITLE: &str = "Rebase onto";
+pub static REBASE_REWORD_POPUP_TITLE: &str = "Reword";
+pub static REBASE_REWORD_POPUP_MSG: &str = "type new commit message";
+pub static REBASE_NOTHING_MSG: &str =
+    "rebase: no commits after the selected one";
+pub static REBASE_EDIT_MSG: &str =
+    "rebase stopped to edit a commit.\nstage changes to amend it, then continue the rebase in the status tab.";
+pub static REBASE_CONFLICTS_MSG: &str =
+    "rebase stopped on conflicts.\nresolve and stage them in the status tab, then continue the rebase.";
+pub static REBASE_STATE: &str = "rebasing";
+pub static REPO_STATE_MERGE: &str = "merging";
+pub static REPO_STATE_CHERRY_PICK: &str = "cherry-picking";
+pub static REPO_STATE_REVERT: &str = "reverting";
+pub static REPO_STATE_OTHER: &str = "operation in progress";
+pub static PICK_CONFLICTS_MSG: &str =
+    "stopped on conflicts.\nresolve and stage them in the status tab, then commit.";
+pub static MERGE_UP_TO_DATE_MSG: &str = "already up to date.";
+pub static REBASE_STATE_EDIT: &str = "stopped to edit";
+pub static REBASE_STATE_CONFLICTS: &str = "stopped on conflicts";
+pub static BANNER_CONFLICTS: &str =
+    "conflicted file(s) - resolve and stage them";
+pub static BANNER_RESOLVED: &str =
+    "no conflicts - continue [R] or abort [A]";
+pub static MERGE_CONFLICTS_LEFT_MSG: &str =
+    "resolve and stage all conflicts first.";
+pub static CONFLICT_VIEW_MERGED: &str = "merged";
+pub static CONFLICT_VIEW_OURS: &str = "ours";
+pub static CONFLICT_VIEW_THEIRS: &str = "theirs";
+pub static CONFLICT_VIEW_BASE: &str = "base";
+pub static CONFLICT_VERSION_MISSING: &str =
+    "file does not exist in this version";
+pub static CRED_USERNAME_POPUP_TITLE: &str = "Username for";
+pub static CRED_USERNAME_POPUP_MSG: &str = "type username";
+pub static CRED_PASSWORD_POPUP_TITLE: &str =
+    "Password / ssh key passphrase";
+pub static CRED_PASSWORD_POPUP_MSG: &str = "type password";
 
 pub static HELP_TITLE: &str = "Help: all commands";
 
 pub static STASHING_FILES_TITLE: &str = "Files to Stash";
 pub static STASHING_OPTIONS_TITLE: &str = "Options";
+pub static DIFF_OPTIONS_TITLE: &str = "Diff Options";
 
 pub mod commit {
     pub static DETAILS_AUTHOR: &str = "Author: ";
@@ -61,6 +150,7 @@ pub mod commands {
     static CMD_GROUP_STASHING: &str = "-- Stashing --";
     static CMD_GROUP_STASHES: &str = "-- Stashes --";
     static CMD_GROUP_LOG: &str = "-- Log --";
+    static CMD_GROUP_BRANCHES: &str = "-- Branches --";
 
     ///
     pub static TOGGLE_TABS: CommandText = CommandText::new(
@@ -70,11 +160,41 @@ pub mod commands {
     );
     ///
     pub static TOGGLE_TABS_DIRECT: CommandText = CommandText::new(
-        "Tab [1234]",
+        "Tab [12345]",
         "switch top level tabs directly",
         CMD_GROUP_GENERAL,
     );
     ///
+    pub static FETCH: CommandText = CommandText::new(
+        "Fetch [f]",
+        "fetch a remote (asks which one if there are several)",
+        CMD_GROUP_GENERAL,
+    );
+    ///
+    pub static SELECT_REMOTE_CONFIRM: CommandText = CommandText::new(
+        "Fetch [enter]",
+        "fetch the selected remote",
+        CMD_GROUP_GENERAL,
+    );
+    ///
+    pub static PULL: CommandText = CommandText::new(
+        "Pull [F]",
+        "fetch and integrate the upstream (fast-forward, else merge or rebase as in `pull.rebase`)",
+        CMD_GROUP_GENERAL,
+    );
+    ///
+    pub static PUSH: CommandText = CommandText::new(
+        "Push [p]",
+        "push the current branch to its remote",
+        CMD_GROUP_GENERAL,
+    );
+    ///
+    pub static FORCE_PUSH: CommandText = CommandText::new(
+        "Force Push [P]",
+        "force push the current branch with lease (after confirmation)",
+        CMD_GROUP_GENERAL,
+    );
+    ///
     pub static HELP_OPEN: CommandText = CommandText::new(
         "Help [h]",
         "open this help screen",
@@ -87,6 +207,24 @@ pub mod commands {
         CMD_GROUP_GENERAL,
     );
     ///
+    pub static FILE_HISTORY: CommandText = CommandText::new(
+        "History [l]",
+        "show the commits changing the selected file",
+        CMD_GROUP_GENERAL,
+    );
+    ///
+    pub static BLAME_FILE: CommandText = CommandText::new(
+        "Blame [B]",
+        "show the commit that last changed each line of the file",
+        CMD_GROUP_GENERAL,
+    );
+    ///
+    pub static BLAME_INSPECT_COMMIT: CommandText = CommandText::new(
+        "Inspect [enter]",
+        "inspect the commit that last changed the line",
+        CMD_GROUP_GENERAL,
+    );
+    ///
     pub static SCROLL: CommandText = CommandText::new(
         "Scroll [\u{2191}\u{2193}]",
         "scroll up or down in focused view",
@@ -94,7 +232,7 @@ pub mod commands {
     );
     ///
     pub static DIFF_HOME_END: CommandText = CommandText::new(
-        "Jump up/down [home,end,\u{2191} up,\u{2193} down]",
+        "Jump up/down [home,end]",
         "scroll to top or bottom of diff",
         CMD_GROUP_DIFF,
     );
@@ -117,6 +255,60 @@ pub mod commands {
         CMD_GROUP_DIFF,
     );
     ///
+    pub static DIFF_TOGGLE_SPLIT: CommandText = CommandText::new(
+        "Split view [v]",
+        "toggle showing old and new lines side by side",
+        CMD_GROUP_DIFF,
+    );
+    ///
+    pub static DIFF_LOAD_FULL: CommandText = CommandText::new(
+        "Load full diff [e]",
+        "show the lines left out of a diff beyond the size limits",
+        CMD_GROUP_DIFF,
+    );
+    ///
+    pub static DIFF_OPTIONS: CommandText = CommandText::new(
+        "Options [o]",
+        "change whitespace, context and rename detection of diffs",
+        CMD_GROUP_DIFF,
+    );
+    ///
+    pub static DIFF_OPTIONS_SELECT: CommandText = CommandText::new(
+        "Select [\u{2191}\u{2193}]",
+        "select a diff option",
+        CMD_GROUP_DIFF,
+    );
+    ///
+    pub static DIFF_OPTIONS_CHANGE: CommandText = CommandText::new(
+        "Change [\u{2190}\u{2192}]",
+        "change the selected diff option",
+        CMD_GROUP_DIFF,
+    );
+    ///
+    pub static DIFF_SELECT_LINES: CommandText = CommandText::new(
+        "Select lines [\u{21e7}\u{2191}\u{2193}]",
+        "extend the selection to a range of lines",
+        CMD_GROUP_DIFF,
+    );
+    ///
+    pub static DIFF_LINES_ADD: CommandText = CommandText::new(
+        "Add lines [l]",
+        "adds selected lines to stage",
+        CMD_GROUP_DIFF,
+    );
+    ///
+    pub static DIFF_LINES_REVERT: CommandText = CommandText::new(
+        "Revert lines [L]",
+        "reverts selected lines",
+        CMD_GROUP_DIFF,
+    );
+    ///
+    pub static DIFF_LINES_REMOVE: CommandText = CommandText::new(
+        "Remove lines [l]",
+        "removes selected lines from stage",
+        CMD_GROUP_DIFF,
+    );
+    ///
     pub static CLOSE_POPUP: CommandText = CommandText::new(
         "Close [esc]",
         "close overlay (e.g commit, help)",
@@ -208,6 +400,78 @@ pub mod commands {
         CMD_GROUP_CHANGES,
     );
     ///
+    pub static REBASE_CONTINUE: CommandText = CommandText::new(
+        "Continue Rebase [R]",
+        "continue the rebase after resolving conflicts or editing",
+        CMD_GROUP_CHANGES,
+    );
+    ///
+    pub static REBASE_ABORT: CommandText = CommandText::new(
+        "Abort Rebase [A]",
+        "abort the rebase and restore the branch (after confirmation)",
+        CMD_GROUP_CHANGES,
+    );
+    ///
+    pub static MERGE_CONTINUE: CommandText = CommandText::new(
+        "Continue [R]",
+        "commit the merge, cherry-pick or revert once all conflicts are resolved",
+        CMD_GROUP_CHANGES,
+    );
+    ///
+    pub static MERGE_ABORT: CommandText = CommandText::new(
+        "Abort [A]",
+        "abort the merge, cherry-pick or revert and restore HEAD (after confirmation)",
+        CMD_GROUP_CHANGES,
+    );
+    ///
+    pub static CONFLICT_MARK_RESOLVED: CommandText = CommandText::new(
+        "Mark Resolved [enter]",
+        "stage the selected file once its conflicts are resolved",
+        CMD_GROUP_CHANGES,
+    );
+    ///
+    pub static CONFLICT_TAKE_OURS: CommandText = CommandText::new(
+        "Take Ours [o]",
+        "resolve the selected file with its HEAD version",
+        CMD_GROUP_CHANGES,
+    );
+    ///
+    pub static CONFLICT_TAKE_THEIRS: CommandText = CommandText::new(
+        "Take Theirs [t]",
+        "resolve the selected file with the version merged in",
+        CMD_GROUP_CHANGES,
+    );
+    ///
+    pub static MERGE_TOOL: CommandText = CommandText::new(
+        "Merge Tool [m]",
+        "resolve the selected file in the merge.tool from git config",
+        CMD_GROUP_CHANGES,
+    );
+    ///
+    pub static DIFF_TOOL: CommandText = CommandText::new(
+        "Diff Tool [d]",
+        "show the changes of the selected file in the diff.tool from git config",
+        CMD_GROUP_CHANGES,
+    );
+    ///
+    pub static CONFLICT_HUNK_OURS: CommandText = CommandText::new(
+        "Hunk Ours [o]",
+        "resolve the selected conflict with the HEAD side",
+        CMD_GROUP_DIFF,
+    );
+    ///
+    pub static CONFLICT_HUNK_THEIRS: CommandText = CommandText::new(
+        "Hunk Theirs [t]",
+        "resolve the selected conflict with the side merged in",
+        CMD_GROUP_DIFF,
+    );
+    ///
+    pub static CONFLICT_VIEW: CommandText = CommandText::new(
+        "Version [v]",
+        "show the merged file, ours, theirs or base",
+        CMD_GROUP_DIFF,
+    );
+    ///
     pub static DIFF_FOCUS_LEFT: CommandText = CommandText::new(
         "Back [\u{2190}]", //←
         "view and select changed files",
@@ -289,4 +553,193 @@ pub mod commands {
         "inspect selected commit in detail",
         CMD_GROUP_LOG,
     );
+    ///
+    pub static LOG_CREATE_BRANCH: CommandText = CommandText::new(
+        "Branch [b]",
+        "create branch on selected commit",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static LOG_WALK_SPEC: CommandText = CommandText::new(
+        "Revisions [w]",
+        "choose what the log shows: HEAD, --all, a branch or a range",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static WALK_SPEC_CONFIRM_MSG: CommandText = CommandText::new(
+        "Show [enter]",
+        "show these revisions in the log",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static LOG_SEARCH: CommandText = CommandText::new(
+        "Search [/]",
+        "only show commits matching text, regex, author, hash or dates",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static LOG_SEARCH_CLEAR: CommandText = CommandText::new(
+        "End Search [esc]",
+        "show all commits again",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static LOG_SEARCH_CONFIRM_MSG: CommandText = CommandText::new(
+        "Search [enter]",
+        "search the log (empty to show all commits)",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static LOG_CHERRY_PICK: CommandText = CommandText::new(
+        "Cherry-pick [C]",
+        "apply the selected commit onto HEAD, staged for committing",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static LOG_REVERT: CommandText = CommandText::new(
+        "Revert [V]",
+        "undo the selected commit on HEAD, staged for committing",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static LOG_CHERRY_PICK_COMMIT: CommandText = CommandText::new(
+        "Cherry-pick & commit [^p]",
+        "apply the selected commit onto HEAD and commit it right away",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static LOG_REVERT_COMMIT: CommandText = CommandText::new(
+        "Revert & commit [^r]",
+        "undo the selected commit on HEAD and commit that right away",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static LOG_REBASE: CommandText = CommandText::new(
+        "Rebase [R]",
+        "interactively rebase the commits after the selected one",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static LOG_MARK: CommandText = CommandText::new(
+        "Mark [space]",
+        "mark the selected commit to compare it with another one",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static LOG_COMPARE: CommandText = CommandText::new(
+        "Compare [c]",
+        "diff the marked and the selected commit (or the working tree)",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static REBASE_TODO_ACTION: CommandText = CommandText::new(
+        "Pick/Reword/Edit/Squash/Fixup/Drop [p,r,e,s,f,d]",
+        "set what to do with the selected commit",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static REBASE_TODO_MOVE: CommandText = CommandText::new(
+        "Move [\u{21e7}\u{2191}\u{2193}]", //⇧↑↓
+        "move the selected commit up or down",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static REBASE_TODO_CONFIRM: CommandText = CommandText::new(
+        "Rebase [enter]",
+        "start the rebase",
+        CMD_GROUP_LOG,
+    );
+    ///
+    pub static REBASE_REWORD_CONFIRM: CommandText = CommandText::new(
+        "Reword [enter]",
+        "use the new commit message",
+        CMD_GROUP_LOG,
+    );
+
+    ///
+    pub static BRANCHLIST_CHECKOUT: CommandText = CommandText::new(
+        "Checkout [enter]",
+        "checkout selected branch",
+        CMD_GROUP_BRANCHES,
+    );
+    ///
+    pub static BRANCHLIST_CREATE: CommandText = CommandText::new(
+        "Create [c]",
+        "create new branch on HEAD",
+        CMD_GROUP_BRANCHES,
+    );
+    ///
+    pub static BRANCHLIST_RENAME: CommandText = CommandText::new(
+        "Rename [r]",
+        "rename selected branch",
+        CMD_GROUP_BRANCHES,
+    );
+    ///
+    pub static BRANCHLIST_DELETE: CommandText = CommandText::new(
+        "Delete [D]",
+        "delete selected branch",
+        CMD_GROUP_BRANCHES,
+    );
+    ///
+    pub static BRANCHLIST_PUSH: CommandText = CommandText::new(
+        "Push [p]",
+        "push selected branch to its remote",
+        CMD_GROUP_BRANCHES,
+    );
+    ///
+    pub static BRANCHLIST_FORCE_PUSH: CommandText = CommandText::new(
+        "Force Push [P]",
+        "force push selected branch with lease (after confirmation)",
+        CMD_GROUP_BRANCHES,
+    );
+    ///
+    pub static BRANCHLIST_COMPARE: CommandText = CommandText::new(
+        "Review [v]",
+        "show commits and changes of HEAD not on the selected branch",
+        CMD_GROUP_BRANCHES,
+    );
+    ///
+    pub static BRANCH_COMPARE_FOCUS: CommandText = CommandText::new(
+        "Commits/Files [tab]",
+        "switch between the commits and the changed files",
+        CMD_GROUP_BRANCHES,
+    );
+    ///
+    pub static BRANCH_COMPARE_INSPECT: CommandText = CommandText::new(
+        "Inspect [enter]",
+        "inspect the selected commit",
+        CMD_GROUP_BRANCHES,
+    );
+    ///
+    pub static BRANCHLIST_MERGE: CommandText = CommandText::new(
+        "Merge [m]",
+        "merge selected branch into HEAD (fast-forward if possible)",
+        CMD_GROUP_BRANCHES,
+    );
+    ///
+    pub static BRANCHLIST_MERGE_NO_FF: CommandText = CommandText::new(
+        "Merge no-ff [M]",
+        "merge selected branch into HEAD creating a merge commit",
+        CMD_GROUP_BRANCHES,
+    );
+    ///
+    pub static CREATE_BRANCH_CONFIRM_MSG: CommandText =
+        CommandText::new(
+            "Create [enter]",
+            "create branch",
+            CMD_GROUP_BRANCHES,
+        );
+    ///
+    pub static RENAME_BRANCH_CONFIRM_MSG: CommandText =
+        CommandText::new(
+            "Rename [enter]",
+            "rename branch",
+            CMD_GROUP_BRANCHES,
+        );
+    ///
+    pub static CRED_CONFIRM: CommandText = CommandText::new(
+        "Next [enter]",
+        "confirm username, then password and retry",
+        CMD_GROUP_GENERAL,
+    );
 }
@@ -0,0 +1,408 @@
+use crate::{
+    components::{
+        visibility_blocking, CommandBlocking, CommandInfo, Component,
+        DrawableComponent, ScrollType,
+    },
+    keys,
+    queue::{
+        Action, InternalEvent, NeedsUpdate, Queue, RemoteOperation,
+    },
+    strings, ui,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, BranchInfo, MergeOutcome},
+    CWD,
+};
+use crossterm::event::Event;
+use std::{borrow::Cow, cmp};
+use strings::commands;
+use tui::{backend::Backend, layout::Rect, widgets::Text, Frame};
+
+///
+pub struct BranchList {
+    branches: Vec<BranchInfo>,
+    selection: usize,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+}
+
+impl BranchList {
+    ///
+    pub fn new(queue: &Queue, theme: SharedTheme) -> Self {
+        Self {
+            branches: Vec::new(),
+            selection: 0,
+            visible: false,
+            queue: queue.clone(),
+            theme,
+        }
+    }
+
+    ///
+    pub fn update(&mut self) -> Result<()> {
+        if self.visible {
+            self.branches = sync::get_branches_info(CWD)?;
+            self.selection = cmp::min(
+                self.selection,
+                self.branches.len().saturating_sub(1),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// called after confirmation
+    pub fn delete(&self, branch_ref: &str) -> bool {
+        if let Err(e) = sync::delete_branch(CWD, branch_ref) {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(format!(
+                    "delete branch error:\n{}",
+                    e
+                )),
+            );
+            false
+        } else {
+            true
+        }
+    }
+
+    fn selected_branch(&self) -> Option<&BranchInfo> {
+        self.branches.get(self.selection)
+    }
+
+    fn move_selection(&mut self, scroll: ScrollType) -> bool {
+        let max = self.branches.len().saturating_sub(1);
+
+        let new_selection = match scroll {
+            ScrollType::Up | ScrollType::PageUp => {
+                self.selection.saturating_sub(1)
+            }
+            ScrollType::Down | ScrollType::PageDown => {
+                self.selection.saturating_add(1)
+            }
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+        };
+
+        let new_selection = cmp::min(new_selection, max);
+        let changed = new_selection != self.selection;
+        self.selection = new_selection;
+
+        changed
+    }
+
+    fn checkout(&mut self) {
+        if let Some(b) = self.selected_branch() {
+            if b.is_head {
+                return;
+            }
+
+            if let Err(e) = sync::checkout_branch(CWD, &b.reference) {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "checkout error:\n{}",
+                        e
+                    )),
+                );
+            } else {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Update(NeedsUpdate::ALL),
+                );
+            }
+        }
+    }
+
+    fn merge(&mut self, no_ff: bool) {
+        if let Some(b) = self.selected_branch() {
+            if b.is_head {
+                return;
+            }
+
+            let res = sync::merge_branch(CWD, &b.reference, no_ff);
+
+            let mut queue = self.queue.borrow_mut();
+            match res {
+                Ok(MergeOutcome::UpToDate) => {
+                    queue.push_back(InternalEvent::ShowInfoMsg(
+                        String::from(strings::MERGE_UP_TO_DATE_MSG),
+                    ));
+                }
+                // conclude the merge with the prepared message
+                Ok(MergeOutcome::Staged) => {
+                    queue.push_back(InternalEvent::OpenCommit);
+                }
+                Ok(MergeOutcome::Conflicts) => {
+                    queue.push_back(InternalEvent::ShowErrorMsg(
+                        String::from(strings::PICK_CONFLICTS_MSG),
+                    ));
+                }
+                Ok(_) => (),
+                Err(e) => {
+                    queue.push_back(InternalEvent::ShowErrorMsg(
+                        format!("merge error:\n{}", e),
+                    ));
+                }
+            }
+            queue.push_back(InternalEvent::Update(NeedsUpdate::ALL));
+        }
+    }
+
+    fn rename(&mut self) {
+        if let Some(b) = self.selected_branch() {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::RenameBranch(
+                    b.reference.clone(),
+                    b.name.clone(),
+                ),
+            );
+        }
+    }
+
+    fn push(&mut self, force: bool) {
+        if let Some(b) = self.selected_branch() {
+            let branch_ref = b.reference.clone();
+            self.queue.borrow_mut().push_back(if force {
+                InternalEvent::ConfirmAction(Action::ForcePush(
+                    branch_ref,
+                ))
+            } else {
+                InternalEvent::Remote(RemoteOperation::Push(
+                    branch_ref, false,
+                ))
+            });
+        }
+    }
+
+    fn compare(&mut self) {
+        if let Some(b) = self.selected_branch() {
+            if b.is_head {
+                return;
+            }
+
+            self.queue.borrow_mut().push_back(
+                InternalEvent::CompareBranch(
+                    b.reference.clone(),
+                    b.name.clone(),
+                ),
+            );
+        }
+    }
+
+    fn delete_confirm(&mut self) {
+        if let Some(b) = self.selected_branch() {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ConfirmAction(Action::DeleteBranch(
+                    b.reference.clone(),
+                )),
+            );
+        }
+    }
+
+    fn get_text(&self, width: usize) -> Vec<Text> {
+        let name_width = self
+            .branches
+            .iter()
+            .map(|b| b.name.len())
+            .max()
+            .unwrap_or_default();
+
+        self.branches
+            .iter()
+            .enumerate()
+            .map(|(idx, b)| {
+                let selected = idx == self.selection;
+                let hash: String = b
+                    .top_commit
+                    .to_string()
+                    .chars()
+                    .take(7)
+                    .collect();
+                let upstream = b
+                    .upstream
+                    .as_ref()
+                    .map(|u| format!(" [{}]", u))
+                    .unwrap_or_default();
+
+                let line = format!(
+                    "{} {:w$} {}{} {}",
+                    if b.is_head { "*" } else { " " },
+                    b.name,
+                    hash,
+                    upstream,
+                    b.top_commit_message,
+                    w = name_width,
+                );
+
+                let line = if selected {
+                    format!("{:w$}", line, w = width)
+                } else {
+                    line
+                };
+
+                Text::Styled(
+                    Cow::from(line),
+                    self.theme.branch(selected, b.is_head),
+                )
+            })
+            .collect()
+    }
+}
+
+impl DrawableComponent for BranchList {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        ui::draw_list(
+            f,
+            rect,
+            strings::BRANCHLIST_TITLE,
+            self.get_text(rect.width.saturating_sub(2) as usize)
+                .into_iter(),
+            Some(self.selection),
+            true,
+            &self.theme,
+        );
+
+        Ok(())
+    }
+}
+
+impl Component for BranchList {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            let selection_valid = self.selected_branch().is_some();
+            let selection_not_head =
+                self.selected_branch().map_or(false, |b| !b.is_head);
+
+            out.push(CommandInfo::new(
+                commands::SCROLL,
+                selection_valid,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::BRANCHLIST_CHECKOUT,
+                selection_not_head,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::BRANCHLIST_CREATE,
+                true,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::BRANCHLIST_RENAME,
+                selection_valid,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::BRANCHLIST_DELETE,
+                selection_not_head,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::BRANCHLIST_MERGE,
+                selection_not_head,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::BRANCHLIST_MERGE_NO_FF,
+                selection_not_head,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::BRANCHLIST_PUSH,
+                selection_valid,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::BRANCHLIST_FORCE_PUSH,
+                selection_valid,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::BRANCHLIST_COMPARE,
+                selection_not_head,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.visible {
+            if let Event::Key(k) = ev {
+                return Ok(match k {
+                    keys::MOVE_UP => {
+                        self.move_selection(ScrollType::Up)
+                    }
+                    keys::MOVE_DOWN => {
+                        self.move_selection(ScrollType::Down)
+                    }
+                    keys::SHIFT_UP | keys::HOME => {
+                        self.move_selection(ScrollType::Home)
+                    }
+                    keys::SHIFT_DOWN | keys::END => {
+                        self.move_selection(ScrollType::End)
+                    }
+                    keys::BRANCH_CHECKOUT => {
+                        self.checkout();
+                        true
+                    }
+                    keys::BRANCH_CREATE => {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::CreateBranch(None),
+                        );
+                        true
+                    }
+                    keys::BRANCH_RENAME => {
+                        self.rename();
+                        true
+                    }
+                    keys::BRANCH_DELETE => {
+                        self.delete_confirm();
+                        true
+                    }
+                    keys::BRANCH_MERGE | keys::BRANCH_MERGE_NO_FF => {
+                        self.merge(k == keys::BRANCH_MERGE_NO_FF);
+                        true
+                    }
+                    keys::PUSH | keys::FORCE_PUSH => {
+                        self.push(k == keys::FORCE_PUSH);
+                        true
+                    }
+                    keys::BRANCH_COMPARE => {
+                        self.compare();
+                        true
+                    }
+                    _ => false,
+                });
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        self.update()?;
+        Ok(())
+    }
+}
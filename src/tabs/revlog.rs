@@ -5,16 +5,21 @@ use crate::{
         DrawableComponent,
     },
     keys,
-    queue::{InternalEvent, Queue},
+    options::SharedOptions,
+    queue::{InternalEvent, NeedsUpdate, Queue},
     strings,
     ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::{sync, AsyncLog, AsyncNotification, FetchStatus, CWD};
+use asyncgit::{
+    sync, AsyncLog, AsyncLogFilter, AsyncNotification, FetchStatus,
+    CWD,
+};
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
+use std::fmt::Write;
 use strings::commands;
-use sync::CommitId;
+use sync::{CommitId, LogFilter, LogWalkSpec, PickOutcome};
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
@@ -28,6 +33,13 @@ pub struct Revlog {
     commit_details: CommitDetailsComponent,
     list: CommitList,
     git_log: AsyncLog,
+    git_filter: AsyncLogFilter,
+    walk_spec: LogWalkSpec,
+    filter: Option<LogFilter>,
+    /// matches changed since the items were fetched
+    filter_changed: bool,
+    /// branches or tags may have moved since they were fetched
+    refs_changed: bool,
     queue: Queue,
     visible: bool,
 }
@@ -37,6 +49,7 @@ impl Revlog {
     pub fn new(
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
+        options: SharedOptions,
         theme: SharedTheme,
     ) -> Self {
         Self {
@@ -44,10 +57,16 @@ impl Revlog {
             commit_details: CommitDetailsComponent::new(
                 queue,
                 sender,
+                options,
                 theme.clone(),
             ),
             list: CommitList::new(strings::LOG_TITLE, theme),
             git_log: AsyncLog::new(sender),
+            git_filter: AsyncLogFilter::new(sender),
+            walk_spec: LogWalkSpec::Head,
+            filter: None,
+            filter_changed: false,
+            refs_changed: false,
             visible: false,
         }
     }
@@ -55,37 +74,83 @@ impl Revlog {
     ///
     pub fn any_work_pending(&self) -> bool {
         self.git_log.is_pending()
+            || self.git_filter.is_pending()
             || self.commit_details.any_work_pending()
     }
 
     ///
     pub fn update(&mut self) -> Result<()> {
+        self.update_log()
+    }
+
+    /// refetch branches and tags on the next update, they may have
+    /// moved without a new log
+    pub fn invalidate_refs(&mut self) {
+        self.refs_changed = true;
+        self.git_log.invalidate_refs();
+    }
+
+    fn update_log(&mut self) -> Result<()> {
         if self.visible {
-            let log_changed =
-                self.git_log.fetch()? == FetchStatus::Started;
+            let status = match self.git_log.fetch(&self.walk_spec) {
+                // e.g. the branch we looked at got deleted
+                Err(e) if self.walk_spec != LogWalkSpec::Head => {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "log error:\n{}",
+                            e
+                        )),
+                    );
+                    self.set_walk_spec(LogWalkSpec::Head);
+                    self.git_log.fetch(&self.walk_spec)?
+                }
+                res => res?,
+            };
+            let log_changed = status == FetchStatus::Started;
+
+            if log_changed {
+                if let Some(filter) = &self.filter {
+                    self.git_filter
+                        .start(&self.git_log, filter.clone());
+                }
+            }
 
-            self.list.set_count_total(self.git_log.count()?);
+            self.list.set_count_total(if self.filter.is_some() {
+                self.git_filter.count()?
+            } else {
+                self.git_log.count()?
+            });
+            self.update_title()?;
 
             let selection = self.list.selection();
             let selection_max = self.list.selection_max();
             if self.list.items().needs_data(selection, selection_max)
                 || log_changed
+                || self.filter_changed
             {
+                self.filter_changed = false;
                 self.fetch_commits()?;
             }
 
-            if !self.list.has_tags() || log_changed {
-                self.list.set_tags(sync::get_tags(CWD)?);
+            if !self.list.has_refs()
+                || log_changed
+                || self.refs_changed
+            {
+                self.refs_changed = false;
+                self.list.set_refs(sync::get_refs(CWD)?);
             }
 
             self.list.set_branch(
                 sync::get_branch_name(CWD).map(Some).unwrap_or(None),
             );
+            self.list.set_upstream(
+                sync::get_head_upstream(CWD).unwrap_or(None),
+            );
 
             if self.commit_details.is_visible() {
                 self.commit_details.set_commit(
                     self.selected_commit(),
-                    self.list.tags().expect("tags"),
+                    self.list.refs().expect("refs"),
                 )?;
             }
         }
@@ -101,7 +166,11 @@ impl Revlog {
         if self.visible {
             match ev {
                 AsyncNotification::CommitFiles
-                | AsyncNotification::Log => self.update()?,
+                | AsyncNotification::Log => self.update_log()?,
+                AsyncNotification::LogFilter => {
+                    self.filter_changed = true;
+                    self.update_log()?
+                }
                 _ => (),
             }
         }
@@ -109,18 +178,79 @@ impl Revlog {
         Ok(())
     }
 
+    /// show `spec` instead of `HEAD` (takes effect on next update)
+    pub fn set_walk_spec(&mut self, spec: LogWalkSpec) {
+        self.list.set_selection(0);
+        self.walk_spec = spec;
+    }
+
+    /// only show commits matching `filter` (takes effect on next
+    /// update)
+    pub fn set_filter(&mut self, filter: Option<LogFilter>) {
+        match &filter {
+            Some(filter) => {
+                self.git_filter.start(&self.git_log, filter.clone())
+            }
+            None => self.git_filter.stop(),
+        }
+        self.list.set_selection(0);
+        self.filter = filter;
+        self.filter_changed = true;
+    }
+
+    fn update_title(&mut self) -> Result<()> {
+        let mut title = String::from(strings::LOG_TITLE);
+
+        if self.walk_spec != LogWalkSpec::Head {
+            write!(title, " [{}]", self.walk_spec)?;
+        }
+
+        if let Some(filter) = &self.filter {
+            write!(
+                title,
+                " /{}: {} {}{}",
+                filter.query(),
+                self.git_filter.count()?,
+                strings::LOG_SEARCH_MATCHES,
+                if self.git_filter.is_pending() {
+                    ".."
+                } else {
+                    ""
+                },
+            )?;
+        }
+
+        self.list.set_title(title);
+
+        Ok(())
+    }
+
     fn fetch_commits(&mut self) -> Result<()> {
         let want_min =
             self.list.selection().saturating_sub(SLICE_SIZE / 2);
 
+        let ids = if self.filter.is_some() {
+            self.git_filter.get_slice(want_min, SLICE_SIZE)?
+        } else {
+            self.git_log.get_slice(want_min, SLICE_SIZE)?
+        };
+
         let commits = sync::get_commits_info(
             CWD,
-            &self.git_log.get_slice(want_min, SLICE_SIZE)?,
+            &ids,
             self.list.current_size().0.into(),
         );
 
         if let Ok(commits) = commits {
             self.list.items().set_items(want_min, commits);
+
+            // lanes make no sense with commits left out
+            if self.filter.is_none() {
+                self.list.items().set_graph(
+                    self.git_log
+                        .get_graph_slice(want_min, SLICE_SIZE)?,
+                );
+            }
         }
 
         Ok(())
@@ -129,6 +259,70 @@ impl Revlog {
     fn selected_commit(&self) -> Option<CommitId> {
         self.list.selected_entry().map(|e| e.id)
     }
+
+    /// compares the marked commit with the selected one, or the
+    /// selected commit with the working tree if none (or the selected
+    /// one itself) is marked
+    fn compare_selected(&self) -> bool {
+        let selected = match self.selected_commit() {
+            Some(id) => id,
+            None => return false,
+        };
+
+        let (from, to) = match self.list.marked() {
+            Some(marked) if marked != selected => {
+                (marked, Some(selected))
+            }
+            _ => (selected, None),
+        };
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::CompareCommits(from, to));
+
+        true
+    }
+
+    /// cherry-picks or reverts the selected commit, committing it
+    /// right away or leaving the changes staged to commit them with
+    /// the prepared message
+    fn pick_selected(&self, revert: bool, commit: bool) -> bool {
+        let id = match self.selected_commit() {
+            Some(id) => id,
+            None => return false,
+        };
+
+        let res = if revert {
+            sync::revert_commit(CWD, id, commit)
+        } else {
+            sync::cherry_pick(CWD, id, commit)
+        };
+
+        let mut queue = self.queue.borrow_mut();
+        match res {
+            Ok(PickOutcome::Staged) => {
+                queue.push_back(InternalEvent::OpenCommit);
+            }
+            Ok(PickOutcome::Conflicts) => {
+                queue.push_back(InternalEvent::ShowErrorMsg(
+                    String::from(strings::PICK_CONFLICTS_MSG),
+                ));
+            }
+            Ok(PickOutcome::Committed(_)) => (),
+            Err(e) => {
+                queue.push_back(InternalEvent::ShowErrorMsg(
+                    format!(
+                        "{} error:\n{}",
+                        if revert { "revert" } else { "cherry-pick" },
+                        e
+                    ),
+                ));
+            }
+        }
+        queue.push_back(InternalEvent::Update(NeedsUpdate::ALL));
+
+        true
+    }
 }
 
 impl DrawableComponent for Revlog {
@@ -171,6 +365,64 @@ impl Component for Revlog {
                 self.commit_details.toggle_visible()?;
                 self.update()?;
                 return Ok(true);
+            } else if let Event::Key(keys::LOG_CREATE_BRANCH) = ev {
+                return if let Some(id) = self.selected_commit() {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::CreateBranch(Some(id)),
+                    );
+                    Ok(true)
+                } else {
+                    Ok(false)
+                };
+            } else if let Event::Key(keys::LOG_WALK_SPEC) = ev {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::OpenWalkSpec(
+                        self.walk_spec.clone(),
+                    ),
+                );
+                return Ok(true);
+            } else if let Event::Key(keys::LOG_SEARCH) = ev {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::OpenLogSearch(
+                        self.filter
+                            .as_ref()
+                            .map(|f| String::from(f.query()))
+                            .unwrap_or_default(),
+                    ),
+                );
+                return Ok(true);
+            } else if let Event::Key(keys::LOG_SEARCH_CLEAR) = ev {
+                return if self.filter.is_some() {
+                    self.set_filter(None);
+                    self.update()?;
+                    Ok(true)
+                } else {
+                    Ok(false)
+                };
+            } else if let Event::Key(keys::LOG_CHERRY_PICK) = ev {
+                return Ok(self.pick_selected(false, false));
+            } else if let Event::Key(keys::LOG_REVERT) = ev {
+                return Ok(self.pick_selected(true, false));
+            } else if let Event::Key(keys::LOG_CHERRY_PICK_COMMIT) =
+                ev
+            {
+                return Ok(self.pick_selected(false, true));
+            } else if let Event::Key(keys::LOG_REVERT_COMMIT) = ev {
+                return Ok(self.pick_selected(true, true));
+            } else if let Event::Key(keys::LOG_REBASE) = ev {
+                return if let Some(id) = self.selected_commit() {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::OpenRebase(id));
+                    Ok(true)
+                } else {
+                    Ok(false)
+                };
+            } else if let Event::Key(keys::LOG_MARK) = ev {
+                self.list.toggle_mark();
+                return Ok(true);
+            } else if let Event::Key(keys::LOG_COMPARE) = ev {
+                return Ok(self.compare_selected());
             } else if let Event::Key(keys::FOCUS_RIGHT) = ev {
                 return if let Some(id) = self.selected_commit() {
                     self.queue
@@ -208,6 +460,72 @@ impl Component for Revlog {
                 || force_all,
         ));
 
+        out.push(CommandInfo::new(
+            commands::LOG_CREATE_BRANCH,
+            self.selected_commit().is_some(),
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_WALK_SPEC,
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_SEARCH,
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_SEARCH_CLEAR,
+            true,
+            (self.visible && self.filter.is_some()) || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_CHERRY_PICK,
+            self.selected_commit().is_some(),
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_REVERT,
+            self.selected_commit().is_some(),
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_CHERRY_PICK_COMMIT,
+            self.selected_commit().is_some(),
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_REVERT_COMMIT,
+            self.selected_commit().is_some(),
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_REBASE,
+            self.selected_commit().is_some(),
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_MARK,
+            self.selected_commit().is_some(),
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_COMPARE,
+            self.selected_commit().is_some(),
+            self.visible || force_all,
+        ));
+
         visibility_blocking(self)
     }
 
@@ -2,25 +2,34 @@ use crate::{
     accessors,
     components::{
         self, event_pump, ChangesComponent, CommandBlocking,
-        CommandInfo, Component, DiffComponent, DrawableComponent,
+        CommandInfo, Component, ConflictViewComponent,
+        ConflictsComponent, DiffComponent, DrawableComponent,
         FileTreeItemKind,
     },
     keys,
-    queue::{InternalEvent, Queue, ResetItem},
+    options::SharedOptions,
+    queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
     strings,
     ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::{self, status::StatusType},
+    sync::{
+        self, status::StatusType, RebaseOutcome, RebaseStatus,
+        RebaseStop, RepoState,
+    },
     AsyncDiff, AsyncNotification, AsyncStatus, DiffParams, DiffType,
-    StatusParams, CWD,
+    StatusItem, StatusItemType, StatusParams, CWD,
 };
 use components::{command_pump, visibility_blocking};
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
+use std::borrow::Cow;
 use strings::{commands, order};
-use tui::layout::{Constraint, Direction, Layout};
+use tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::{Paragraph, Text},
+};
 
 ///
 #[derive(PartialEq)]
@@ -28,6 +37,7 @@ enum Focus {
     WorkDir,
     Diff,
     Stage,
+    Conflicts,
 }
 
 ///
@@ -35,6 +45,7 @@ enum Focus {
 enum DiffTarget {
     Stage,
     WorkingDir,
+    Conflicts,
 }
 
 pub struct Status {
@@ -43,10 +54,16 @@ pub struct Status {
     diff_target: DiffTarget,
     index: ChangesComponent,
     index_wd: ChangesComponent,
+    conflicts: ConflictsComponent,
     diff: DiffComponent,
+    conflict_view: ConflictViewComponent,
     git_diff: AsyncDiff,
     git_status_workdir: AsyncStatus,
     git_status_stage: AsyncStatus,
+    repo_state: RepoState,
+    rebase: Option<RebaseStatus>,
+    conflict_count: usize,
+    theme: SharedTheme,
     queue: Queue,
 }
 
@@ -56,6 +73,22 @@ impl DrawableComponent for Status {
         f: &mut tui::Frame<B>,
         rect: tui::layout::Rect,
     ) -> Result<()> {
+        let rect = if self.repo_state == RepoState::Clean {
+            rect
+        } else {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [Constraint::Length(1), Constraint::Min(0)]
+                        .as_ref(),
+                )
+                .split(rect);
+
+            self.draw_banner(f, chunks[0]);
+
+            chunks[1]
+        };
+
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(
@@ -74,6 +107,25 @@ impl DrawableComponent for Status {
             )
             .split(rect);
 
+        let left = if self.conflicts.is_empty() {
+            chunks[0]
+        } else {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [
+                        Constraint::Percentage(30),
+                        Constraint::Percentage(70),
+                    ]
+                    .as_ref(),
+                )
+                .split(chunks[0]);
+
+            self.conflicts.draw(f, chunks[0])?;
+
+            chunks[1]
+        };
+
         let left_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints(
@@ -90,23 +142,32 @@ impl DrawableComponent for Status {
                 }
                 .as_ref(),
             )
-            .split(chunks[0]);
+            .split(left);
 
         self.index_wd.draw(f, left_chunks[0])?;
         self.index.draw(f, left_chunks[1])?;
-        self.diff.draw(f, chunks[1])?;
+
+        if self.diff_target == DiffTarget::Conflicts {
+            self.conflict_view.draw(f, chunks[1])?;
+        } else {
+            self.diff.draw(f, chunks[1])?;
+        }
 
         Ok(())
     }
 }
 
 impl Status {
-    accessors!(self, [index, index_wd, diff]);
+    accessors!(
+        self,
+        [index, index_wd, conflicts, diff, conflict_view]
+    );
 
     ///
     pub fn new(
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
+        options: SharedOptions,
         theme: SharedTheme,
     ) -> Self {
         Self {
@@ -128,18 +189,72 @@ impl Status {
                 queue.clone(),
                 theme.clone(),
             ),
-            diff: DiffComponent::new(Some(queue.clone()), theme),
+            conflicts: ConflictsComponent::new(
+                queue.clone(),
+                theme.clone(),
+            ),
+            diff: DiffComponent::new(
+                queue.clone(),
+                options,
+                theme.clone(),
+                false,
+            ),
+            conflict_view: ConflictViewComponent::new(
+                queue.clone(),
+                theme.clone(),
+            ),
             git_diff: AsyncDiff::new(sender.clone()),
             git_status_workdir: AsyncStatus::new(sender.clone()),
             git_status_stage: AsyncStatus::new(sender.clone()),
+            repo_state: RepoState::Clean,
+            rebase: None,
+            conflict_count: 0,
+            theme,
         }
     }
 
+    /// e.g. "merging: 2 conflicted file(s) - resolve and stage them"
+    fn draw_banner<B: tui::backend::Backend>(
+        &self,
+        f: &mut tui::Frame<B>,
+        r: Rect,
+    ) {
+        let state = self.rebase.as_ref().map_or_else(
+            || String::from(repo_state(self.repo_state)),
+            rebase_state,
+        );
+
+        let (txt, style) = if self.conflict_count > 0 {
+            (
+                format!(
+                    " {}: {} {}",
+                    state,
+                    self.conflict_count,
+                    strings::BANNER_CONFLICTS
+                ),
+                self.theme.text_danger(),
+            )
+        } else {
+            (
+                format!(" {}: {}", state, strings::BANNER_RESOLVED),
+                self.theme.option(true),
+            )
+        };
+
+        f.render_widget(
+            Paragraph::new(
+                [Text::Styled(Cow::from(txt), style)].iter(),
+            ),
+            r,
+        );
+    }
+
     fn can_focus_diff(&self) -> bool {
         match self.focus {
             Focus::WorkDir => self.index_wd.is_file_seleted(),
             Focus::Stage => self.index.is_file_seleted(),
-            _ => false,
+            Focus::Conflicts => self.conflicts.is_file_seleted(),
+            Focus::Diff => false,
         }
     }
 
@@ -156,11 +271,20 @@ impl Status {
                     self.set_diff_target(DiffTarget::Stage);
                     self.diff.focus(false);
                 }
+                Focus::Conflicts => {
+                    self.set_diff_target(DiffTarget::Conflicts);
+                    self.conflict_view.focus(false);
+                }
                 Focus::Diff => {
                     self.index.focus(false);
                     self.index_wd.focus(false);
+                    self.conflicts.focus(false);
 
-                    self.diff.focus(true);
+                    if self.diff_target == DiffTarget::Conflicts {
+                        self.conflict_view.focus(true);
+                    } else {
+                        self.diff.focus(true);
+                    }
                 }
             };
 
@@ -174,19 +298,24 @@ impl Status {
 
     fn set_diff_target(&mut self, target: DiffTarget) {
         self.diff_target = target;
-        let is_stage = self.diff_target == DiffTarget::Stage;
 
-        self.index_wd.focus_select(!is_stage);
-        self.index.focus_select(is_stage);
+        self.index_wd.focus_select(target == DiffTarget::WorkingDir);
+        self.index.focus_select(target == DiffTarget::Stage);
+        self.conflicts.focus_select(target == DiffTarget::Conflicts);
     }
 
     pub fn selected_path(&self) -> Option<(String, bool)> {
-        let (idx, is_stage) = match self.diff_target {
-            DiffTarget::Stage => (&self.index, true),
-            DiffTarget::WorkingDir => (&self.index_wd, false),
+        let (item, is_stage) = match self.diff_target {
+            DiffTarget::Stage => (self.index.selection(), true),
+            DiffTarget::WorkingDir => {
+                (self.index_wd.selection(), false)
+            }
+            DiffTarget::Conflicts => {
+                (self.conflicts.selection(), false)
+            }
         };
 
-        if let Some(item) = idx.selection() {
+        if let Some(item) = item {
             if let FileTreeItemKind::File(i) = item.kind {
                 return Some((i.path, is_stage));
             }
@@ -231,11 +360,38 @@ impl Status {
     }
 
     fn update_status(&mut self) -> Result<()> {
-        let status = self.git_status_stage.last()?;
-        self.index.update(&status.items)?;
-
-        let status = self.git_status_workdir.last()?;
-        self.index_wd.update(&status.items)?;
+        self.repo_state =
+            sync::repo_state(CWD).unwrap_or(RepoState::Clean);
+        self.rebase = sync::get_rebase_status(CWD).unwrap_or(None);
+
+        // conflicted files show up in both lists, list them separately
+        let (conflicts, workdir): (Vec<StatusItem>, Vec<StatusItem>) =
+            self.git_status_workdir
+                .last()?
+                .items
+                .into_iter()
+                .partition(|item| {
+                    item.status == StatusItemType::Conflicted
+                });
+
+        let stage: Vec<StatusItem> = self
+            .git_status_stage
+            .last()?
+            .items
+            .into_iter()
+            .filter(|item| item.status != StatusItemType::Conflicted)
+            .collect();
+
+        self.conflict_count = conflicts.len();
+        self.conflicts.update(&conflicts)?;
+        self.index.update(&stage)?;
+        self.index_wd.update(&workdir)?;
+
+        if self.conflicts.is_empty()
+            && self.diff_target == DiffTarget::Conflicts
+        {
+            self.switch_focus(Focus::WorkDir)?;
+        }
 
         self.update_diff()?;
 
@@ -244,6 +400,20 @@ impl Status {
 
     ///
     pub fn update_diff(&mut self) -> Result<()> {
+        if self.diff_target == DiffTarget::Conflicts {
+            match self.selected_path() {
+                Some((path, _)) => {
+                    if let Err(e) = self.conflict_view.update(path) {
+                        log::error!("conflict view error: {}", e);
+                        self.conflict_view.clear();
+                    }
+                }
+                None => self.conflict_view.clear(),
+            }
+
+            return Ok(());
+        }
+
         if let Some((path, is_stage)) = self.selected_path() {
             let diff_type = if is_stage {
                 DiffType::Stage
@@ -254,6 +424,7 @@ impl Status {
             let diff_params = DiffParams {
                 path: path.clone(),
                 diff_type,
+                options: self.diff.diff_options_for(&path),
             };
 
             if self.diff.current() == (path.clone(), is_stage) {
@@ -262,6 +433,9 @@ impl Status {
                 if let Some((params, last)) = self.git_diff.last()? {
                     if params == diff_params {
                         self.diff.update(path, is_stage, last)?;
+                    } else {
+                        // e.g. the diff options changed
+                        self.git_diff.request(diff_params)?;
                     }
                 }
             } else {
@@ -281,6 +455,91 @@ impl Status {
         Ok(())
     }
 
+    fn continue_rebase(&self) -> bool {
+        let msg = match sync::rebase_continue(CWD) {
+            Ok(RebaseOutcome::Finished) => None,
+            Ok(RebaseOutcome::Edit(_)) => {
+                Some(String::from(strings::REBASE_EDIT_MSG))
+            }
+            Ok(RebaseOutcome::Conflicts) => {
+                Some(String::from(strings::REBASE_CONFLICTS_MSG))
+            }
+            Err(e) => Some(format!("rebase error:\n{}", e)),
+        };
+
+        let mut queue = self.queue.borrow_mut();
+        if let Some(msg) = msg {
+            queue.push_back(InternalEvent::ShowErrorMsg(msg));
+        }
+        queue.push_back(InternalEvent::Update(NeedsUpdate::ALL));
+
+        true
+    }
+
+    /// concludes a merge, cherry-pick or revert by committing it
+    fn continue_merge(&self) -> bool {
+        self.queue.borrow_mut().push_back(
+            if self.conflict_count > 0 {
+                InternalEvent::ShowErrorMsg(String::from(
+                    strings::MERGE_CONFLICTS_LEFT_MSG,
+                ))
+            } else {
+                InternalEvent::OpenCommit
+            },
+        );
+
+        true
+    }
+
+    fn merging(&self) -> bool {
+        matches!(
+            self.repo_state,
+            RepoState::Merge
+                | RepoState::CherryPick
+                | RepoState::Revert
+        )
+    }
+
+    fn operation_commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) {
+        let rebasing = self.rebase.is_some();
+        out.push(CommandInfo::new(
+            commands::REBASE_CONTINUE,
+            self.rebase.map_or(false, |r| {
+                r.stop != Some(RebaseStop::Conflicts)
+            }),
+            (self.visible && rebasing) || force_all,
+        ));
+        out.push(CommandInfo::new(
+            commands::REBASE_ABORT,
+            true,
+            (self.visible && rebasing) || force_all,
+        ));
+
+        let merging = self.merging();
+        out.push(CommandInfo::new(
+            commands::MERGE_CONTINUE,
+            self.conflict_count == 0,
+            (self.visible && merging) || force_all,
+        ));
+        out.push(CommandInfo::new(
+            commands::MERGE_ABORT,
+            true,
+            (self.visible && merging) || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::CONFLICT_VIEW,
+            true,
+            (self.visible
+                && self.diff_target == DiffTarget::Conflicts)
+                || force_all,
+        ));
+    }
+
     /// called after confirmation
     pub fn reset(&mut self, item: &ResetItem) -> bool {
         if let Err(e) = sync::reset_workdir(CWD, item.path.as_str()) {
@@ -326,6 +585,8 @@ impl Component for Status {
             ));
         }
 
+        self.operation_commands(out, force_all);
+
         out.push(
             CommandInfo::new(
                 commands::SELECT_STATUS,
@@ -380,6 +641,7 @@ impl Component for Status {
                         self.switch_focus(match self.diff_target {
                             DiffTarget::Stage => Focus::Stage,
                             DiffTarget::WorkingDir => Focus::WorkDir,
+                            DiffTarget::Conflicts => Focus::Conflicts,
                         })
                     }
                     keys::MOVE_DOWN
@@ -388,7 +650,49 @@ impl Component for Status {
                     {
                         self.switch_focus(Focus::Stage)
                     }
+                    keys::MOVE_DOWN
+                        if self.focus == Focus::Conflicts =>
+                    {
+                        self.switch_focus(Focus::WorkDir)
+                    }
+                    keys::MOVE_UP
+                        if self.focus == Focus::WorkDir
+                            && !self.conflicts.is_empty() =>
+                    {
+                        self.switch_focus(Focus::Conflicts)
+                    }
+                    keys::CONFLICT_VIEW
+                        if self.diff_target
+                            == DiffTarget::Conflicts =>
+                    {
+                        self.conflict_view.cycle_view()?;
+                        Ok(true)
+                    }
 
+                    keys::REBASE_CONTINUE
+                        if self.rebase.is_some() =>
+                    {
+                        Ok(self.continue_rebase())
+                    }
+                    keys::REBASE_ABORT if self.rebase.is_some() => {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ConfirmAction(
+                                Action::RebaseAbort,
+                            ),
+                        );
+                        Ok(true)
+                    }
+                    keys::MERGE_CONTINUE if self.merging() => {
+                        Ok(self.continue_merge())
+                    }
+                    keys::MERGE_ABORT if self.merging() => {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ConfirmAction(
+                                Action::MergeAbort,
+                            ),
+                        );
+                        Ok(true)
+                    }
                     keys::MOVE_UP
                         if self.focus == Focus::Stage
                             && !self.index_wd.is_empty() =>
@@ -418,3 +722,34 @@ impl Component for Status {
         Ok(())
     }
 }
+
+fn repo_state(state: RepoState) -> &'static str {
+    match state {
+        RepoState::Merge => strings::REPO_STATE_MERGE,
+        RepoState::CherryPick => strings::REPO_STATE_CHERRY_PICK,
+        RepoState::Revert => strings::REPO_STATE_REVERT,
+        RepoState::Rebase => strings::REBASE_STATE,
+        RepoState::Other => strings::REPO_STATE_OTHER,
+        RepoState::Clean => "",
+    }
+}
+
+/// e.g. "rebasing 2/5: stopped to edit"
+fn rebase_state(status: &RebaseStatus) -> String {
+    let stop = match status.stop {
+        Some(RebaseStop::Edit) => strings::REBASE_STATE_EDIT,
+        Some(RebaseStop::Conflicts) => {
+            strings::REBASE_STATE_CONFLICTS
+        }
+        None => "",
+    };
+
+    format!(
+        "{} {}/{}{}{}",
+        strings::REBASE_STATE,
+        status.step,
+        status.total,
+        if stop.is_empty() { "" } else { ": " },
+        stop
+    )
+}
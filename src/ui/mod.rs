@@ -1,5 +1,6 @@
 mod scrolllist;
 pub mod style;
+pub mod syntax;
 use scrolllist::ScrollableList;
 use style::SharedTheme;
 use tui::{
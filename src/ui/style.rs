@@ -1,6 +1,6 @@
 use crate::get_app_config_path;
 use anyhow::Result;
-use asyncgit::{DiffLineType, StatusItemType};
+use asyncgit::{sync::RefKind, DiffLineType, StatusItemType};
 use ron::{
     de::from_bytes,
     ser::{to_string_pretty, PrettyConfig},
@@ -17,6 +17,7 @@ use tui::style::{Color, Modifier, Style};
 pub type SharedTheme = Rc<Theme>;
 
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
 pub struct Theme {
     #[serde(with = "ColorDef")]
     selected_tab: Color,
@@ -33,6 +34,14 @@ pub struct Theme {
     #[serde(with = "ColorDef")]
     diff_line_delete: Color,
     #[serde(with = "ColorDef")]
+    diff_line_add_emphasis: Color,
+    #[serde(with = "ColorDef")]
+    diff_line_delete_emphasis: Color,
+    #[serde(with = "ColorDef")]
+    diff_line_add_bg: Color,
+    #[serde(with = "ColorDef")]
+    diff_line_delete_bg: Color,
+    #[serde(with = "ColorDef")]
     diff_file_added: Color,
     #[serde(with = "ColorDef")]
     diff_file_removed: Color,
@@ -48,6 +57,14 @@ pub struct Theme {
     commit_author: Color,
     #[serde(with = "ColorDef")]
     danger_fg: Color,
+    #[serde(with = "ColorDef")]
+    ref_head: Color,
+    #[serde(with = "ColorDef")]
+    ref_local_branch: Color,
+    #[serde(with = "ColorDef")]
+    ref_remote_branch: Color,
+    #[serde(with = "ColorDef")]
+    ref_tag: Color,
 }
 
 impl Theme {
@@ -75,15 +92,16 @@ impl Theme {
         }
     }
 
-    pub fn tags(&self, selected: bool) -> Style {
-        Style::default()
-            .fg(self.selected_tab)
-            .modifier(Modifier::BOLD)
-            .bg(if selected {
-                self.selection_bg
-            } else {
-                Color::Reset
-            })
+    pub fn branch(&self, selected: bool, head: bool) -> Style {
+        let style = if head {
+            Style::default()
+                .fg(self.selected_tab)
+                .modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+
+        self.apply_select(style, selected)
     }
 
     pub fn text(&self, enabled: bool, selected: bool) -> Style {
@@ -110,6 +128,9 @@ impl Theme {
             StatusItemType::Renamed => {
                 Style::default().fg(self.diff_file_moved)
             }
+            StatusItemType::Conflicted => {
+                Style::default().fg(self.danger_fg)
+            }
             _ => Style::default(),
         };
 
@@ -165,6 +186,60 @@ impl Theme {
         self.apply_select(style, selected)
     }
 
+    /// changed part of a modified line
+    pub fn diff_line_emphasis(
+        &self,
+        typ: DiffLineType,
+        selected: bool,
+    ) -> Style {
+        let color = match typ {
+            DiffLineType::Delete => self.diff_line_delete_emphasis,
+            _ => self.diff_line_add_emphasis,
+        };
+
+        self.apply_select(
+            Style::default().fg(color).modifier(Modifier::REVERSED),
+            selected,
+        )
+    }
+
+    /// part of a diff line, `syntax` is its highlighted color: then
+    /// adds and deletes are told apart by their background
+    pub fn diff_line_part(
+        &self,
+        typ: DiffLineType,
+        selected: bool,
+        emphasis: bool,
+        syntax: Option<Color>,
+    ) -> Style {
+        let fg = match syntax {
+            Some(fg) => fg,
+            None if emphasis => {
+                return self.diff_line_emphasis(typ, selected)
+            }
+            None => return self.diff_line(typ, selected),
+        };
+
+        let style = match typ {
+            DiffLineType::Add => {
+                Style::default().bg(self.diff_line_add_bg)
+            }
+            DiffLineType::Delete => {
+                Style::default().bg(self.diff_line_delete_bg)
+            }
+            _ => Style::default(),
+        }
+        .fg(fg);
+
+        let style = if emphasis {
+            style.modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            style
+        };
+
+        self.apply_select(style, selected)
+    }
+
     pub fn text_danger(&self) -> Style {
         Style::default().fg(self.danger_fg)
     }
@@ -201,6 +276,65 @@ impl Theme {
         )
     }
 
+    pub fn commit_ref(&self, kind: RefKind, selected: bool) -> Style {
+        let color = match kind {
+            RefKind::Head => self.ref_head,
+            RefKind::LocalBranch => self.ref_local_branch,
+            RefKind::RemoteBranch => self.ref_remote_branch,
+            RefKind::Tag => self.ref_tag,
+        };
+
+        self.apply_select(
+            Style::default().fg(color).modifier(Modifier::BOLD),
+            selected,
+        )
+    }
+
+    pub fn commit_graph(&self, lane: usize, selected: bool) -> Style {
+        const LANE_COLORS: [Color; 6] = [
+            Color::LightBlue,
+            Color::LightGreen,
+            Color::LightYellow,
+            Color::LightMagenta,
+            Color::LightCyan,
+            Color::LightRed,
+        ];
+
+        self.apply_select(
+            Style::default()
+                .fg(LANE_COLORS[lane % LANE_COLORS.len()]),
+            selected,
+        )
+    }
+
+    /// `age` from 0 (newest) to 1 (oldest) line of a blame, `None`
+    /// for lines not committed yet
+    pub fn blame_age(
+        &self,
+        age: Option<f64>,
+        selected: bool,
+    ) -> Style {
+        const AGE_COLORS: [Color; 5] = [
+            Color::LightRed,
+            Color::LightYellow,
+            Color::LightGreen,
+            Color::LightCyan,
+            Color::Blue,
+        ];
+
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let color = age.map_or(self.diff_line_add, |age| {
+            let idx = (age * AGE_COLORS.len() as f64) as usize;
+            AGE_COLORS[idx.min(AGE_COLORS.len() - 1)]
+        });
+
+        self.apply_select(Style::default().fg(color), selected)
+    }
+
     fn save(&self) -> Result<()> {
         let theme_file = Self::get_theme_file()?;
         let mut file = File::create(theme_file)?;
@@ -249,6 +383,10 @@ impl Default for Theme {
             disabled_fg: Color::DarkGray,
             diff_line_add: Color::Green,
             diff_line_delete: Color::Red,
+            diff_line_add_emphasis: Color::LightGreen,
+            diff_line_delete_emphasis: Color::LightRed,
+            diff_line_add_bg: Color::Rgb(0, 64, 0),
+            diff_line_delete_bg: Color::Rgb(72, 0, 0),
             diff_file_added: Color::LightGreen,
             diff_file_removed: Color::LightRed,
             diff_file_moved: Color::LightMagenta,
@@ -257,6 +395,10 @@ impl Default for Theme {
             commit_time: Color::LightCyan,
             commit_author: Color::Green,
             danger_fg: Color::Red,
+            ref_head: Color::Cyan,
+            ref_local_branch: Color::LightGreen,
+            ref_remote_branch: Color::LightRed,
+            ref_tag: Color::Yellow,
         }
     }
 }
@@ -0,0 +1,151 @@
+//! language-aware highlighting of file content (only with the
+//! `syntax-highlighting` feature, otherwise nothing is highlighted)
+
+use std::ops::Range;
+use tui::style::Color;
+
+/// foreground colors of byte ranges of a line
+pub type HighlightedLine = Vec<(Range<usize>, Color)>;
+
+#[cfg(feature = "syntax-highlighting")]
+mod highlighter {
+    use super::HighlightedLine;
+    use std::path::Path;
+    use syntect::{
+        easy::HighlightLines,
+        highlighting::{Theme, ThemeSet},
+        parsing::SyntaxSet,
+    };
+    use tui::style::Color;
+
+    const THEME: &str = "base16-eighties.dark";
+
+    struct Syntax {
+        syntaxes: SyntaxSet,
+        theme: Theme,
+    }
+
+    thread_local! {
+        // loading the definitions is expensive, do it once
+        static SYNTAX: Syntax = Syntax {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            theme: ThemeSet::load_defaults()
+                .themes
+                .remove(THEME)
+                .unwrap_or_default(),
+        };
+    }
+
+    pub fn highlight<'a>(
+        path: &str,
+        lines: impl Iterator<Item = &'a str>,
+    ) -> Vec<HighlightedLine> {
+        let extension = match Path::new(path).extension() {
+            Some(extension) => extension.to_string_lossy(),
+            None => return Vec::new(),
+        };
+
+        SYNTAX.with(|syntax| {
+            let reference = match syntax
+                .syntaxes
+                .find_syntax_by_extension(&extension)
+            {
+                Some(reference) => reference,
+                None => return Vec::new(),
+            };
+
+            let mut highlighter =
+                HighlightLines::new(reference, &syntax.theme);
+
+            lines
+                .map(|line| {
+                    // the syntax definitions expect a newline
+                    let line = if line.ends_with('\n') {
+                        line.to_string()
+                    } else {
+                        format!("{}\n", line)
+                    };
+
+                    let mut start = 0;
+                    highlighter
+                        .highlight_line(&line, &syntax.syntaxes)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|(style, text)| {
+                            let range = start..start + text.len();
+                            start = range.end;
+                            let fg = style.foreground;
+                            (range, Color::Rgb(fg.r, fg.g, fg.b))
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+    }
+}
+
+/// highlights consecutive `lines` of the file at `path` in the
+/// language its extension suggests, empty if there is none
+#[cfg(feature = "syntax-highlighting")]
+pub fn highlight<'a>(
+    path: &str,
+    lines: impl Iterator<Item = &'a str>,
+) -> Vec<HighlightedLine> {
+    highlighter::highlight(path, lines)
+}
+
+/// highlights consecutive `lines` of the file at `path` in the
+/// language its extension suggests, empty if there is none
+#[cfg(not(feature = "syntax-highlighting"))]
+pub fn highlight<'a>(
+    _path: &str,
+    _lines: impl Iterator<Item = &'a str>,
+) -> Vec<HighlightedLine> {
+    Vec::new()
+}
+
+/// splits `len` bytes at the ends of `ranges` and the `highlighted`
+/// spans: each segment with whether it is in one of `ranges` and its
+/// highlighted color
+pub fn segments(
+    len: usize,
+    ranges: &[Range<usize>],
+    highlighted: Option<&HighlightedLine>,
+) -> Vec<(Range<usize>, bool, Option<Color>)> {
+    let mut bounds: Vec<usize> = ranges
+        .iter()
+        .flat_map(|r| vec![r.start, r.end])
+        .chain(
+            highlighted
+                .into_iter()
+                .flatten()
+                .flat_map(|(r, _)| vec![r.start, r.end]),
+        )
+        .filter(|b| *b < len)
+        .chain(vec![0, len])
+        .collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut segments: Vec<(Range<usize>, bool, Option<Color>)> =
+        Vec::new();
+    for w in bounds.windows(2) {
+        let start = w[0];
+        let in_range = ranges.iter().any(|r| r.contains(&start));
+        let color = highlighted.and_then(|spans| {
+            spans
+                .iter()
+                .find(|(r, _)| r.contains(&start))
+                .map(|(_, color)| *color)
+        });
+
+        match segments.last_mut() {
+            Some(last) if last.1 == in_range && last.2 == color => {
+                last.0.end = w[1]
+            }
+            _ => segments.push((start..w[1], in_range, color)),
+        }
+    }
+
+    segments
+}